@@ -0,0 +1,274 @@
+//! Compressed snapshots of the task list.
+//!
+//! `backup now` stores the tasks and daily plans as a gzipped JSON snapshot in the backups
+//! directory, named after the time it was taken, e.g. `20240701T123000Z.json.gz`. Snapshots are
+//! independent of the store kind, so a snapshot of a JSON store can be restored into a CRDT store
+//! and the other way around. After every snapshot, old ones are pruned according to the
+//! retention policy in the `backup` section of `config.json`. With an `encryption_key`,
+//! snapshots are encrypted with it like the store, as the backups directory is usually synced
+//! along with it.
+
+use crate::{crypto, DailyPlans, Task};
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const NAME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const EXTENSION: &str = ".json.gz";
+
+/// `BackupConfig`: The `backup` section of `config.json`.
+///
+/// For example `{ "dir": "backups", "keep_last": 10, "keep_daily": 7, "keep_weekly": 4 }` keeps
+/// the 10 newest snapshots, plus the newest snapshot of each of the last 7 days and of each of
+/// the last 4 weeks that have one.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    pub dir: String,
+    pub keep_last: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            dir: String::from("backups"),
+            keep_last: 10,
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+/// `Snapshot`: The contents of a backup.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub plans: DailyPlans,
+}
+
+/// Takes a snapshot, encrypted given a key, then prunes old snapshots. Returns the name of the
+/// new snapshot.
+pub fn create(
+    config: &BackupConfig,
+    tasks: &[Task],
+    plans: &DailyPlans,
+    now: DateTime<Utc>,
+    key: Option<&crypto::Key>,
+) -> Result<String, Box<dyn Error>> {
+    fs::create_dir_all(&config.dir)?;
+    let name = now.format(NAME_FORMAT).to_string();
+    write(&path(config, &name), &SnapshotRef { tasks, plans }, key)?;
+
+    let snapshots = list(config)?;
+    let keep = retained(&snapshots, config);
+    for taken_at in snapshots.iter().filter(|taken_at| !keep.contains(taken_at)) {
+        fs::remove_file(path(config, &taken_at.format(NAME_FORMAT).to_string()))?;
+    }
+    Ok(name)
+}
+
+/// Lists when the snapshots in the backups directory were taken, newest first.
+pub fn list(config: &BackupConfig) -> Result<Vec<DateTime<Utc>>, Box<dyn Error>> {
+    if !Path::new(&config.dir).exists() {
+        return Ok(vec![]);
+    }
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&config.dir)? {
+        let file_name = entry?.file_name().to_string_lossy().into_owned();
+        if let Some(taken_at) = file_name.strip_suffix(EXTENSION).and_then(parse_name) {
+            snapshots.push(taken_at);
+        }
+    }
+    snapshots.sort_by(|a, b| b.cmp(a));
+    Ok(snapshots)
+}
+
+/// Reads the snapshot with the given name, as shown by `backup list`.
+pub fn read(
+    config: &BackupConfig,
+    name: &str,
+    key: Option<&crypto::Key>,
+) -> Result<Snapshot, Box<dyn Error>> {
+    let name = name.trim_end_matches(EXTENSION);
+    parse_name(name).ok_or("invalid snapshot name")?;
+    let data = crypto::decrypt(fs::read(path(config, name))?, key)?;
+    Ok(serde_json::from_reader(GzDecoder::new(&data[..]))?)
+}
+
+/// Encrypts the snapshots taken before the key was set, for `key encrypt`.
+pub fn encrypt(config: &BackupConfig, key: &crypto::Key) -> Result<(), Box<dyn Error>> {
+    for taken_at in list(config)? {
+        let name = taken_at.format(NAME_FORMAT).to_string();
+        let snapshot = read(config, &name, Some(key))?;
+        let snapshot = SnapshotRef {
+            tasks: &snapshot.tasks,
+            plans: &snapshot.plans,
+        };
+        write(&path(config, &name), &snapshot, Some(key))?;
+    }
+    Ok(())
+}
+
+/// Writes a snapshot gzipped and, given a key, encrypted.
+fn write(
+    path: &Path,
+    snapshot: &SnapshotRef,
+    key: Option<&crypto::Key>,
+) -> Result<(), Box<dyn Error>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, snapshot)?;
+    let compressed = encoder.finish()?;
+    fs::write(
+        path,
+        match key {
+            Some(key) => crypto::encrypt(&compressed, key),
+            None => compressed,
+        },
+    )?;
+    Ok(())
+}
+
+/// Decides which snapshots the retention policy keeps: the `keep_last` newest ones, at least one,
+/// and the newest of each of the last `keep_daily` days and `keep_weekly` weeks that have
+/// snapshots.
+fn retained(snapshots: &[DateTime<Utc>], config: &BackupConfig) -> Vec<DateTime<Utc>> {
+    let mut newest_first = snapshots.to_vec();
+    newest_first.sort_by(|a, b| b.cmp(a));
+
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut keep = Vec::new();
+    for (index, taken_at) in newest_first.into_iter().enumerate() {
+        let day = taken_at.date_naive();
+        let week = day.iso_week();
+        let new_day = days.len() < config.keep_daily && days.insert(day);
+        let new_week = weeks.len() < config.keep_weekly && weeks.insert(week);
+        if index < config.keep_last.max(1) || new_day || new_week {
+            keep.push(taken_at);
+        }
+    }
+    keep
+}
+
+/// Borrowed form of `Snapshot`, to write one without cloning the tasks.
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    tasks: &'a [Task],
+    plans: &'a DailyPlans,
+}
+
+fn path(config: &BackupConfig, name: &str) -> PathBuf {
+    Path::new(&config.dir).join(format!("{}{}", name, EXTENSION))
+}
+
+fn parse_name(name: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(name, NAME_FORMAT)
+        .ok()
+        .map(|taken_at| taken_at.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDate};
+    use std::env;
+    use std::io::Read;
+    use std::process;
+
+    #[test]
+    fn test_encrypted_snapshots() {
+        let dir = env::temp_dir().join(format!("backups-{}", process::id()));
+        let config = BackupConfig {
+            dir: dir.display().to_string(),
+            ..Default::default()
+        };
+        let tasks = vec![Task {
+            title: String::from("Renew passport"),
+            status: String::from("Todo"),
+            ..Default::default()
+        }];
+        let plans = DailyPlans::default();
+        let key = crypto::Key::generate();
+        let taken_at = |minute: u32| {
+            NaiveDate::from_ymd_opt(2024, 7, 1)
+                .unwrap()
+                .and_hms_opt(12, minute, 0)
+                .unwrap()
+                .and_utc()
+        };
+
+        let name = create(&config, &tasks, &plans, taken_at(0), Some(&key)).unwrap();
+        let data = fs::read(path(&config, &name)).unwrap();
+        // Not even gzipped text is left to read
+        assert!(GzDecoder::new(&data[..])
+            .read_to_end(&mut Vec::new())
+            .is_err());
+        let snapshot = read(&config, &name, Some(&key)).unwrap();
+        assert_eq!(snapshot.tasks, tasks);
+        assert!(read(&config, &name, None).is_err());
+        assert!(read(&config, &name, Some(&crypto::Key::generate())).is_err());
+
+        // Snapshots taken before the key was set are encrypted once
+        let plain = create(&config, &tasks, &plans, taken_at(1), None).unwrap();
+        assert_eq!(read(&config, &plain, None).unwrap().tasks, tasks);
+        crypto::accept_plaintext();
+        encrypt(&config, &key).unwrap();
+        assert!(read(&config, &plain, None).is_err());
+        assert_eq!(read(&config, &plain, Some(&key)).unwrap().tasks, tasks);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_retained() {
+        // Monday 1 July 2024 at noon and in the morning, then one snapshot a day going back
+        let noon = NaiveDate::from_ymd_opt(2024, 7, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc();
+        let mut snapshots = vec![noon, noon - Duration::hours(3)];
+        snapshots.extend((1..=20).map(|days| noon - Duration::days(days)));
+        let days_ago = |days: Vec<i64>| -> Vec<DateTime<Utc>> {
+            days.into_iter().map(|d| noon - Duration::days(d)).collect()
+        };
+
+        let daily = BackupConfig {
+            keep_last: 1,
+            keep_daily: 3,
+            keep_weekly: 0,
+            ..Default::default()
+        };
+        assert_eq!(retained(&snapshots, &daily), days_ago(vec![0, 1, 2]));
+
+        // The newest snapshot of this week, last week (Sunday 30 June) and the week before
+        let weekly = BackupConfig {
+            keep_last: 1,
+            keep_daily: 1,
+            keep_weekly: 3,
+            ..Default::default()
+        };
+        assert_eq!(retained(&snapshots, &weekly), days_ago(vec![0, 1, 8]));
+
+        // Recent snapshots from the same day are kept too
+        let recent = BackupConfig {
+            keep_last: 2,
+            keep_daily: 0,
+            keep_weekly: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            retained(&snapshots, &recent),
+            vec![noon, noon - Duration::hours(3)]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Measures loading, saving, searching and filtering stores of 1k, 10k and 100k tasks with each
+//! store kind, by running the task manager on generated stores.
+//!
+//! Run with `cargo bench --bench commands -- --save-baseline main` before a change and
+//! `cargo bench --bench commands -- --baseline main` after it to see what got slower. To find
+//! out where the time goes, run a single command with `--timings`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The stores to measure: a name, the store kind and the `config.json` selecting it.
+const STORES: [(&str, &str, &str); 4] = [
+    ("json", "json", r#"{ "store": "json" }"#),
+    (
+        "json-zstd",
+        "json",
+        r#"{ "store": "json", "compression": "zstd" }"#,
+    ),
+    ("binary", "binary", r#"{ "store": "binary" }"#),
+    ("ndjson", "ndjson", r#"{ "store": "ndjson" }"#),
+];
+
+/// The commands to measure. Every command loads the store; `save` also writes it back.
+const COMMANDS: [(&str, &[&str]); 4] = [
+    ("load", &["list-by-status", "--status", "Missing"]),
+    ("save", &["update", "Task 0", "--priority", "2"]),
+    ("search", &["search", "task 99"]),
+    ("filter", &["list-by-project", "--project", "Project 3"]),
+];
+
+/// Creates a directory with a store of `count` tasks of the given kind.
+fn store(count: usize, name: &str, kind: &str, config: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "task-manager-bench-{}-{}-{}",
+        std::process::id(),
+        name,
+        count
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let tasks: Vec<_> = (0..count)
+        .map(|i| {
+            let status = ["Todo", "In Progress", "Done"][i % 3];
+            json!({
+                "title": format!("Task {}", i),
+                "description": format!("Notes for task {}", i),
+                "priority": i % 5 + 1,
+                "status": status,
+                "project": format!("Project {}", i % 10),
+                "tags": ["work"],
+            })
+        })
+        .collect();
+    fs::write(dir.join("tasks.json"), serde_json::to_vec(&tasks).unwrap()).unwrap();
+    run(&dir, &["convert", kind]);
+    fs::write(dir.join("config.json"), config).unwrap();
+    if kind == "json" {
+        // Saves the store again with the configured compression
+        run(&dir, &["convert", kind]);
+    }
+    dir
+}
+
+fn run(dir: &Path, args: &[&str]) {
+    let status = Command::new(env!("CARGO_BIN_EXE_task_manager_app"))
+        .args(args)
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn bench_commands(c: &mut Criterion) {
+    for count in [1_000, 10_000, 100_000] {
+        let dirs: Vec<_> = STORES
+            .iter()
+            .map(|(name, kind, config)| (name, store(count, name, kind, config)))
+            .collect();
+        for (command, args) in COMMANDS {
+            let mut group = c.benchmark_group(format!("{} {} tasks", command, count));
+            group.sample_size(10);
+            for (name, dir) in &dirs {
+                group.bench_function(BenchmarkId::from_parameter(name), |b| {
+                    b.iter(|| run(dir, args))
+                });
+            }
+            group.finish();
+        }
+        for (_, dir) in dirs {
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
+
+criterion_group!(benches, bench_commands);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+//! Measures what compressing the JSON store costs when saving and loading it.
+//!
+//! Run with `cargo bench --bench store`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::{json, Value};
+
+#[path = "../compress.rs"]
+#[allow(dead_code, unused_imports)]
+mod compress;
+
+use compress::Compression;
+
+/// A store of `count` tasks with descriptions of a few hundred bytes.
+fn store(count: usize) -> Vec<Value> {
+    (0..count)
+        .map(|i| {
+            let status = ["Todo", "In Progress", "Done"][i % 3];
+            json!({
+                "title": format!("Task {}", i),
+                "description": format!("Notes for task {}: ", i).repeat(20),
+                "priority": i % 5 + 1,
+                "status": status,
+                "project": format!("Project {}", i % 10),
+                "tags": ["work", "review"],
+            })
+        })
+        .collect()
+}
+
+fn bench_store(c: &mut Criterion) {
+    for count in [1_000, 10_000] {
+        let tasks = store(count);
+        let mut save = c.benchmark_group(format!("save {} tasks", count));
+        for compression in Compression::ALL {
+            let name = format!("tasks.json{}", compression.extension());
+            save.bench_function(BenchmarkId::from_parameter(&name), |b| {
+                b.iter(|| {
+                    let serialized = serde_json::to_string_pretty(&tasks).unwrap();
+                    compress::compress(serialized.as_bytes(), compression).unwrap()
+                })
+            });
+        }
+        save.finish();
+
+        let serialized = serde_json::to_string_pretty(&tasks).unwrap();
+        let mut load = c.benchmark_group(format!("load {} tasks", count));
+        for compression in Compression::ALL {
+            let name = format!("tasks.json{}", compression.extension());
+            let stored = compress::compress(serialized.as_bytes(), compression).unwrap();
+            load.bench_function(BenchmarkId::from_parameter(&name), |b| {
+                b.iter(|| {
+                    let contents = compress::decompress(&stored).unwrap();
+                    serde_json::from_slice::<Vec<Value>>(&contents).unwrap()
+                })
+            });
+        }
+        load.finish();
+    }
+}
+
+criterion_group!(benches, bench_store);
+criterion_main!(benches);
@@ -0,0 +1,178 @@
+//! Working days.
+//!
+//! The `calendar` section of `config.json` says which days are worked: by default Monday to
+//! Friday, with the weekend days and holidays given there, or read from an iCalendar (`.ics`)
+//! file such as the public holidays exported from a calendar app. Due dates can then be given in
+//! working days, as in `--due 3bd`, `--due "in 3 business days"` or `due:3bd` in `capture`,
+//! counting from today and skipping weekends and holidays.
+
+use crate::i18n::t;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+
+/// The most working days a due date can be given in, about ten years' worth.
+const MAX_WORKING_DAYS: u32 = 2600;
+
+/// `CalendarConfig`: The `calendar` section of `config.json`.
+///
+/// For example `{ "weekend": ["Fri", "Sat"], "holidays": ["2024-12-25"], "holidays_file":
+/// "holidays.ics" }`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    pub weekend: Vec<Weekday>,
+    pub holidays: Vec<NaiveDate>,
+    /// An iCalendar file whose events are holidays, relative to the data directory.
+    pub holidays_file: Option<String>,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        CalendarConfig {
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: Vec::new(),
+            holidays_file: None,
+        }
+    }
+}
+
+/// `Calendar`: The days that are not worked.
+#[derive(Debug)]
+pub struct Calendar {
+    weekend: Vec<Weekday>,
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar::new(&CalendarConfig::default()).unwrap()
+    }
+}
+
+impl Calendar {
+    /// Builds the calendar, reading the holidays file if there is one. Fails when the file cannot
+    /// be read or no day of the week is worked.
+    pub fn new(config: &CalendarConfig) -> Result<Calendar, String> {
+        if config.weekend.len() >= 7 {
+            return Err(t!("calendar-no-working-days"));
+        }
+        let mut holidays: BTreeSet<NaiveDate> = config.holidays.iter().copied().collect();
+        if let Some(file) = &config.holidays_file {
+            let contents = fs::read_to_string(file).map_err(|err| {
+                t!(
+                    "calendar-file-failed",
+                    file = file.as_str(),
+                    error = err.to_string()
+                )
+            })?;
+            holidays.extend(ics_dates(&contents));
+        }
+        Ok(Calendar {
+            weekend: config.weekend.clone(),
+            holidays,
+        })
+    }
+
+    /// Returns true unless a date falls on the weekend or a holiday.
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// The date a number of working days after another, unless that is past the last date there
+    /// can be.
+    pub fn add_working_days(&self, from: NaiveDate, days: u32) -> Option<NaiveDate> {
+        let mut date = from;
+        for _ in 0..days {
+            date = date.succ_opt()?;
+            while !self.is_working_day(date) {
+                date = date.succ_opt()?;
+            }
+        }
+        Some(date)
+    }
+}
+
+/// Reads a number of working days such as `3bd`, `3 business days` or `in 1 working day`, up to
+/// about ten years' worth.
+pub fn parse_working_days(text: &str) -> Option<u32> {
+    read_working_days(text).filter(|days| *days <= MAX_WORKING_DAYS)
+}
+
+fn read_working_days(text: &str) -> Option<u32> {
+    let text = text.trim().to_lowercase();
+    let text = text.strip_prefix("in ").unwrap_or(&text);
+    if let Some(days) = text.strip_suffix("bd") {
+        return days.trim().parse().ok();
+    }
+    let (days, unit) = text.split_once(' ')?;
+    let unit = unit.trim().strip_suffix('s').unwrap_or(unit.trim());
+    match unit {
+        "business day" | "working day" | "workday" => days.parse().ok(),
+        _ => None,
+    }
+}
+
+/// The dates of the events in an iCalendar file, every day of those lasting several days.
+fn ics_dates(contents: &str) -> Vec<NaiveDate> {
+    // Long lines are folded onto lines starting with a space
+    let contents = contents.replace("\r\n", "\n").replace("\n ", "");
+    let date = |line: &str, name: &str| {
+        let rest = line.strip_prefix(name)?;
+        let (_, value) = rest.split_once(':')?;
+        NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+    };
+    let mut dates = Vec::new();
+    let mut start = None;
+    for line in contents.lines() {
+        if line.starts_with("BEGIN:VEVENT") {
+            start = None;
+        } else if let Some(date) = date(line, "DTSTART") {
+            start = Some((date, None));
+        } else if let Some(end) = date(line, "DTEND") {
+            start = start.map(|(date, _)| (date, Some(end)));
+        } else if line.starts_with("END:VEVENT") {
+            let Some((first, end)) = start.take() else {
+                continue;
+            };
+            // The end date of an all-day event is the day after it
+            let last = end.map_or(first, |end| (end - Duration::days(1)).max(first));
+            dates.extend(first.iter_days().take_while(|date| *date <= last));
+        }
+    }
+    dates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_working_days() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 12, d).unwrap();
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20241225\r\n\
+                   DTEND;VALUE=DATE:20241227\r\nSUMMARY:Christmas\r\nEND:VEVENT\r\n\
+                   BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20241231\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(ics_dates(ics), [day(25), day(26), day(31)]);
+
+        let calendar = Calendar {
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            holidays: ics_dates(ics).into_iter().collect(),
+        };
+        // 2024-12-20 is a Friday
+        assert_eq!(calendar.add_working_days(day(20), 1), Some(day(23)));
+        assert_eq!(calendar.add_working_days(day(20), 3), Some(day(27)));
+        assert_eq!(calendar.add_working_days(day(20), 0), Some(day(20)));
+        assert_eq!(calendar.add_working_days(NaiveDate::MAX, 1), None);
+
+        assert_eq!(parse_working_days("3bd"), Some(3));
+        assert_eq!(parse_working_days("in 3 business days"), Some(3));
+        assert_eq!(parse_working_days("1 Working Day"), Some(1));
+        assert_eq!(parse_working_days("3 days"), None);
+        assert_eq!(parse_working_days("2024-12-20"), None);
+        assert_eq!(parse_working_days("2600bd"), Some(2600));
+        assert_eq!(parse_working_days("100000000bd"), None);
+        assert_eq!(parse_working_days("99999999999bd"), None);
+    }
+}
@@ -0,0 +1,140 @@
+//! Quick capture.
+//!
+//! `capture` adds a task from a single line such as `Fix login bug +Backend @work p:4 due:friday`.
+//! Words with a marker fill in the task's fields:
+//!
+//! - `+Backend` files the task in a project,
+//! - `@work` adds a context, such as the place or tool the task needs,
+//! - `#release` adds a tag,
+//! - `p:4` sets the priority,
+//! - `due:friday` sets the due date: `today`, `tomorrow`, a weekday (the next one after today), a
+//!   number of working days such as `3bd` (see the `calendar` module) or a date such as
+//!   `2024-07-01`.
+//!
+//! All other words make up the title. A backslash keeps a word that looks like a marker in the
+//! title, as in `Reply to \#42`.
+
+use crate::calendar::{self, Calendar};
+use crate::i18n::t;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// `Capture`: The fields of a task read from a capture line.
+#[derive(Debug, Default, PartialEq)]
+pub struct Capture {
+    pub title: String,
+    pub project: Option<String>,
+    pub contexts: Vec<String>,
+    pub tags: Vec<String>,
+    pub priority: Option<u8>,
+    pub due: Option<NaiveDate>,
+}
+
+/// Parses a capture line, reading relative due dates from today.
+pub fn parse(line: &str, today: NaiveDate, calendar: &Calendar) -> Result<Capture, String> {
+    let mut capture = Capture::default();
+    let mut title = Vec::new();
+    for word in line.split_whitespace() {
+        if let Some(literal) = word.strip_prefix('\\') {
+            title.push(literal);
+        } else if let Some(project) = marked(word, "+") {
+            capture.project = Some(project.to_string());
+        } else if let Some(context) = marked(word, "@") {
+            capture.contexts.push(context.to_string());
+        } else if let Some(tag) = marked(word, "#") {
+            capture.tags.push(tag.to_string());
+        } else if let Some(priority) = marked(word, "p:") {
+            let priority = priority
+                .parse()
+                .map_err(|_| t!("capture-invalid-priority", priority = priority))?;
+            capture.priority = Some(priority);
+        } else if let Some(due) = marked(word, "due:") {
+            let date = parse_date(due, today, calendar)
+                .ok_or_else(|| t!("capture-invalid-due", due = due))?;
+            capture.due = Some(date);
+        } else {
+            title.push(word);
+        }
+    }
+    if title.is_empty() {
+        return Err(t!("capture-no-title"));
+    }
+    capture.title = title.join(" ");
+    Ok(capture)
+}
+
+/// The rest of a word after a marker, unless there is nothing after it.
+fn marked<'a>(word: &'a str, marker: &str) -> Option<&'a str> {
+    word.strip_prefix(marker).filter(|rest| !rest.is_empty())
+}
+
+/// Reads a due date given as a date, `today`, `tomorrow`, a weekday or a number of working days.
+fn parse_date(due: &str, today: NaiveDate, calendar: &Calendar) -> Option<NaiveDate> {
+    if let Some(days) = calendar::parse_working_days(due) {
+        return calendar.add_working_days(today, days);
+    }
+    match due.to_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => Some(today + Duration::days(1)),
+        name => match name.parse::<Weekday>() {
+            Ok(weekday) => {
+                let days = (weekday.num_days_from_monday() + 6
+                    - today.weekday().num_days_from_monday())
+                    % 7
+                    + 1;
+                Some(today + Duration::days(days as i64))
+            }
+            Err(_) => NaiveDate::parse_from_str(due, "%Y-%m-%d").ok(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        // 2024-07-05 is a Friday
+        let today = NaiveDate::from_ymd_opt(2024, 7, 3).unwrap();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 7, d);
+        let calendar = Calendar::default();
+        assert_eq!(
+            parse(
+                "Fix login bug +Backend @work p:4 due:friday",
+                today,
+                &calendar
+            )
+            .unwrap(),
+            Capture {
+                title: String::from("Fix login bug"),
+                project: Some(String::from("Backend")),
+                contexts: vec![String::from("work")],
+                priority: Some(4),
+                due: day(5),
+                ..Default::default()
+            }
+        );
+
+        let capture = parse(
+            "#errands Buy milk @store \\#2 + due:2024-07-10",
+            today,
+            &calendar,
+        )
+        .unwrap();
+        assert_eq!(capture.title, "Buy milk #2 +");
+        assert_eq!(capture.tags, ["errands"]);
+        assert_eq!(capture.due, day(10));
+
+        assert_eq!(
+            parse("x due:Tomorrow", today, &calendar).unwrap().due,
+            day(4)
+        );
+        // A weekday is the next one, a week on when it is today
+        assert_eq!(parse("x due:wed", today, &calendar).unwrap().due, day(10));
+        // Working days skip the weekend
+        assert_eq!(parse("x due:3bd", today, &calendar).unwrap().due, day(8));
+        assert!(parse("x due:someday", today, &calendar).is_err());
+        assert!(parse("x p:high", today, &calendar).is_err());
+        assert!(parse("+Backend @work", today, &calendar).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Hygiene checks for the task list.
+//!
+//! `check` works like a linter: it flags tasks that are not wrong as such but probably deserve a
+//! second look, such as work that has been In Progress for weeks. Every rule has a default
+//! severity that can be changed, or set to `off`, in the `check` section of `config.json`.
+
+use crate::i18n::t;
+use crate::{is_done, is_in_progress, Task};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// `Severity`: How serious a finding is. Findings of severity `error` make `check` fail.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+/// `Rule`: One of the hygiene checks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    /// An open task in a deadline-driven project has no due date.
+    NoDueDate,
+    /// A task has been In Progress for longer than `stale_after_days`.
+    StaleInProgress,
+    /// A task has an empty description.
+    EmptyDescription,
+    /// A task depends on a task that no longer exists.
+    MissingDependency,
+}
+
+impl Rule {
+    /// The name of the rule, used in `config.json` and in the output.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::NoDueDate => "no-due-date",
+            Rule::StaleInProgress => "stale-in-progress",
+            Rule::EmptyDescription => "empty-description",
+            Rule::MissingDependency => "missing-dependency",
+        }
+    }
+
+    fn default_severity(self) -> Severity {
+        match self {
+            Rule::EmptyDescription => Severity::Info,
+            Rule::MissingDependency => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// `CheckConfig`: The `check` section of `config.json`.
+///
+/// For example `{ "deadline_projects": ["Release"], "stale_after_days": 7,
+/// "severity": { "empty-description": "off" } }`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CheckConfig {
+    /// Projects whose open tasks are all expected to have a due date.
+    pub deadline_projects: Vec<String>,
+    /// How long a task may stay In Progress before it is reported as stale.
+    pub stale_after_days: i64,
+    /// Severity overrides by rule name.
+    pub severity: HashMap<String, Severity>,
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        CheckConfig {
+            deadline_projects: Vec::new(),
+            stale_after_days: 14,
+            severity: HashMap::new(),
+        }
+    }
+}
+
+impl CheckConfig {
+    fn severity(&self, rule: Rule) -> Severity {
+        self.severity
+            .get(rule.name())
+            .copied()
+            .unwrap_or(rule.default_severity())
+    }
+}
+
+/// `Finding`: A hygiene issue found on a task.
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    pub rule: Rule,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+}
+
+/// Runs all enabled rules over the tasks, returning the findings with the most severe first.
+pub fn check_tasks(tasks: &[Task], config: &CheckConfig, now: DateTime<Utc>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut report = |rule: Rule, task: &Task, message: String| {
+        let severity = config.severity(rule);
+        if severity != Severity::Off {
+            findings.push(Finding {
+                rule,
+                severity,
+                title: task.title.clone(),
+                message,
+            });
+        }
+    };
+
+    for task in tasks {
+        let open = !is_done(&task.status);
+        if open && task.due.is_none() && config.deadline_projects.contains(&task.project) {
+            report(
+                Rule::NoDueDate,
+                task,
+                t!("check-no-due-date", project = task.project.as_str()),
+            );
+        }
+        // Tasks started before the start was recorded, or imported In Progress, have no
+        // `started_at`; the last change, or when they were added, stands in for it
+        let started_at = task.started_at.or(task.updated_at).or(task.created_at);
+        if let Some(started_at) = started_at.filter(|_| is_in_progress(&task.status)) {
+            let days = (now - started_at).num_days();
+            if days > config.stale_after_days {
+                report(
+                    Rule::StaleInProgress,
+                    task,
+                    t!("check-stale-in-progress", days = days),
+                );
+            }
+        }
+        if task.description.trim().is_empty() {
+            report(Rule::EmptyDescription, task, t!("check-empty-description"));
+        }
+        for dep in &task.depends_on {
+            if !tasks.iter().any(|other| other.title == *dep) {
+                report(
+                    Rule::MissingDependency,
+                    task,
+                    t!("check-missing-dependency", dependency = dep.as_str()),
+                );
+            }
+        }
+    }
+
+    findings.sort_by_key(|finding| Reverse(finding.severity));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_check_tasks() {
+        let now = Utc::now();
+        let tasks = vec![
+            Task {
+                title: String::from("Ship it"),
+                description: String::from("Release 1.0"),
+                status: String::from("In Progress"),
+                project: String::from("Release"),
+                started_at: Some(now - Duration::days(20)),
+                depends_on: vec![String::from("Removed")],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Fresh"),
+                status: String::from("In Progress"),
+                project: String::from("Other"),
+                started_at: Some(now - Duration::days(2)),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Imported"),
+                description: String::from("Started elsewhere"),
+                status: String::from("In Progress"),
+                project: String::from("Other"),
+                created_at: Some(now - Duration::days(40)),
+                ..Default::default()
+            },
+        ];
+        let mut config = CheckConfig {
+            deadline_projects: vec![String::from("Release")],
+            ..Default::default()
+        };
+
+        let rules = |findings: Vec<Finding>| -> Vec<(Rule, String)> {
+            findings.into_iter().map(|f| (f.rule, f.title)).collect()
+        };
+        assert_eq!(
+            rules(check_tasks(&tasks, &config, now)),
+            vec![
+                (Rule::MissingDependency, String::from("Ship it")),
+                (Rule::NoDueDate, String::from("Ship it")),
+                (Rule::StaleInProgress, String::from("Ship it")),
+                (Rule::StaleInProgress, String::from("Imported")),
+                (Rule::EmptyDescription, String::from("Fresh")),
+            ]
+        );
+
+        config
+            .severity
+            .insert(String::from("empty-description"), Severity::Off);
+        config.stale_after_days = 30;
+        assert_eq!(check_tasks(&tasks, &config, now).len(), 3);
+    }
+}
@@ -0,0 +1,53 @@
+//! Adding tasks from the clipboard.
+//!
+//! `add --from-clipboard` turns the text on the system clipboard, such as a copied email, into a
+//! task: its first line becomes the title and the rest the description. Reading the clipboard
+//! needs the default `clipboard` feature.
+
+#[cfg(not(feature = "clipboard"))]
+use crate::i18n::t;
+use std::error::Error;
+
+/// Reads the text on the system clipboard.
+#[cfg(feature = "clipboard")]
+pub fn read() -> Result<String, Box<dyn Error>> {
+    Ok(arboard::Clipboard::new()?.get_text()?)
+}
+
+/// Stands in for the clipboard when built without the `clipboard` feature.
+#[cfg(not(feature = "clipboard"))]
+pub fn read() -> Result<String, Box<dyn Error>> {
+    Err(t!("clipboard-unsupported").into())
+}
+
+/// Splits text into the title and description of a task, or `None` if it is blank.
+pub fn split(text: &str) -> Option<(String, String)> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let (title, description) = text.split_once('\n').unwrap_or((text, ""));
+    Some((title.trim().to_string(), description.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let email = "\n  Quarterly report due  \r\nHi,\n\nplease send the numbers by Friday.\n";
+        assert_eq!(
+            split(email),
+            Some((
+                String::from("Quarterly report due"),
+                String::from("Hi,\n\nplease send the numbers by Friday.")
+            ))
+        );
+        assert_eq!(
+            split("Call the bank"),
+            Some((String::from("Call the bank"), String::new()))
+        );
+        assert_eq!(split(" \n\t"), None);
+    }
+}
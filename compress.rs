@@ -0,0 +1,89 @@
+//! Compression of the JSON store.
+//!
+//! With `"compression": "gzip"` or `"zstd"` in `config.json`, the JSON store is kept in
+//! `tasks.json.gz` or `tasks.json.zst` instead of `tasks.json`, which pays off for large stores
+//! with long descriptions, on disk and when syncing. Stores are decompressed by their magic
+//! bytes, so a store compressed with either format, or not at all, can always be read.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// `Compression`: How the JSON store is compressed.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 3] = [Compression::None, Compression::Gzip, Compression::Zstd];
+
+    /// The extension added to the store's file name.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    /// Recognizes the compression of stored data by its magic bytes.
+    pub fn detect(data: &[u8]) -> Compression {
+        if data.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if data.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Compresses data with the given compression.
+pub fn compress(data: &[u8], compression: Compression) -> io::Result<Vec<u8>> {
+    match compression {
+        Compression::None => Ok(data.to_vec()),
+        Compression::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+        Compression::Zstd => zstd::encode_all(data, zstd::DEFAULT_COMPRESSION_LEVEL),
+    }
+}
+
+/// Decompresses data compressed with any supported compression, or none.
+pub fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    match Compression::detect(data) {
+        Compression::None => Ok(data.to_vec()),
+        Compression::Gzip => {
+            let mut decompressed = Vec::new();
+            flate2::read::GzDecoder::new(data).read_to_end(&mut decompressed)?;
+            Ok(decompressed)
+        }
+        Compression::Zstd => zstd::decode_all(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = br#"[{"title":"Task 1","description":"Description 1"}]"#.repeat(20);
+        for compression in Compression::ALL {
+            let compressed = compress(&data, compression).unwrap();
+            assert_eq!(Compression::detect(&compressed), compression);
+            assert_eq!(decompress(&compressed).unwrap(), data);
+        }
+        assert!(compress(&data, Compression::Zstd).unwrap().len() < data.len() / 4);
+    }
+}
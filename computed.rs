@@ -0,0 +1,186 @@
+//! Computed fields.
+//!
+//! Besides the fields kept with a task, a few are computed from them: `urgency`, the score
+//! `suggest` weighs tasks by, from the priority and how close the due date is; `age`, the days
+//! since the task was added; `days-until-due`, negative once the task is overdue; and `blocked`,
+//! 1 for Blocked tasks and 0 for others. They can be used in views without writing a script:
+//! `--where "urgency>=6"` picks tasks by them in `list` and `export`, `list --sort -urgency`
+//! sorts by them (a leading `-` for the largest first) and `list --columns title,urgency,age`
+//! shows them. Tasks without a value, like `days-until-due` of a task without a due date, match
+//! no condition on it and are sorted last.
+
+use crate::i18n::t;
+use crate::{is_blocked, urgency, Task};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+/// `Computed`: A field computed from the others.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Computed {
+    Urgency,
+    Age,
+    DaysUntilDue,
+    Blocked,
+}
+
+impl Computed {
+    /// Parses a field name, such as `days-until-due`.
+    pub fn parse(name: &str) -> Result<Computed, String> {
+        let name = name.trim().to_lowercase();
+        serde_json::from_value(serde_json::Value::from(name.as_str()))
+            .map_err(|_| t!("unknown-field", name = name))
+    }
+
+    /// The value of the field for a task, if it has one.
+    pub fn value(self, task: &Task, today: NaiveDate) -> Option<f64> {
+        match self {
+            Computed::Urgency => Some(urgency(task, today)),
+            Computed::Age => task
+                .created_at
+                .map(|created_at| (today - created_at.date_naive()).num_days() as f64),
+            Computed::DaysUntilDue => task.due.map(|due| (due - today).num_days() as f64),
+            Computed::Blocked => Some(if is_blocked(&task.status) { 1.0 } else { 0.0 }),
+        }
+    }
+}
+
+/// `Condition`: A comparison of a computed field with a number, as given to `--where`.
+#[derive(Debug, PartialEq)]
+pub struct Condition {
+    field: Computed,
+    operator: Ordering,
+    /// Whether the comparison is negated, as in `>=` (not less) or `!=` (not equal).
+    negated: bool,
+    value: f64,
+}
+
+impl Condition {
+    /// Parses a condition such as `urgency>=6` or `days-until-due<0`. A field alone, as in
+    /// `blocked`, means it is not 0, and `!blocked` that it is.
+    pub fn parse(text: &str) -> Result<Condition, String> {
+        let invalid = || t!("invalid-condition", condition = text);
+        let operators = [
+            (">=", Ordering::Less, true),
+            ("<=", Ordering::Greater, true),
+            ("!=", Ordering::Equal, true),
+            ("==", Ordering::Equal, false),
+            ("=", Ordering::Equal, false),
+            (">", Ordering::Greater, false),
+            ("<", Ordering::Less, false),
+        ];
+        for (symbol, operator, negated) in operators {
+            if let Some((field, value)) = text.split_once(symbol) {
+                return Ok(Condition {
+                    field: Computed::parse(field)?,
+                    operator,
+                    negated,
+                    value: value.trim().parse().map_err(|_| invalid())?,
+                });
+            }
+        }
+        let (field, negated) = match text.trim().strip_prefix('!') {
+            Some(field) => (field, false),
+            None => (text, true),
+        };
+        Ok(Condition {
+            field: Computed::parse(field)?,
+            operator: Ordering::Equal,
+            negated,
+            value: 0.0,
+        })
+    }
+
+    /// Tells whether a task meets the condition.
+    pub fn holds(&self, task: &Task, today: NaiveDate) -> bool {
+        self.field
+            .value(task, today)
+            .is_some_and(|value| (value.total_cmp(&self.value) == self.operator) != self.negated)
+    }
+}
+
+/// `Sort`: The computed field to sort tasks by, as given to `--sort`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sort {
+    field: Computed,
+    descending: bool,
+}
+
+impl Sort {
+    /// Parses a sort such as `age`, or `-urgency` for the largest first.
+    pub fn parse(text: &str) -> Result<Sort, String> {
+        let (field, descending) = match text.trim().strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (text, false),
+        };
+        Ok(Sort {
+            field: Computed::parse(field)?,
+            descending,
+        })
+    }
+
+    /// Sorts tasks by the field, keeping the order of tasks with the same value.
+    pub fn apply<T: Borrow<Task>>(self, tasks: &mut [T], today: NaiveDate) {
+        tasks.sort_by(|a, b| {
+            let value = |task: &T| self.field.value(task.borrow(), today);
+            match (value(a), value(b)) {
+                (Some(a), Some(b)) if self.descending => b.total_cmp(&a),
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_computed_fields() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let task = |title: &str, priority: u8, due: Option<u32>, status: &str| Task {
+            title: title.to_string(),
+            priority,
+            status: status.to_string(),
+            due: due.and_then(|day| NaiveDate::from_ymd_opt(2024, 7, day)),
+            created_at: Some(Utc.with_ymd_and_hms(2024, 7, 5, 9, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Write docs", 2, None, "Todo"),
+            task("Fix login bug", 3, Some(13), "In Progress"),
+            task("Deploy", 1, Some(20), "Blocked"),
+        ];
+        assert_eq!(Computed::Age.value(&tasks[0], today), Some(10.0));
+        assert_eq!(Computed::DaysUntilDue.value(&tasks[0], today), None);
+        assert_eq!(Computed::DaysUntilDue.value(&tasks[1], today), Some(-2.0));
+        assert_eq!(Computed::Urgency.value(&tasks[1], today), Some(13.0));
+        assert_eq!(Computed::Blocked.value(&tasks[2], today), Some(1.0));
+
+        let holds =
+            |condition: &str, task: &Task| Condition::parse(condition).unwrap().holds(task, today);
+        assert!(holds("urgency>=6", &tasks[1]));
+        assert!(!holds("urgency >= 6", &tasks[0]));
+        assert!(holds("days-until-due<0", &tasks[1]));
+        assert!(!holds("days-until-due<0", &tasks[0]));
+        assert!(holds("blocked", &tasks[2]));
+        assert!(holds("!blocked", &tasks[0]));
+        assert!(holds("age=10", &tasks[0]));
+        assert!(holds("age!=3", &tasks[0]));
+        assert!(Condition::parse("urgency>high").is_err());
+        assert!(Condition::parse("effort>3").is_err());
+
+        Sort::parse("-urgency").unwrap().apply(&mut tasks, today);
+        let titles: Vec<&str> = tasks.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles, ["Fix login bug", "Deploy", "Write docs"]);
+        Sort::parse("days-until-due")
+            .unwrap()
+            .apply(&mut tasks, today);
+        let titles: Vec<&str> = tasks.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles, ["Fix login bug", "Deploy", "Write docs"]);
+    }
+}
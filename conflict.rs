@@ -0,0 +1,216 @@
+//! Resolving conflicting changes to a remote store.
+//!
+//! When a change cannot be saved to a remote store because someone else saved it in the
+//! meantime (see the `remote` module), the tasks as they would have been saved are kept in
+//! `conflict.json`. `sync conflicts` then goes through the tasks changed on both sides and asks,
+//! field by field, whether to keep the local or the remote value, before saving the result.
+//! Tasks found on one side only are kept, so a task removed on one side and changed on the
+//! other is not lost.
+//!
+//! A local store changed by another program between reading and saving it, e.g. by a sync
+//! client, is caught the same way: the change is not saved over it, and is merged on the spot
+//! when there is a terminal to ask on, or else kept for `sync conflicts`.
+
+use crate::i18n::t;
+use crate::Task;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// The file the local tasks are kept in while they conflict with the remote store.
+pub const CONFLICT_FILE: &str = "conflict.json";
+
+/// Fields that are merged without asking: the revision and time of the last change.
+const BOOKKEEPING: [&str; 2] = ["revision", "updated_at"];
+
+thread_local! {
+    /// The local store as it was read: its path, and a hash of its contents or `None` when there
+    /// was no store yet.
+    static READ: RefCell<Option<(PathBuf, Option<u64>)>> = const { RefCell::new(None) };
+}
+
+/// `ChangedOnDisk`: The error of a save refused because the local store changed since it was
+/// read.
+#[derive(Debug)]
+pub struct ChangedOnDisk;
+
+impl fmt::Display for ChangedOnDisk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&t!("store-changed-on-disk"))
+    }
+}
+
+impl Error for ChangedOnDisk {}
+
+fn fingerprint(path: &Path) -> Option<u64> {
+    let contents = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Remembers the local store as it is now, after reading or saving it.
+pub fn remember(path: &str) {
+    let path = env::current_dir().map_or_else(|_| PathBuf::from(path), |dir| dir.join(path));
+    READ.with(|read| *read.borrow_mut() = Some((path.clone(), fingerprint(&path))));
+}
+
+/// Forgets the local store as it was read, so the next save goes ahead regardless.
+pub fn forget() {
+    READ.with(|read| *read.borrow_mut() = None);
+}
+
+/// Tells whether the local store changed since it was read.
+pub fn changed_on_disk() -> bool {
+    READ.with(|read| {
+        read.borrow()
+            .as_ref()
+            .is_some_and(|(path, read)| fingerprint(path) != *read)
+    })
+}
+
+/// `Side`: Which value of a conflicting field to keep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+/// `Conflict`: A field of a task that was changed differently on both sides.
+#[derive(Debug, PartialEq)]
+pub struct Conflict<'a> {
+    pub title: &'a str,
+    pub field: &'a str,
+    pub local: &'a Value,
+    pub remote: &'a Value,
+}
+
+/// The fields of a task as JSON, with those left out for being empty as null.
+fn fields(task: &Task) -> Map<String, Value> {
+    match serde_json::to_value(task) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+/// Merges the local tasks into the remote ones, asking `choose` which side to keep for every
+/// field that differs. Returns `None` when `choose` gives up, leaving the conflict unresolved.
+pub fn merge(
+    local: Vec<Task>,
+    mut remote: Vec<Task>,
+    mut choose: impl FnMut(&Conflict) -> Option<Side>,
+) -> Option<Vec<Task>> {
+    for task in local {
+        let Some(theirs) = remote.iter_mut().find(|other| other.title == task.title) else {
+            remote.push(task);
+            continue;
+        };
+        let (ours, mut merged) = (fields(&task), fields(theirs));
+        let null = Value::Null;
+        let mut names: Vec<&String> = ours.keys().chain(merged.keys()).collect();
+        names.sort();
+        names.dedup();
+        let mut changed = false;
+        let mut resolved = Map::new();
+        for name in names
+            .into_iter()
+            .filter(|name| !BOOKKEEPING.contains(&name.as_str()))
+        {
+            let (local, remote) = (
+                ours.get(name).unwrap_or(&null),
+                merged.get(name).unwrap_or(&null),
+            );
+            if local == remote {
+                continue;
+            }
+            let conflict = Conflict {
+                title: &task.title,
+                field: name,
+                local,
+                remote,
+            };
+            if choose(&conflict)? == Side::Local {
+                resolved.insert(name.clone(), local.clone());
+                changed = true;
+            }
+        }
+        if !changed {
+            continue;
+        }
+        merged.extend(resolved);
+        let updated_at = task.updated_at.max(theirs.updated_at);
+        let revision = task.revision.max(theirs.revision) + 1;
+        if let Ok(mut task) = serde_json::from_value::<Task>(Value::Object(merged)) {
+            task.updated_at = updated_at;
+            task.revision = revision;
+            *theirs = task;
+        }
+    }
+    Some(remote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let task = |title: &str, priority: u8, status: &str| Task {
+            title: title.to_string(),
+            priority,
+            status: status.to_string(),
+            revision: 3,
+            ..Default::default()
+        };
+        let local = vec![task("Report", 5, "Todo"), task("Local only", 1, "Todo")];
+        let remote = vec![task("Report", 2, "Done"), task("Remote only", 1, "Todo")];
+
+        let mut asked = Vec::new();
+        let merged = merge(local, remote, |conflict| {
+            asked.push((conflict.field.to_string(), conflict.local.clone()));
+            Some(if conflict.field == "priority" {
+                Side::Local
+            } else {
+                Side::Remote
+            })
+        })
+        .unwrap();
+        assert_eq!(
+            asked,
+            [
+                (String::from("priority"), Value::from(5)),
+                (String::from("status"), Value::from("Todo")),
+            ]
+        );
+        let titles: Vec<&str> = merged.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["Report", "Remote only", "Local only"]);
+        assert_eq!((merged[0].priority, merged[0].status.as_str()), (5, "Done"));
+        assert_eq!(merged[0].revision, 4);
+
+        let local = vec![task("Report", 5, "Todo")];
+        assert!(merge(local, vec![task("Report", 2, "Todo")], |_| None).is_none());
+    }
+
+    #[test]
+    fn test_changed_on_disk() {
+        let path = env::temp_dir().join(format!("conflict-test-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        assert!(!changed_on_disk());
+        remember(path);
+        assert!(!changed_on_disk());
+        fs::write(path, "[]").unwrap();
+        assert!(changed_on_disk());
+        remember(path);
+        assert!(!changed_on_disk());
+        fs::remove_file(path).unwrap();
+        assert!(changed_on_disk());
+        forget();
+        assert!(!changed_on_disk());
+    }
+}
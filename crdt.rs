@@ -0,0 +1,258 @@
+//! Conflict-free replicated task store.
+//!
+//! Tasks are kept in an Automerge document (`tasks.automerge`) instead of plain JSON. Every save
+//! only records the fields that actually changed, so two machines editing the store concurrently
+//! produce changes that merge field by field. When a file-sync tool such as Dropbox or Syncthing
+//! leaves a conflicted copy of the store next to the original, the copy is merged back in on the
+//! next load and then removed.
+
+use crate::i18n::t;
+use crate::Task;
+use automerge::transaction::Transactable;
+use automerge::{AutoCommit, ObjId, ObjType, ReadDoc, ScalarValue, Value, ROOT};
+use serde_json::{Map, Number};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+pub const STORE_FILE: &str = "tasks.automerge";
+const JSON_STORE_FILE: &str = "tasks.json";
+
+/// Loads tasks from the CRDT store, merging in any conflicted copies left by a sync tool.
+pub fn load_tasks() -> Result<Vec<Task>, Box<dyn Error>> {
+    let doc = open_store()?;
+    read_tasks(&doc)
+}
+
+/// Saves tasks to the CRDT store, recording only the fields that changed since the last save.
+pub fn save_tasks(tasks: &[Task]) -> Result<(), Box<dyn Error>> {
+    let mut doc = open_store()?;
+    write_tasks(&mut doc, tasks)?;
+    fs::write(STORE_FILE, doc.save())?;
+    Ok(())
+}
+
+/// Opens the store document, merging and removing conflicted copies found next to it.
+///
+/// The first time the store is opened, it is seeded from an existing `tasks.json`.
+fn open_store() -> Result<AutoCommit, Box<dyn Error>> {
+    let mut doc = if Path::new(STORE_FILE).exists() {
+        AutoCommit::load(&fs::read(STORE_FILE)?)?
+    } else {
+        let mut doc = AutoCommit::new();
+        let contents = fs::read_to_string(JSON_STORE_FILE).unwrap_or_default();
+        if let Ok(tasks) = serde_json::from_str::<Vec<Task>>(&contents) {
+            write_tasks(&mut doc, &tasks)?;
+        }
+        doc
+    };
+
+    let conflicts = conflicted_copies()?;
+    if !conflicts.is_empty() {
+        for path in &conflicts {
+            let mut other = AutoCommit::load(&fs::read(path)?)?;
+            doc.merge(&mut other)?;
+        }
+        fs::write(STORE_FILE, doc.save())?;
+        for path in &conflicts {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(doc)
+}
+
+/// Finds copies of the store created by sync tools, e.g. `tasks.sync-conflict-<...>.automerge`
+/// (Syncthing) or `tasks (conflicted copy <...>).automerge` (Dropbox).
+fn conflicted_copies() -> Result<Vec<String>, Box<dyn Error>> {
+    let mut copies = Vec::new();
+    for entry in fs::read_dir(".")? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name != STORE_FILE && name.starts_with("tasks") && name.ends_with(".automerge") {
+            copies.push(name);
+        }
+    }
+    copies.sort();
+    Ok(copies)
+}
+
+/// Reads all tasks out of the document.
+fn read_tasks(doc: &AutoCommit) -> Result<Vec<Task>, Box<dyn Error>> {
+    let list = match doc.get(ROOT, "tasks")? {
+        Some((Value::Object(ObjType::List), id)) => id,
+        _ => return Ok(vec![]),
+    };
+
+    let mut tasks = Vec::new();
+    for index in 0..doc.length(&list) {
+        if let Some((Value::Object(ObjType::Map), obj)) = doc.get(&list, index)? {
+            let mut fields = Map::new();
+            for key in doc.keys(&obj) {
+                if let Some((Value::Scalar(value), _)) = doc.get(&obj, key.as_str())? {
+                    fields.insert(key, from_scalar(&value)?);
+                }
+            }
+            tasks.push(serde_json::from_value(serde_json::Value::Object(fields))?);
+        }
+    }
+    Ok(tasks)
+}
+
+/// Brings the document in line with `tasks`.
+///
+/// Tasks are matched by title. Only fields whose value differs from the document are written, so
+/// a concurrent edit to another field of the same task on another machine is not overwritten.
+fn write_tasks(doc: &mut AutoCommit, tasks: &[Task]) -> Result<(), Box<dyn Error>> {
+    let list = match doc.get(ROOT, "tasks")? {
+        Some((Value::Object(ObjType::List), id)) => id,
+        _ => doc.put_object(ROOT, "tasks", ObjType::List)?,
+    };
+
+    // Drop tasks that no longer exist, walking backwards so indexes stay valid.
+    for index in (0..doc.length(&list)).rev() {
+        let keep = match doc.get(&list, index)? {
+            Some((Value::Object(ObjType::Map), obj)) => {
+                let title = read_title(doc, &obj)?;
+                tasks.iter().any(|task| Some(&task.title) == title.as_ref())
+            }
+            _ => false,
+        };
+        if !keep {
+            doc.delete(&list, index)?;
+        }
+    }
+
+    for task in tasks {
+        let fields = match serde_json::to_value(task)? {
+            serde_json::Value::Object(fields) => fields,
+            _ => unreachable!("tasks serialize to JSON objects"),
+        };
+
+        let mut existing = None;
+        for index in 0..doc.length(&list) {
+            if let Some((Value::Object(ObjType::Map), obj)) = doc.get(&list, index)? {
+                if read_title(doc, &obj)?.as_ref() == Some(&task.title) {
+                    existing = Some(obj);
+                    break;
+                }
+            }
+        }
+        let obj = match existing {
+            Some(obj) => obj,
+            None => doc.insert_object(&list, doc.length(&list), ObjType::Map)?,
+        };
+
+        for key in doc.keys(&obj).collect::<Vec<_>>() {
+            if !fields.contains_key(&key) {
+                doc.delete(&obj, key.as_str())?;
+            }
+        }
+        for (key, value) in fields {
+            let current = match doc.get(&obj, key.as_str())? {
+                Some((Value::Scalar(current), _)) => Some(from_scalar(&current)?),
+                _ => None,
+            };
+            if current.as_ref() != Some(&value) {
+                doc.put(&obj, key.as_str(), to_scalar(&value)?)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the title of a task object.
+fn read_title(doc: &AutoCommit, obj: &ObjId) -> Result<Option<String>, Box<dyn Error>> {
+    match doc.get(obj, "title")? {
+        Some((Value::Scalar(value), _)) => match from_scalar(&value)? {
+            serde_json::Value::String(title) => Ok(Some(title)),
+            _ => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Converts a JSON field value into an Automerge scalar. Arrays and objects are stored as JSON
+/// encoded bytes, which makes them last-writer-wins as a whole.
+fn to_scalar(value: &serde_json::Value) -> Result<ScalarValue, Box<dyn Error>> {
+    Ok(match value {
+        serde_json::Value::Null => ScalarValue::Null,
+        serde_json::Value::Bool(b) => ScalarValue::Boolean(*b),
+        serde_json::Value::Number(n) => {
+            if let Some(n) = n.as_u64() {
+                ScalarValue::Uint(n)
+            } else if let Some(n) = n.as_i64() {
+                ScalarValue::Int(n)
+            } else {
+                ScalarValue::F64(n.as_f64().unwrap_or_default())
+            }
+        }
+        serde_json::Value::String(s) => ScalarValue::Str(s.as_str().into()),
+        nested => ScalarValue::Bytes(serde_json::to_vec(nested)?),
+    })
+}
+
+/// Converts an Automerge scalar back into the JSON field value it was written from.
+fn from_scalar(value: &ScalarValue) -> Result<serde_json::Value, Box<dyn Error>> {
+    Ok(match value {
+        ScalarValue::Null => serde_json::Value::Null,
+        ScalarValue::Boolean(b) => serde_json::Value::Bool(*b),
+        ScalarValue::Uint(n) => serde_json::Value::from(*n),
+        ScalarValue::Int(n) => serde_json::Value::from(*n),
+        ScalarValue::F64(n) => Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        ScalarValue::Str(s) => serde_json::Value::String(s.to_string()),
+        ScalarValue::Bytes(bytes) => serde_json::from_slice(bytes)?,
+        other => return Err(t!("crdt-unsupported-value", value = other.to_string()).into()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str, priority: u8, status: &str) -> Task {
+        Task {
+            title: String::from(title),
+            description: String::from("Description"),
+            priority,
+            status: String::from(status),
+            project: String::from("Project"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tasks = vec![task("Task 1", 1, "Todo"), task("Task 2", 2, "In Progress")];
+        let mut doc = AutoCommit::new();
+        write_tasks(&mut doc, &tasks).unwrap();
+
+        let reloaded = AutoCommit::load(&doc.save()).unwrap();
+        assert_eq!(read_tasks(&reloaded).unwrap(), tasks);
+    }
+
+    #[test]
+    fn test_concurrent_edits_merge() {
+        let mut laptop = AutoCommit::new();
+        write_tasks(
+            &mut laptop,
+            &[task("Task 1", 1, "Todo"), task("Task 2", 2, "Todo")],
+        )
+        .unwrap();
+        let mut desktop = laptop.fork();
+
+        // The laptop changes the status of Task 1, the desktop its priority and removes Task 2.
+        write_tasks(
+            &mut laptop,
+            &[task("Task 1", 1, "Done"), task("Task 2", 2, "Todo")],
+        )
+        .unwrap();
+        write_tasks(&mut desktop, &[task("Task 1", 5, "Todo")]).unwrap();
+
+        laptop.merge(&mut desktop).unwrap();
+        assert_eq!(
+            read_tasks(&laptop).unwrap(),
+            vec![task("Task 1", 5, "Done")]
+        );
+    }
+}
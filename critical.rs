@@ -0,0 +1,206 @@
+//! Critical paths.
+//!
+//! `project analyze <name>` adds up the work left in a project and finds its critical path: the
+//! chain of dependent open tasks that takes longest. The work left on a task is its estimate less
+//! the time tracked on it. Tasks on the critical path have no slack, so any slippage on them
+//! delays the end of the project; the others could slip by their slack first. Only dependencies
+//! between the project's own open tasks are followed, and tasks without an estimate count as no
+//! work, so they are listed to be estimated.
+
+use crate::{is_done, Task};
+
+/// `Analysis`: The work left in a project and how it depends on itself.
+#[derive(Debug)]
+pub struct Analysis<'a> {
+    /// The hours of work left on all open tasks.
+    pub remaining: f64,
+    /// The hours the longest chain of dependent tasks takes, the least the project can take.
+    pub length: f64,
+    /// The longest chain of dependent tasks, in the order they have to be done.
+    pub path: Vec<&'a Task>,
+    /// Every open task with the hours it could slip without delaying the project; 0 for tasks
+    /// on a critical path.
+    pub slack: Vec<(&'a Task, f64)>,
+    /// Open tasks without an estimate.
+    pub unestimated: Vec<&'a Task>,
+}
+
+/// Tolerance for comparing sums of hours.
+const EPSILON: f64 = 1e-9;
+
+/// The hours of work left on a task: its estimate less the time tracked on it.
+pub fn remaining(task: &Task) -> f64 {
+    let tracked: f64 = task.time_entries.iter().map(|entry| entry.hours).sum();
+    (task.estimate.unwrap_or(0.0) - tracked).max(0.0)
+}
+
+/// Analyzes the open tasks of a project, or returns `None` when it has none.
+pub fn analyze<'a>(tasks: &'a [Task], project: &str) -> Option<Analysis<'a>> {
+    let open: Vec<&Task> = tasks
+        .iter()
+        .filter(|task| task.project == project && !is_done(&task.status))
+        .collect();
+    if open.is_empty() {
+        return None;
+    }
+    let work: Vec<f64> = open.iter().map(|task| remaining(task)).collect();
+    let dependencies: Vec<Vec<usize>> = open
+        .iter()
+        .map(|task| {
+            task.depends_on
+                .iter()
+                .filter_map(|dep| open.iter().position(|other| other.title == *dep))
+                .collect()
+        })
+        .collect();
+
+    // Earliest finish of each task, following dependencies first; a dependency cycle is cut
+    // where it closes
+    let mut finish: Vec<Option<f64>> = vec![None; open.len()];
+    let mut visiting = vec![false; open.len()];
+    fn earliest(
+        index: usize,
+        work: &[f64],
+        dependencies: &[Vec<usize>],
+        finish: &mut [Option<f64>],
+        visiting: &mut [bool],
+    ) -> f64 {
+        if let Some(finish) = finish[index] {
+            return finish;
+        }
+        if visiting[index] {
+            return 0.0;
+        }
+        visiting[index] = true;
+        let start = dependencies[index]
+            .iter()
+            .map(|&dep| earliest(dep, work, dependencies, finish, visiting))
+            .fold(0.0, f64::max);
+        visiting[index] = false;
+        finish[index] = Some(start + work[index]);
+        start + work[index]
+    }
+    let finish: Vec<f64> = (0..open.len())
+        .map(|index| earliest(index, &work, &dependencies, &mut finish, &mut visiting))
+        .collect();
+    let length = finish.iter().copied().fold(0.0, f64::max);
+
+    // Latest finish of each task that still lets the project end at `length`: the latest start
+    // of the tasks waiting on it, or the end for tasks nothing waits on
+    let dependents: Vec<Vec<usize>> = (0..open.len())
+        .map(|index| {
+            (0..open.len())
+                .filter(|&other| dependencies[other].contains(&index))
+                .collect()
+        })
+        .collect();
+    let mut latest: Vec<Option<f64>> = vec![None; open.len()];
+    fn latest_finish(
+        index: usize,
+        length: f64,
+        work: &[f64],
+        dependents: &[Vec<usize>],
+        latest: &mut [Option<f64>],
+        visiting: &mut [bool],
+    ) -> f64 {
+        if let Some(latest) = latest[index] {
+            return latest;
+        }
+        if visiting[index] {
+            return length;
+        }
+        visiting[index] = true;
+        let finish = dependents[index]
+            .iter()
+            .map(|&other| {
+                latest_finish(other, length, work, dependents, latest, visiting) - work[other]
+            })
+            .fold(length, f64::min);
+        visiting[index] = false;
+        latest[index] = Some(finish);
+        finish
+    }
+    let latest: Vec<f64> = (0..open.len())
+        .map(|index| {
+            latest_finish(
+                index,
+                length,
+                &work,
+                &dependents,
+                &mut latest,
+                &mut visiting,
+            )
+        })
+        .collect();
+
+    let mut path = Vec::new();
+    let mut current = (0..open.len()).max_by(|&a, &b| finish[a].total_cmp(&finish[b]));
+    while let Some(index) = current {
+        path.push(open[index]);
+        let start = finish[index] - work[index];
+        current = dependencies[index]
+            .iter()
+            .copied()
+            .filter(|&dep| (finish[dep] - start).abs() < EPSILON)
+            .max_by(|&a, &b| work[a].total_cmp(&work[b]));
+    }
+    path.reverse();
+
+    Some(Analysis {
+        remaining: work.iter().sum(),
+        length,
+        path,
+        slack: (0..open.len())
+            .map(|index| (open[index], (latest[index] - finish[index]).max(0.0)))
+            .collect(),
+        unestimated: open
+            .iter()
+            .copied()
+            .filter(|task| task.estimate.is_none())
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimeEntry;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_analyze() {
+        let task = |title: &str, estimate: Option<f64>, depends_on: &[&str]| Task {
+            title: title.to_string(),
+            status: String::from("Todo"),
+            project: String::from("Launch"),
+            estimate,
+            depends_on: depends_on.iter().map(|dep| dep.to_string()).collect(),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Design", Some(4.0), &[]),
+            task("Backend", Some(10.0), &["Design"]),
+            task("Frontend", Some(6.0), &["Design"]),
+            task("Docs", None, &[]),
+            task("Release", Some(1.0), &["Backend", "Frontend"]),
+        ];
+        tasks[1].time_entries.push(TimeEntry {
+            date: NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+            hours: 2.0,
+        });
+
+        let analysis = analyze(&tasks, "Launch").unwrap();
+        assert_eq!(analysis.remaining, 19.0);
+        assert_eq!(analysis.length, 13.0);
+        let path: Vec<&str> = analysis.path.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(path, ["Design", "Backend", "Release"]);
+        let slack: Vec<f64> = analysis.slack.iter().map(|(_, slack)| *slack).collect();
+        assert_eq!(slack, [0.0, 0.0, 2.0, 13.0, 0.0]);
+        assert_eq!(analysis.unestimated[0].title, "Docs");
+
+        // A finished dependency no longer counts
+        tasks[0].status = String::from("Done");
+        assert_eq!(analyze(&tasks, "Launch").unwrap().length, 9.0);
+        assert!(analyze(&tasks, "Other").is_none());
+    }
+}
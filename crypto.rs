@@ -0,0 +1,139 @@
+//! Encrypted stores.
+//!
+//! With `"encryption_key": "tasks.key"` in `config.json`, the JSON store is encrypted with the key
+//! in that file before it leaves the program, so a sync folder, an SSH server or an S3 bucket
+//! keeping it never sees what the tasks say. `key generate` creates the key; copy the file to
+//! every device sharing the store, since the store cannot be read without it. Stores are
+//! encrypted with XChaCha20-Poly1305, which also detects any tampering with them. The binary,
+//! NDJSON and CRDT stores cannot be, so a key set for them is refused instead of saving them in
+//! plaintext.
+//!
+//! Encrypted stores start with a marker. Once a key is set, a store without it is refused, so
+//! that no one with access to the store can slip in tasks of their own. A store saved before
+//! encryption was turned on is encrypted once with `key encrypt`.
+
+use crate::i18n::t;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The start of an encrypted store.
+const MAGIC: &[u8] = b"TMENC1";
+const NONCE_LEN: usize = 24;
+
+thread_local! {
+    /// Whether stores that are not encrypted are read although a key is set, for `key encrypt`.
+    static ACCEPT_PLAINTEXT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Reads stores that are not encrypted yet although a key is set, so they can be encrypted.
+pub fn accept_plaintext() {
+    ACCEPT_PLAINTEXT.with(|accept| accept.set(true));
+}
+
+/// `Key`: The key a store is encrypted with.
+pub struct Key([u8; 32]);
+
+impl Key {
+    /// Makes a new random key.
+    pub fn generate() -> Key {
+        let mut key = [0; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Key(key)
+    }
+
+    /// Reads a key saved as 64 hexadecimal digits.
+    fn parse(text: &str) -> Option<Key> {
+        let text = text.trim();
+        if text.len() != 64 || !text.is_ascii() {
+            return None;
+        }
+        let mut key = [0; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[2 * i..2 * i + 2], 16).ok()?;
+        }
+        Some(Key(key))
+    }
+
+    /// Loads the key from a file.
+    pub fn load(path: &str) -> Result<Key, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| t!("key-read-failed", path = path, error = err.to_string()))?;
+        Key::parse(&text).ok_or_else(|| t!("key-invalid", path = path))
+    }
+
+    /// Saves the key to a new file, readable only by its owner where that can be said.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let hex: String = self.0.iter().map(|byte| format!("{:02x}", byte)).collect();
+        io::Write::write_all(&mut options.open(path)?, format!("{}\n", hex).as_bytes())
+    }
+}
+
+/// Encrypts a store.
+pub fn encrypt(data: &[u8], key: &Key) -> Vec<u8> {
+    let mut nonce = [0; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let cipher = XChaCha20Poly1305::new(&key.0.into());
+    let sealed = cipher
+        .encrypt(XNonce::from_slice(&nonce), data)
+        .expect("encrypting into memory cannot fail");
+    [MAGIC, &nonce, &sealed].concat()
+}
+
+/// Decrypts a store, or returns it as it is when it is not encrypted and no key is set.
+pub fn decrypt(data: Vec<u8>, key: Option<&Key>) -> Result<Vec<u8>, String> {
+    let Some(sealed) = data.strip_prefix(MAGIC) else {
+        if key.is_some() && !data.is_empty() && !ACCEPT_PLAINTEXT.with(Cell::get) {
+            return Err(t!("store-not-encrypted"));
+        }
+        return Ok(data);
+    };
+    let key = key.ok_or_else(|| t!("store-encrypted"))?;
+    if sealed.len() < NONCE_LEN {
+        return Err(t!("store-decrypt-failed"));
+    }
+    let (nonce, sealed) = sealed.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(&key.0.into())
+        .decrypt(XNonce::from_slice(nonce), sealed)
+        .map_err(|_| t!("store-decrypt-failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let key = Key::generate();
+        let store = br#"[{"title":"Renew passport"}]"#;
+        let sealed = encrypt(store, &key);
+        assert!(sealed.starts_with(MAGIC));
+        assert!(!sealed.windows(8).any(|w| w == b"passport"));
+        assert_eq!(decrypt(sealed.clone(), Some(&key)).unwrap(), store);
+
+        // Without the key, with another key or tampered with, the store cannot be read
+        assert!(decrypt(sealed.clone(), None).is_err());
+        assert!(decrypt(sealed.clone(), Some(&Key::generate())).is_err());
+        let mut tampered = sealed;
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(tampered, Some(&key)).is_err());
+        // Stores saved before encryption was turned on are only read to encrypt them
+        assert!(decrypt(store.to_vec(), Some(&key)).is_err());
+        assert_eq!(decrypt(store.to_vec(), None).unwrap(), store);
+        assert!(decrypt(Vec::new(), Some(&key)).unwrap().is_empty());
+        accept_plaintext();
+        assert_eq!(decrypt(store.to_vec(), Some(&key)).unwrap(), store);
+
+        let hex = "00ff".repeat(16);
+        assert_eq!(Key::parse(&hex).unwrap().0[..2], [0, 255]);
+        assert!(Key::parse("00ff").is_none());
+    }
+}
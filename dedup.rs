@@ -0,0 +1,166 @@
+//! Duplicate detection on import.
+//!
+//! Imported tasks are compared with the existing ones by title, after normalizing case,
+//! punctuation and word order, so `Fix login bug` and `Bug: fix the login` are taken for the
+//! same task. What happens to a likely duplicate is chosen with `import --on-duplicate`: it is
+//! skipped, merged into the existing task, or created anyway.
+
+use crate::Task;
+
+/// How alike two normalized titles must be, from 0 to 1, to be taken for duplicates.
+pub const SIMILARITY: f64 = 0.85;
+
+/// `OnDuplicate`: What to do with an imported task that looks like an existing one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnDuplicate {
+    /// Ask for each duplicate, or skip it when there is no terminal to ask on.
+    Ask,
+    Skip,
+    /// Fill in what the existing task lacks from the imported one.
+    Merge,
+    /// Add the imported task next to the existing one.
+    Create,
+}
+
+impl OnDuplicate {
+    /// Parses a policy as given to `--on-duplicate`.
+    pub fn parse(policy: &str) -> Option<OnDuplicate> {
+        match policy {
+            "ask" => Some(OnDuplicate::Ask),
+            "skip" => Some(OnDuplicate::Skip),
+            "merge" => Some(OnDuplicate::Merge),
+            "create" => Some(OnDuplicate::Create),
+            _ => None,
+        }
+    }
+}
+
+/// Lowercases a title and reduces it to its words.
+fn normalize(title: &str) -> Vec<String> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !["a", "an", "the"].contains(word))
+        .map(String::from)
+        .collect()
+}
+
+/// The number of single-character edits turning one string into another.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// How alike two titles are, from 0 for nothing in common to 1 for the same words.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let ratio = |a: &str, b: &str| {
+        let len = a.chars().count().max(b.chars().count());
+        if len == 0 {
+            return 1.0;
+        }
+        1.0 - levenshtein(a, b) as f64 / len as f64
+    };
+    let (mut a, mut b) = (normalize(a), normalize(b));
+    let in_order = ratio(&a.join(" "), &b.join(" "));
+    a.sort();
+    b.sort();
+    in_order.max(ratio(&a.join(" "), &b.join(" ")))
+}
+
+/// Finds the existing task most like an imported title, if any is alike enough, with how alike
+/// they are. A task with exactly the same title always wins.
+pub fn find(tasks: &[Task], title: &str) -> Option<(usize, f64)> {
+    if let Some(index) = tasks.iter().position(|task| task.title == title) {
+        return Some((index, 1.0));
+    }
+    tasks
+        .iter()
+        .map(|task| similarity(&task.title, title))
+        .enumerate()
+        .filter(|(_, similarity)| *similarity >= SIMILARITY)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+/// Fills in what an existing task lacks from an imported duplicate of it. The existing task
+/// keeps its title, status and priority.
+pub fn merge(existing: &mut Task, imported: Task) {
+    if existing.description.is_empty() {
+        existing.description = imported.description;
+    }
+    if existing.due.is_none() {
+        existing.due = imported.due;
+        existing.due_at = imported.due_at;
+    }
+    existing.estimate = existing.estimate.or(imported.estimate);
+    existing.url = existing.url.take().or(imported.url);
+    existing.external_id = existing.external_id.take().or(imported.external_id);
+    for tag in imported.tags {
+        if !existing.tags.contains(&tag) {
+            existing.tags.push(tag);
+        }
+    }
+    for dependency in imported.depends_on {
+        if dependency != existing.title && !existing.depends_on.contains(&dependency) {
+            existing.depends_on.push(dependency);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_and_merge() {
+        assert_eq!(similarity("Fix login bug", "fix login bug!"), 1.0);
+        assert_eq!(similarity("Fix login bug", "Bug: fix the login"), 1.0);
+        assert!(similarity("Fix login bug", "Fix logn bug") > SIMILARITY);
+        assert!(similarity("Fix login bug", "Fix logout bug") < SIMILARITY);
+
+        let mut tasks = vec![
+            Task {
+                title: String::from("Write release notes"),
+                tags: vec![String::from("docs")],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Renew certificate"),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            find(&tasks, "Renew the certificates").map(|(i, _)| i),
+            Some(1)
+        );
+        assert_eq!(find(&tasks, "Rotate keys"), None);
+        tasks[0].title = String::from("Renew the certificate");
+        tasks[1].title = String::from("Renew certificate");
+        assert_eq!(find(&tasks, "Renew the certificate"), Some((0, 1.0)));
+        tasks[0].title = String::from("Write release notes");
+
+        let imported = Task {
+            title: String::from("write release notes"),
+            description: String::from("For 2.0"),
+            tags: vec![String::from("release"), String::from("docs")],
+            ..Default::default()
+        };
+        merge(&mut tasks[0], imported);
+        assert_eq!(tasks[0].title, "Write release notes");
+        assert_eq!(tasks[0].description, "For 2.0");
+        assert_eq!(tasks[0].tags, ["docs", "release"]);
+    }
+}
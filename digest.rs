@@ -0,0 +1,203 @@
+//! Daily digests by email.
+//!
+//! `digest` sums up the overdue tasks, the tasks due today and those completed yesterday, and
+//! emails the summary over SMTP as set up in the `email` section of `config.json`. It is meant
+//! to be run once a day, e.g. from cron; `digest --print` shows the summary instead of sending
+//! it. The SMTP password can be kept in the OS keyring with `auth login smtp` (see the `secrets`
+//! module). Sending email needs the default `email` feature.
+
+use crate::format::Formatter;
+use crate::i18n::t;
+use crate::{is_done, Task};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// `EmailConfig`: The `email` section of `config.json`.
+///
+/// For example `{ "smtp_host": "smtp.example.com", "username": "ann@example.com", "to":
+/// ["ann@example.com"] }` logs in as ann@example.com and sends the digest to that same address
+/// over STARTTLS.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub smtp_host: Option<String>,
+    /// The port of the SMTP server, by default the usual one for the security chosen.
+    pub smtp_port: Option<u16>,
+    pub security: Security,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// The sender, by default the username.
+    pub from: Option<String>,
+    pub to: Vec<String>,
+}
+
+/// `Security`: How the connection to the SMTP server is secured.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Security {
+    /// TLS from the start, usually on port 465.
+    Tls,
+    /// Upgraded to TLS after connecting, usually on port 587.
+    #[default]
+    StartTls,
+    /// Not at all, for a relay on the same machine.
+    None,
+}
+
+/// `Digest`: The tasks a digest tells about.
+#[derive(Debug, Default)]
+pub struct Digest<'a> {
+    pub overdue: Vec<&'a Task>,
+    pub due_today: Vec<&'a Task>,
+    pub completed_yesterday: Vec<&'a Task>,
+}
+
+impl Digest<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.overdue.is_empty() && self.due_today.is_empty() && self.completed_yesterday.is_empty()
+    }
+}
+
+/// The local date a task is due on, if it is due.
+fn due_date(task: &Task, fmt: &Formatter) -> Option<NaiveDate> {
+    task.due_at
+        .map(|due_at| fmt.local_date(due_at))
+        .or(task.due)
+}
+
+/// Collects the tasks for the digest of a day.
+pub fn collect<'a>(tasks: &'a [Task], fmt: &Formatter, today: NaiveDate) -> Digest<'a> {
+    let mut digest = Digest::default();
+    for task in tasks {
+        if is_done(&task.status) {
+            let completed = task.completed_at.map(|at| fmt.local_date(at));
+            if completed == Some(today - Duration::days(1)) {
+                digest.completed_yesterday.push(task);
+            }
+            continue;
+        }
+        match due_date(task, fmt) {
+            Some(due) if due < today => digest.overdue.push(task),
+            Some(due) if due == today => digest.due_today.push(task),
+            _ => {}
+        }
+    }
+    digest.overdue.sort_by_key(|task| due_date(task, fmt));
+    digest
+}
+
+/// Renders a digest as plain text.
+pub fn render(digest: &Digest, fmt: &Formatter, today: NaiveDate) -> String {
+    if digest.is_empty() {
+        return format!("{}\n", t!("digest-empty"));
+    }
+    let sections = [
+        ("digest-overdue", &digest.overdue),
+        ("digest-due-today", &digest.due_today),
+        ("digest-completed-yesterday", &digest.completed_yesterday),
+    ];
+    let mut text = String::new();
+    for (heading, tasks) in sections {
+        if tasks.is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!("{} ({})\n", t!(heading), tasks.len()));
+        for task in tasks {
+            let mut line = format!("- {} [{}]", task.title, task.project);
+            if let Some(due) = due_date(task, fmt).filter(|due| *due < today) {
+                line.push_str(&format!(" {}", fmt.relative_due(due, today)));
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Emails a digest.
+#[cfg(feature = "email")]
+pub fn send(config: &EmailConfig, subject: &str, body: &str) -> Result<(), Box<dyn Error>> {
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{Message, SmtpTransport, Transport};
+
+    let host = config
+        .smtp_host
+        .as_deref()
+        .ok_or_else(|| t!("digest-no-email"))?;
+    let from = config.from.as_ref().or(config.username.as_ref());
+    let from = from.ok_or_else(|| t!("digest-no-email"))?;
+    if config.to.is_empty() {
+        return Err(t!("digest-no-email").into());
+    }
+    let mut message = Message::builder().from(from.parse()?).subject(subject);
+    for to in &config.to {
+        message = message.to(to.parse()?);
+    }
+    let message = message.body(body.to_string())?;
+
+    let mut transport = match config.security {
+        Security::Tls => SmtpTransport::relay(host)?,
+        Security::StartTls => SmtpTransport::starttls_relay(host)?,
+        Security::None => SmtpTransport::builder_dangerous(host),
+    };
+    if let Some(port) = config.smtp_port {
+        transport = transport.port(port);
+    }
+    if let Some(username) = &config.username {
+        let password = config
+            .password
+            .clone()
+            .or_else(|| crate::secrets::get("smtp-password"));
+        let password = password.ok_or_else(|| t!("digest-no-password"))?;
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+    transport.build().send(&message)?;
+    Ok(())
+}
+
+/// Stands in for the SMTP client when built without the `email` feature.
+#[cfg(not(feature = "email"))]
+pub fn send(_config: &EmailConfig, _subject: &str, _body: &str) -> Result<(), Box<dyn Error>> {
+    Err(t!("email-unsupported").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_collect() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 10).unwrap();
+        let task = |title: &str, status: &str, due: Option<u32>, completed: Option<u32>| Task {
+            title: title.to_string(),
+            status: status.to_string(),
+            due: due.map(|day| NaiveDate::from_ymd_opt(2024, 7, day).unwrap()),
+            completed_at: completed.map(|day| {
+                chrono::Utc
+                    .with_ymd_and_hms(2024, 7, day, 12, 0, 0)
+                    .unwrap()
+            }),
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("Late", "Todo", Some(8), None),
+            task("Later", "Todo", Some(5), None),
+            task("Today", "In Progress", Some(10), None),
+            task("Tomorrow", "Todo", Some(11), None),
+            task("Finished", "Done", Some(8), Some(9)),
+            task("Long ago", "Done", None, Some(1)),
+        ];
+        let fmt = Formatter::default();
+        let digest = collect(&tasks, &fmt, today);
+        let titles = |tasks: &[&Task]| tasks.iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&digest.overdue), ["Later", "Late"]);
+        assert_eq!(titles(&digest.due_today), ["Today"]);
+        assert_eq!(titles(&digest.completed_yesterday), ["Finished"]);
+        assert!(collect(&tasks[3..4], &fmt, today).is_empty());
+    }
+}
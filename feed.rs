@@ -0,0 +1,125 @@
+//! Atom feed of task activity.
+//!
+//! `export --format atom` writes the tasks added and completed recently as an Atom feed, so
+//! teammates can follow a project in their feed reader, e.g. by publishing the file next to a
+//! shared store.
+
+use crate::i18n::t;
+use crate::query::Query;
+use crate::Task;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::cmp::Reverse;
+
+/// Renders the tasks chosen by a query that were added or completed in the last `days` days as
+/// an Atom feed, newest first. Returns `None` if that reaches back further than times go.
+pub fn atom_feed(tasks: &[Task], query: &Query, now: DateTime<Utc>, days: i64) -> Option<String> {
+    let since = now.checked_sub_signed(Duration::try_days(days)?)?;
+    let mut events: Vec<(DateTime<Utc>, &str, &Task)> = Vec::new();
+    for task in tasks.iter().filter(|task| query.matches(task)) {
+        if let Some(created_at) = task.created_at.filter(|at| *at >= since) {
+            events.push((created_at, "added", task));
+        }
+        if let Some(completed_at) = task.completed_at.filter(|at| *at >= since) {
+            events.push((completed_at, "completed", task));
+        }
+    }
+    events.sort_by_key(|(at, _, _)| Reverse(*at));
+
+    let timestamp = |at: DateTime<Utc>| at.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let updated = events.first().map_or(now, |event| event.0);
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str(&format!("  <title>{}</title>\n", escape(&t!("feed-title"))));
+    feed.push_str("  <id>urn:task-manager:activity</id>\n");
+    feed.push_str(&format!("  <updated>{}</updated>\n", timestamp(updated)));
+    // RFC 4287 asks for an author of the feed unless every entry has one
+    feed.push_str(&format!(
+        "  <author>\n    <name>{}</name>\n  </author>\n",
+        escape(&t!("feed-author"))
+    ));
+    for (at, kind, task) in events {
+        let title = match kind {
+            "added" => t!("feed-added", title = task.title.as_str()),
+            _ => t!("feed-completed", title = task.title.as_str()),
+        };
+        let summary = t!(
+            "feed-summary",
+            project = task.project.as_str(),
+            priority = task.priority,
+            description = task.description.as_str()
+        );
+        feed.push_str("  <entry>\n");
+        feed.push_str(&format!("    <title>{}</title>\n", escape(&title)));
+        feed.push_str(&format!(
+            "    <id>urn:task-manager:{}:{}:{}</id>\n",
+            kind,
+            at.timestamp(),
+            encode(&task.title)
+        ));
+        feed.push_str(&format!("    <updated>{}</updated>\n", timestamp(at)));
+        feed.push_str(&format!(
+            "    <category term=\"{}\"/>\n",
+            escape(&task.project)
+        ));
+        feed.push_str(&format!("    <summary>{}</summary>\n", escape(&summary)));
+        feed.push_str("  </entry>\n");
+    }
+    feed.push_str("</feed>\n");
+    Some(feed)
+}
+
+/// Escapes text for use in XML content and attributes.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Percent-encodes a title for use in an entry id.
+fn encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atom_feed() {
+        let now = Utc::now();
+        let tasks = vec![
+            Task {
+                title: String::from("Fix <login> & signup"),
+                project: String::from("Website"),
+                created_at: Some(now - Duration::days(3)),
+                completed_at: Some(now - Duration::days(1)),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Old"),
+                created_at: Some(now - Duration::days(90)),
+                ..Default::default()
+            },
+        ];
+
+        let feed = atom_feed(&tasks, &Query::default(), now, 30).unwrap();
+        assert!(feed.contains("<author>\n    <name>Task Manager</name>\n  </author>"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert!(feed.contains("<title>Completed: Fix &lt;login&gt; &amp; signup</title>"));
+        assert!(feed.contains("urn:task-manager:added:"));
+        assert!(feed.contains("Fix%20%3Clogin%3E%20%26%20signup"));
+        assert!(feed.find("Completed:").unwrap() < feed.find("Added:").unwrap());
+        assert!(!feed.contains("Old"));
+        assert_eq!(atom_feed(&tasks, &Query::default(), now, i64::MAX), None);
+        assert_eq!(atom_feed(&tasks, &Query::default(), now, 1 << 40), None);
+    }
+}
@@ -0,0 +1,488 @@
+//! Locale-aware formatting of dates, relative times and numbers, and themed priorities and
+//! statuses.
+//!
+//! Every command that prints dates or numbers goes through a `Formatter`, built from the
+//! `display` section of `config.json`, so output looks the same across list and report views.
+//!
+//! Glyphs such as `✔`, `◐` and `‼` are also chosen here: when the locale does not use UTF-8, or
+//! with `--ascii` or `"ascii": true`, they are replaced by ASCII ones such as `[x]`, `[~]` and `!!`.
+
+use crate::calendar::Calendar;
+use crate::i18n::t;
+use crate::platform;
+use crate::table::Column;
+use crate::theme::{self, Theme, ThemeConfig};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::{self, IsTerminal};
+
+/// Priorities from which a task is marked as urgent.
+const HIGH_PRIORITY: u8 = 4;
+
+/// `DisplayConfig`: The `display` section of `config.json`.
+///
+/// For example `{ "locale": "de-DE", "timezone": "Europe/Berlin", "week_start": "monday",
+/// "theme": "colorblind", "ascii": true }`. The locale falls back to `LC_ALL`, `LC_TIME` or
+/// `LANG`, the timezone to the system timezone, the week start to the locale's convention and
+/// `ascii` to whether the terminal's locale uses UTF-8. See the `theme` module for the themes.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub week_start: Option<WeekStart>,
+    pub theme: Option<ThemeConfig>,
+    pub ascii: Option<bool>,
+}
+
+/// `WeekStart`: The first day of the week in calendar-like output.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+}
+
+/// `Locale`: The locales with their own date and number conventions. Anything else is shown
+/// like `en-US`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    EnUs,
+    EnGb,
+    De,
+    Fr,
+    Es,
+}
+
+impl Locale {
+    /// Parses a locale tag such as `de-DE`, `en_GB.UTF-8` or `fr`.
+    pub fn from_tag(tag: &str) -> Locale {
+        let tag = tag.split('.').next().unwrap_or_default().replace('_', "-");
+        let mut parts = tag.split('-');
+        let language = parts.next().unwrap_or_default().to_lowercase();
+        let region = parts.next().unwrap_or_default().to_uppercase();
+        match (language.as_str(), region.as_str()) {
+            ("en", "US") | ("en", "") => Locale::EnUs,
+            ("en", _) => Locale::EnGb,
+            ("de", _) => Locale::De,
+            ("fr", _) => Locale::Fr,
+            ("es", _) => Locale::Es,
+            _ => Locale::EnUs,
+        }
+    }
+
+    /// The locale configured in the display settings, or else the one from the environment.
+    pub fn resolve(config: &DisplayConfig) -> Locale {
+        match &config.locale {
+            Some(tag) => Locale::from_tag(tag),
+            None => Locale::from_env(),
+        }
+    }
+
+    /// Reads the locale from the environment, the way the C library would for dates.
+    fn from_env() -> Locale {
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .map(|value| Locale::from_tag(&value))
+            .unwrap_or(Locale::EnUs)
+    }
+
+    fn date_pattern(self) -> &'static str {
+        match self {
+            Locale::EnUs => "%m/%d/%Y",
+            Locale::EnGb | Locale::Fr | Locale::Es => "%d/%m/%Y",
+            Locale::De => "%d.%m.%Y",
+        }
+    }
+
+    fn time_pattern(self) -> &'static str {
+        match self {
+            Locale::EnUs => "%I:%M %p",
+            _ => "%H:%M",
+        }
+    }
+
+    fn decimal_separator(self) -> char {
+        match self {
+            Locale::EnUs | Locale::EnGb => '.',
+            Locale::De | Locale::Fr | Locale::Es => ',',
+        }
+    }
+
+    fn week_start(self) -> WeekStart {
+        match self {
+            Locale::EnUs => WeekStart::Sunday,
+            _ => WeekStart::Monday,
+        }
+    }
+}
+
+/// `Formatter`: Formats dates, relative times, numbers, priorities and statuses for display.
+#[derive(Debug)]
+pub struct Formatter {
+    locale: Locale,
+    timezone: Option<Tz>,
+    week_start: WeekStart,
+    theme: Theme,
+    /// Whether to color output, i.e. it goes to a terminal and `NO_COLOR` is not set.
+    color: bool,
+    /// Whether to show ASCII glyphs instead of Unicode ones.
+    ascii: bool,
+    /// The working days, for due dates given in working days.
+    calendar: Calendar,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Formatter {
+            locale: Locale::EnUs,
+            timezone: None,
+            week_start: WeekStart::Monday,
+            theme: Theme::default(),
+            color: false,
+            ascii: false,
+            calendar: Calendar::default(),
+        }
+    }
+}
+
+impl Formatter {
+    /// Builds a formatter from the display settings, failing on an unknown timezone or theme.
+    pub fn new(config: &DisplayConfig) -> Result<Formatter, String> {
+        let locale = Locale::resolve(config);
+        let timezone = match &config.timezone {
+            Some(name) => Some(
+                name.parse::<Tz>()
+                    .map_err(|_| t!("unknown-timezone", name = name.as_str()))?,
+            ),
+            None => None,
+        };
+        Ok(Formatter {
+            locale,
+            timezone,
+            week_start: config.week_start.unwrap_or(locale.week_start()),
+            theme: Theme::new(config.theme.as_ref())?,
+            color: io::stdout().is_terminal()
+                && env::var_os("NO_COLOR").is_none()
+                && platform::enable_ansi(),
+            ascii: config.ascii.unwrap_or_else(|| !unicode_supported()),
+            calendar: Calendar::default(),
+        })
+    }
+
+    /// Uses a calendar of working days other than Monday to Friday.
+    pub fn with_calendar(self, calendar: Calendar) -> Formatter {
+        Formatter { calendar, ..self }
+    }
+
+    /// The working days (see the `calendar` module).
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// The current date in the configured timezone.
+    pub fn today(&self) -> NaiveDate {
+        self.local_date(Utc::now())
+    }
+
+    /// The date of an instant in the configured timezone.
+    pub fn local_date(&self, instant: DateTime<Utc>) -> NaiveDate {
+        match self.timezone {
+            Some(tz) => instant.with_timezone(&tz).date_naive(),
+            None => instant.with_timezone(&Local).date_naive(),
+        }
+    }
+
+    /// The date and time of an instant in the configured timezone.
+    pub fn local_datetime(&self, instant: DateTime<Utc>) -> NaiveDateTime {
+        match self.timezone {
+            Some(tz) => instant.with_timezone(&tz).naive_local(),
+            None => instant.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// The instant of a local time in the configured timezone. A time that occurs twice when the
+    /// clocks go back is taken the first time; one skipped when they go forward is `None`.
+    pub fn utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self.timezone {
+            Some(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|instant| instant.with_timezone(&Utc)),
+            None => Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|instant| instant.with_timezone(&Utc)),
+        }
+    }
+
+    /// The first day of the week.
+    pub fn week_start(&self) -> Weekday {
+        match self.week_start {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+        }
+    }
+
+    /// Formats a date, e.g. `07/01/2024` or `01.07.2024`.
+    pub fn date(&self, date: NaiveDate) -> String {
+        date.format(self.locale.date_pattern()).to_string()
+    }
+
+    /// Formats an instant as a date and time in the configured timezone.
+    pub fn datetime(&self, instant: DateTime<Utc>) -> String {
+        let pattern = format!(
+            "{} {}",
+            self.locale.date_pattern(),
+            self.locale.time_pattern()
+        );
+        match self.timezone {
+            Some(tz) => instant.with_timezone(&tz).format(&pattern).to_string(),
+            None => instant.with_timezone(&Local).format(&pattern).to_string(),
+        }
+    }
+
+    /// Describes a due date relative to today, e.g. `due in 3 days` or `overdue by 1 day`.
+    pub fn relative_due(&self, due: NaiveDate, today: NaiveDate) -> String {
+        match (due - today).num_days() {
+            0 => t!("due-today"),
+            1 => t!("due-tomorrow"),
+            d if d > 1 => t!("due-in", days = d),
+            d => t!("overdue-by", days = -d),
+        }
+    }
+
+    /// Describes a due time relative to now, in minutes or hours within a day of it and else like
+    /// `relative_due`. Comparing instants keeps this right across DST changes.
+    pub fn relative_due_at(&self, due_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        let left = due_at - now;
+        match left.num_minutes() {
+            minutes @ 0..60 => t!("due-in-minutes", minutes = minutes),
+            minutes @ 60..1440 => t!("due-in-hours", hours = minutes / 60),
+            minutes @ -59..0 => t!("overdue-by-minutes", minutes = -minutes),
+            minutes @ -1439..-59 => t!("overdue-by-hours", hours = -minutes / 60),
+            _ => self.relative_due(self.local_date(due_at), self.local_date(now)),
+        }
+    }
+
+    /// Formats a number with up to `decimals` decimals, dropping trailing zeros.
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value);
+        let formatted = if formatted.contains('.') {
+            formatted.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &formatted
+        };
+        formatted.replace('.', &self.locale.decimal_separator().to_string())
+    }
+
+    /// Shows text, such as a task title, in the style of a priority.
+    pub fn priority(&self, priority: u8, text: &str) -> String {
+        self.paint(text, self.theme.priority(priority))
+    }
+
+    /// Shows a status with its glyph, in its style.
+    pub fn status(&self, status: &str) -> String {
+        self.paint_status(status, &self.status_label(status))
+    }
+
+    /// A status with its glyph, without styling.
+    pub fn status_label(&self, status: &str) -> String {
+        let glyph = self.theme.status(status).map_or("", |style| {
+            if !self.ascii || style.glyph.is_ascii() {
+                &style.glyph
+            } else {
+                &style.ascii
+            }
+        });
+        if glyph.is_empty() {
+            status.to_string()
+        } else {
+            format!("{} {}", glyph, status)
+        }
+    }
+
+    /// A priority, marked with `‼` when it is high.
+    pub fn priority_label(&self, priority: u8) -> String {
+        if priority >= HIGH_PRIORITY {
+            format!("{} {}", self.glyph("‼", "!!"), priority)
+        } else {
+            priority.to_string()
+        }
+    }
+
+    /// Picks a Unicode glyph, or its ASCII fallback.
+    pub fn glyph<'a>(&self, unicode: &'a str, ascii: &'a str) -> &'a str {
+        if self.ascii {
+            ascii
+        } else {
+            unicode
+        }
+    }
+
+    /// Shows text, such as a padded status label, in the style of a status.
+    pub fn paint_status(&self, status: &str, text: &str) -> String {
+        match self.theme.status(status) {
+            Some(style) => self.paint(text, &style.color),
+            None => text.to_string(),
+        }
+    }
+
+    /// Shows text that needs attention, such as a column over its limit.
+    pub fn alert(&self, text: &str) -> String {
+        self.paint(text, "bold red")
+    }
+
+    /// The table columns of the theme.
+    pub fn columns(&self) -> &[Column] {
+        self.theme.columns()
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.color {
+            theme::paint(text, style)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Whether the terminal can show Unicode, judging by the character set of its locale.
+fn unicode_supported() -> bool {
+    match ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+    {
+        Some(value) => {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        }
+        // Windows terminals do not set a locale, but handle Unicode
+        None => cfg!(windows),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatter(locale: &str) -> Formatter {
+        Formatter::new(&DisplayConfig {
+            locale: Some(String::from(locale)),
+            timezone: Some(String::from("Europe/Berlin")),
+            week_start: None,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), Locale::De);
+        assert_eq!(Locale::from_tag("en-GB"), Locale::EnGb);
+        assert_eq!(Locale::from_tag("en"), Locale::EnUs);
+        assert_eq!(Locale::from_tag("ja_JP"), Locale::EnUs);
+    }
+
+    #[test]
+    fn test_dates_and_numbers() {
+        let date = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let instant = date.and_hms_opt(15, 30, 0).unwrap().and_utc();
+
+        let us = formatter("en-US");
+        assert_eq!(us.date(date), "07/01/2024");
+        assert_eq!(us.datetime(instant), "07/01/2024 05:30 PM");
+        assert_eq!(us.number(2.50, 2), "2.5");
+        assert_eq!(us.week_start(), Weekday::Sun);
+
+        let de = formatter("de-DE");
+        assert_eq!(de.date(date), "01.07.2024");
+        assert_eq!(de.datetime(instant), "01.07.2024 17:30");
+        assert_eq!(de.number(2.25, 2), "2,25");
+        assert_eq!(de.number(3.0, 2), "3");
+        assert_eq!(de.week_start(), Weekday::Mon);
+
+        assert!(Formatter::new(&DisplayConfig {
+            timezone: Some(String::from("Mars/Olympus")),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_glyphs() {
+        let unicode = Formatter::default();
+        assert_eq!(unicode.status_label("Done"), "✔ Done");
+        assert_eq!(unicode.status_label("Waiting"), "Waiting");
+        assert_eq!(unicode.priority_label(5), "‼ 5");
+        assert_eq!(unicode.priority_label(2), "2");
+
+        let ascii = Formatter {
+            ascii: true,
+            ..Default::default()
+        };
+        assert_eq!(ascii.status_label("Done"), "[x] Done");
+        assert_eq!(ascii.status_label("In Progress"), "[~] In Progress");
+        assert_eq!(ascii.priority_label(4), "!! 4");
+        assert_eq!(ascii.glyph("…", "..."), "...");
+    }
+
+    #[test]
+    fn test_due_times() {
+        let berlin = formatter("en-GB");
+        // The clocks go forward from 02:00 to 03:00 on 2024-03-31, and back on 2024-10-27
+        let local = |d: u32, m: u32, h: u32| {
+            NaiveDate::from_ymd_opt(2024, m, d)
+                .unwrap()
+                .and_hms_opt(h, 30, 0)
+                .unwrap()
+        };
+        assert_eq!(
+            berlin.utc(local(30, 3, 17)).unwrap().to_rfc3339(),
+            "2024-03-30T16:30:00+00:00"
+        );
+        assert_eq!(
+            berlin.utc(local(31, 3, 17)).unwrap().to_rfc3339(),
+            "2024-03-31T15:30:00+00:00"
+        );
+        assert!(berlin.utc(local(31, 3, 2)).is_none());
+        assert_eq!(
+            berlin.utc(local(27, 10, 2)).unwrap().to_rfc3339(),
+            "2024-10-27T00:30:00+00:00"
+        );
+
+        // Across the change, 01:00 to 05:00 local time is three hours
+        let due_at = berlin.utc(local(31, 3, 5)).unwrap();
+        let now = berlin.utc(local(31, 3, 1)).unwrap();
+        assert_eq!(berlin.relative_due_at(due_at, now), "due in 3 hours");
+        assert_eq!(berlin.relative_due_at(now, due_at), "overdue by 3 hours");
+        assert_eq!(
+            berlin.relative_due_at(due_at, due_at - chrono::Duration::minutes(5)),
+            "due in 5 minutes"
+        );
+        assert_eq!(
+            berlin.relative_due_at(due_at, due_at - chrono::Duration::days(2)),
+            "due in 2 days"
+        );
+    }
+
+    #[test]
+    fn test_relative_due() {
+        let fmt = Formatter::default();
+        let today = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let relative =
+            |day| fmt.relative_due(NaiveDate::from_ymd_opt(2024, 7, day).unwrap(), today);
+        assert_eq!(relative(1), "due today");
+        assert_eq!(relative(2), "due tomorrow");
+        assert_eq!(relative(4), "due in 3 days");
+        assert_eq!(
+            fmt.relative_due(NaiveDate::from_ymd_opt(2024, 6, 30).unwrap(), today),
+            "overdue by 1 day"
+        );
+    }
+}
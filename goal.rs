@@ -0,0 +1,99 @@
+//! Goals and key results.
+//!
+//! Goals are objectives, such as a quarter's OKRs, kept in `goals.json` next to the tasks. Tasks
+//! link to a goal, and optionally to one of its key results, with `--goal` and `--key-result` on
+//! `add` and `update`. `goal progress` then tells how far along each goal is from its finished
+//! tasks, weighted by their estimates.
+
+use crate::{is_done, Task};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::io;
+
+const GOALS_FILE: &str = "goals.json";
+
+/// `Goal`: An objective and the key results that measure it.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct Goal {
+    pub name: String,
+    /// The period the goal is set for, such as `2024-Q3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_results: Vec<String>,
+}
+
+/// The share of the work on some tasks that is done, from 0 to 1, weighing each task by its
+/// estimate. Tasks without an estimate count as much as the average estimated one. `None` when
+/// there are no tasks.
+pub fn progress(tasks: &[&Task]) -> Option<f64> {
+    if tasks.is_empty() {
+        return None;
+    }
+    let estimates: Vec<f64> = tasks.iter().filter_map(|task| task.estimate).collect();
+    let default = if estimates.iter().sum::<f64>() > 0.0 {
+        estimates.iter().sum::<f64>() / estimates.len() as f64
+    } else {
+        1.0
+    };
+    let weight = |task: &&Task| task.estimate.unwrap_or(default);
+    let total: f64 = tasks.iter().map(weight).sum();
+    if total == 0.0 {
+        // Only tasks estimated at nothing; count them instead
+        let done = tasks.iter().filter(|task| is_done(&task.status)).count();
+        return Some(done as f64 / tasks.len() as f64);
+    }
+    let done: f64 = tasks
+        .iter()
+        .filter(|task| is_done(&task.status))
+        .map(weight)
+        .sum();
+    Some(done / total)
+}
+
+/// Loads the goals, of which there are none before the first is added.
+pub fn load() -> Result<Vec<Goal>, Box<dyn Error>> {
+    let contents = match fs::read_to_string(GOALS_FILE) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Saves the goals.
+pub fn save(goals: &[Goal]) -> Result<(), Box<dyn Error>> {
+    fs::write(GOALS_FILE, serde_json::to_string_pretty(goals)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let task = |status: &str, estimate: Option<f64>| Task {
+            status: String::from(status),
+            estimate,
+            ..Default::default()
+        };
+        let tasks = [
+            task("Done", Some(6.0)),
+            task("Todo", Some(2.0)),
+            task("Done", None),
+            task("In Progress", None),
+        ];
+        // The unestimated tasks weigh 4 hours each: (6 + 4) / (6 + 2 + 4 + 4)
+        let all: Vec<&Task> = tasks.iter().collect();
+        assert_eq!(progress(&all), Some(10.0 / 16.0));
+        assert_eq!(progress(&all[2..]), Some(0.5));
+        assert_eq!(progress(&[]), None);
+        let zero = [task("Done", Some(0.0)), task("Todo", Some(0.0))];
+        assert_eq!(progress(&zero.iter().collect::<Vec<_>>()), Some(0.5));
+    }
+}
@@ -0,0 +1,155 @@
+//! Dependency graphs.
+//!
+//! `export --format dot` and `export --format mermaid` write the dependencies between tasks as a
+//! graph, to render a visual plan with Graphviz or paste into a Markdown document. Edges point
+//! from a task to the tasks waiting on it, finished tasks are greyed out, and dependencies on
+//! tasks left out of the export are drawn dashed.
+
+use crate::query::Query;
+use crate::{is_done, Task};
+
+/// A task in the graph, or a dependency that is not a task (any more).
+struct Node<'a> {
+    title: &'a str,
+    task: Option<&'a Task>,
+    /// Set for dependencies on tasks left out of the export.
+    external: bool,
+}
+
+/// Collects the tasks chosen by a query and the tasks they depend on, plus the edges between them
+/// as indexes into the nodes.
+fn graph<'a>(tasks: &'a [Task], query: &Query) -> (Vec<Node<'a>>, Vec<(usize, usize)>) {
+    let mut nodes: Vec<Node> = tasks
+        .iter()
+        .filter(|task| query.matches(task))
+        .map(|task| Node {
+            title: &task.title,
+            task: Some(task),
+            external: false,
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for index in 0..nodes.len() {
+        let Some(task) = nodes[index].task else {
+            continue;
+        };
+        for dependency in &task.depends_on {
+            let from = match nodes.iter().position(|node| node.title == *dependency) {
+                Some(from) => from,
+                None => {
+                    nodes.push(Node {
+                        title: dependency,
+                        task: tasks.iter().find(|other| other.title == *dependency),
+                        external: true,
+                    });
+                    nodes.len() - 1
+                }
+            };
+            edges.push((from, index));
+        }
+    }
+    (nodes, edges)
+}
+
+/// Renders the dependency graph in Graphviz's DOT language.
+pub fn dot(tasks: &[Task], query: &Query) -> String {
+    let (nodes, edges) = graph(tasks, query);
+    let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = String::from("digraph tasks {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (index, node) in nodes.iter().enumerate() {
+        let mut attributes = vec![format!("label=\"{}\"", escape(&label(node)))];
+        let mut styles = Vec::new();
+        if node.task.is_some_and(|task| is_done(&task.status)) {
+            styles.push("filled");
+            attributes.push(String::from("fillcolor=lightgrey, fontcolor=grey40"));
+        }
+        if node.external {
+            styles.push("dashed");
+        }
+        if !styles.is_empty() {
+            attributes.push(format!("style=\"{}\"", styles.join(",")));
+        }
+        out.push_str(&format!("    n{} [{}];\n", index, attributes.join(", ")));
+    }
+    for (from, to) in edges {
+        out.push_str(&format!("    n{} -> n{};\n", from, to));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Renders the dependency graph as a Mermaid flowchart.
+pub fn mermaid(tasks: &[Task], query: &Query) -> String {
+    let (nodes, edges) = graph(tasks, query);
+    let escape = |text: &str| text.replace('"', "#quot;");
+    let mut out = String::from("flowchart LR\n");
+    for (index, node) in nodes.iter().enumerate() {
+        out.push_str(&format!("    n{}[\"{}\"]\n", index, escape(&label(node))));
+    }
+    for (from, to) in edges {
+        out.push_str(&format!("    n{} --> n{}\n", from, to));
+    }
+    let class = |keep: &dyn Fn(&Node) -> bool| -> Vec<String> {
+        let ids = nodes.iter().enumerate().filter(|(_, node)| keep(node));
+        ids.map(|(index, _)| format!("n{}", index)).collect()
+    };
+    let done = class(&|node| node.task.is_some_and(|task| is_done(&task.status)));
+    if !done.is_empty() {
+        out.push_str("    classDef done fill:#ddd,color:#666\n");
+        out.push_str(&format!("    class {} done\n", done.join(",")));
+    }
+    let external = class(&|node| node.external);
+    if !external.is_empty() {
+        out.push_str("    classDef external stroke-dasharray:5 5\n");
+        out.push_str(&format!("    class {} external\n", external.join(",")));
+    }
+    out
+}
+
+/// The title of a node, with the status of the task if there is one.
+fn label(node: &Node) -> String {
+    match node.task {
+        Some(task) => format!("{} ({})", node.title, task.status),
+        None => node.title.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphs() {
+        let task = |title: &str, project: &str, status: &str, depends_on: &[&str]| Task {
+            title: String::from(title),
+            project: String::from(project),
+            status: String::from(status),
+            depends_on: depends_on.iter().map(|dep| dep.to_string()).collect(),
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("Design", "Website", "Done", &[]),
+            task("Build \"v2\"", "Website", "Todo", &["Design", "Hosting"]),
+            task("Hosting", "Ops", "Todo", &[]),
+            task("Unrelated", "Ops", "Todo", &[]),
+        ];
+
+        let website = Query {
+            project: Some(String::from("Website")),
+            ..Default::default()
+        };
+        let dot = dot(&tasks, &website);
+        assert!(dot.contains("n1 [label=\"Build \\\"v2\\\" (Todo)\"];"));
+        assert!(dot.contains("n0 -> n1;"));
+        assert!(dot.contains("n2 [label=\"Hosting (Todo)\", style=\"dashed\"];"));
+        assert!(dot.contains("n2 -> n1;"));
+        assert!(!dot.contains("Unrelated"));
+
+        let mermaid = mermaid(&tasks, &website);
+        assert!(mermaid.contains("n1[\"Build #quot;v2#quot; (Todo)\"]"));
+        assert!(mermaid.contains("n0 --> n1"));
+        assert!(mermaid.contains("class n0 done"));
+        assert!(mermaid.contains("class n2 external"));
+    }
+}
@@ -0,0 +1,157 @@
+//! Habits.
+//!
+//! Habits are recurring routines such as exercising, kept in `habits.json` next to the tasks.
+//! Unlike a task, a habit is never finished: `habit done` records that it was kept in the
+//! current day or week, and `habit streaks` reports how many periods in a row it has been kept.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::io;
+
+const HABITS_FILE: &str = "habits.json";
+
+/// `Frequency`: How often a habit is meant to be kept.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Day,
+    Week,
+}
+
+impl Frequency {
+    /// Parses a frequency as given to `--every`.
+    pub fn parse(every: &str) -> Option<Frequency> {
+        match every.to_lowercase().as_str() {
+            "day" | "daily" => Some(Frequency::Day),
+            "week" | "weekly" => Some(Frequency::Week),
+            _ => None,
+        }
+    }
+}
+
+/// `Habit`: A routine and the days it was kept on.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct Habit {
+    pub name: String,
+    pub every: Frequency,
+    #[serde(default)]
+    pub done: BTreeSet<NaiveDate>,
+}
+
+impl Habit {
+    /// The first day of the period a date falls in.
+    fn period(&self, date: NaiveDate, week_start: Weekday) -> NaiveDate {
+        match self.every {
+            Frequency::Day => date,
+            Frequency::Week => date - Duration::days(date.weekday().days_since(week_start) as i64),
+        }
+    }
+
+    fn previous_period(&self, period: NaiveDate) -> NaiveDate {
+        match self.every {
+            Frequency::Day => period - Duration::days(1),
+            Frequency::Week => period - Duration::weeks(1),
+        }
+    }
+
+    /// The periods the habit was kept in, oldest first.
+    fn kept_periods(&self, week_start: Weekday) -> BTreeSet<NaiveDate> {
+        self.done
+            .iter()
+            .map(|date| self.period(*date, week_start))
+            .collect()
+    }
+
+    /// Whether the habit was kept in the period of a date.
+    pub fn kept(&self, date: NaiveDate, week_start: Weekday) -> bool {
+        self.kept_periods(week_start)
+            .contains(&self.period(date, week_start))
+    }
+
+    /// The number of periods in a row the habit has been kept, up to today. A streak is not
+    /// broken before the current period is over.
+    pub fn current_streak(&self, today: NaiveDate, week_start: Weekday) -> usize {
+        let kept = self.kept_periods(week_start);
+        let mut period = self.period(today, week_start);
+        if !kept.contains(&period) {
+            period = self.previous_period(period);
+        }
+        let mut streak = 0;
+        while kept.contains(&period) {
+            streak += 1;
+            period = self.previous_period(period);
+        }
+        streak
+    }
+
+    /// The most periods in a row the habit was ever kept.
+    pub fn longest_streak(&self, week_start: Weekday) -> usize {
+        let mut longest = 0;
+        let mut streak = 0;
+        let mut last = None;
+        for period in self.kept_periods(week_start) {
+            streak = match last {
+                Some(last) if self.previous_period(period) == last => streak + 1,
+                _ => 1,
+            };
+            longest = longest.max(streak);
+            last = Some(period);
+        }
+        longest
+    }
+}
+
+/// Loads the habits, of which there are none before the first is added.
+pub fn load() -> Result<Vec<Habit>, Box<dyn Error>> {
+    let contents = match fs::read_to_string(HABITS_FILE) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Saves the habits.
+pub fn save(habits: &[Habit]) -> Result<(), Box<dyn Error>> {
+    fs::write(HABITS_FILE, serde_json::to_string_pretty(habits)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaks() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 7, d).unwrap();
+        let mut exercise = Habit {
+            name: String::from("Exercise"),
+            every: Frequency::Day,
+            done: [1, 2, 3, 5, 6].map(day).into(),
+        };
+        assert_eq!(exercise.longest_streak(Weekday::Mon), 3);
+        // Not done yet today, but the streak holds until the day is over
+        assert_eq!(exercise.current_streak(day(7), Weekday::Mon), 2);
+        assert_eq!(exercise.current_streak(day(8), Weekday::Mon), 0);
+        exercise.done.insert(day(7));
+        assert_eq!(exercise.current_streak(day(7), Weekday::Mon), 3);
+
+        // 2024-07-01 is a Monday; keeping a weekly habit twice in a week counts once
+        let review = Habit {
+            name: String::from("Review"),
+            every: Frequency::Week,
+            done: [1, 3, 9, 17].map(day).into(),
+        };
+        assert!(review.kept(day(21), Weekday::Mon));
+        assert!(!review.kept(day(21), Weekday::Sun));
+        assert_eq!(review.current_streak(day(24), Weekday::Mon), 3);
+        assert_eq!(Frequency::parse("Daily"), Some(Frequency::Day));
+        assert_eq!(Frequency::parse("monthly"), None);
+    }
+}
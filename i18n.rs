@@ -0,0 +1,129 @@
+//! Translations of user-facing messages.
+//!
+//! Messages live in Fluent files under `locales/`, one per language, and are looked up by id
+//! with the `t!` macro. The language follows the configured display locale (see the `format`
+//! module); messages missing from a translation fall back to English.
+
+use crate::format::Locale;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use std::sync::OnceLock;
+use unic_langid::{langid, LanguageIdentifier};
+
+const ENGLISH: &str = include_str!("locales/en.ftl");
+const GERMAN: &str = include_str!("locales/de.ftl");
+
+static CATALOG: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+static FALLBACK: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+/// Looks up a message by id, filling in its arguments, e.g. `t!("task-not-found")` or
+/// `t!("blocked-for", days = 3)`.
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::message($id, &[])
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message($id, &[$((stringify!($name), $value.into())),+])
+    };
+}
+pub(crate) use t;
+
+/// Selects the language messages are shown in. Only the first call has an effect; messages
+/// looked up before it are in English.
+pub fn init(locale: Locale) {
+    CATALOG.get_or_init(|| catalog(locale));
+}
+
+/// Builds the bundles to search, the selected language first and English last.
+fn catalog(locale: Locale) -> Vec<FluentBundle<FluentResource>> {
+    let mut bundles = Vec::new();
+    if locale == Locale::De {
+        bundles.push(bundle(langid!("de"), GERMAN));
+    }
+    bundles.push(bundle(langid!("en-US"), ENGLISH));
+    bundles
+}
+
+fn bundle(language: LanguageIdentifier, source: &str) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("Invalid {} translations: {:?}", language, errors));
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // Terminals don't need the Unicode isolation marks Fluent puts around arguments
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .expect("translation ids are unique");
+    bundle
+}
+
+/// Looks up a message by id in the selected language, falling back to English and finally to
+/// the id itself.
+pub fn message(id: &str, args: &[(&str, FluentValue)]) -> String {
+    let bundles = CATALOG
+        .get()
+        .unwrap_or_else(|| FALLBACK.get_or_init(|| catalog(Locale::EnUs)));
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+
+    for bundle in bundles {
+        if let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) {
+            let mut errors = Vec::new();
+            return bundle
+                .format_pattern(pattern, Some(&fluent_args), &mut errors)
+                .into_owned();
+        }
+    }
+    id.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(locale: Locale, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let bundles = catalog(locale);
+        let bundle = &bundles[0];
+        let pattern = bundle.get_message(id).unwrap().value().unwrap();
+        bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut vec![])
+            .into_owned()
+    }
+
+    #[test]
+    fn test_translations() {
+        assert_eq!(
+            lookup(Locale::EnUs, "due-in", &[("days", 3.into())]),
+            "due in 3 days"
+        );
+        assert_eq!(
+            lookup(Locale::De, "due-in", &[("days", 1.into())]),
+            "fällig in 1 Tag"
+        );
+        assert_eq!(
+            lookup(Locale::De, "task-not-found", &[]),
+            "Aufgabe nicht gefunden"
+        );
+    }
+
+    #[test]
+    fn test_translations_are_complete() {
+        let german = catalog(Locale::De);
+        let ids = ENGLISH
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split(" =").next());
+        for id in ids {
+            assert!(
+                german[0].has_message(id),
+                "missing German translation for {}",
+                id
+            );
+        }
+    }
+}
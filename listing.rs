@@ -0,0 +1,77 @@
+//! Task numbers from the last listing.
+//!
+//! Listings number their tasks, and `list`, `search`, `list-by-*` and `today` remember which task
+//! got which number in `listing.json`, so commands taking a task also take its number: after
+//! `list`, `done 3` marks the third task listed as Done. The numbers stay as they were until the
+//! next listing, even when tasks are added, removed or changed in between, so a number always
+//! means the task shown with it. Tasks are remembered by slug (see the `slug` module).
+
+use crate::Task;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+const LISTING_FILE: &str = "listing.json";
+
+thread_local! {
+    /// The tasks shown so far, by number.
+    static SHOWN: RefCell<BTreeMap<usize, String>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Notes the number a task is shown with.
+pub fn show(number: usize, task: &Task) {
+    let reference = task.slug.clone().unwrap_or_else(|| task.title.clone());
+    SHOWN.with(|shown| shown.borrow_mut().insert(number, reference));
+}
+
+/// Remembers the numbers shown by a listing, replacing the last listing.
+pub fn save() -> Result<(), Box<dyn Error>> {
+    save_to(Path::new(LISTING_FILE))
+}
+
+fn save_to(path: &Path) -> Result<(), Box<dyn Error>> {
+    let shown = SHOWN.with(|shown| shown.take());
+    fs::write(path, serde_json::to_string(&shown)?)?;
+    Ok(())
+}
+
+/// The slug or title of the task shown with a number in the last listing.
+pub fn lookup(number: usize) -> Option<String> {
+    lookup_in(Path::new(LISTING_FILE), number)
+}
+
+fn lookup_in(path: &Path, number: usize) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let mut shown: BTreeMap<usize, String> = serde_json::from_str(&contents).ok()?;
+    shown.remove(&number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listing() {
+        let path = std::env::temp_dir().join(format!("listing-test-{}.json", std::process::id()));
+        let task = |title: &str, slug: Option<&str>| Task {
+            title: title.to_string(),
+            slug: slug.map(String::from),
+            ..Default::default()
+        };
+        show(1, &task("Fix login bug", Some("fix-login-bug")));
+        show(2, &task("Write docs", None));
+        save_to(&path).unwrap();
+        assert_eq!(lookup_in(&path, 1).as_deref(), Some("fix-login-bug"));
+        assert_eq!(lookup_in(&path, 2).as_deref(), Some("Write docs"));
+        assert_eq!(lookup_in(&path, 3), None);
+
+        // A new listing replaces the last one
+        show(1, &task("Deploy", Some("deploy")));
+        save_to(&path).unwrap();
+        assert_eq!(lookup_in(&path, 1).as_deref(), Some("deploy"));
+        assert_eq!(lookup_in(&path, 2), None);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,479 @@
+# German messages.
+
+## Errors
+
+error = Fehler: { $message }
+warning = Warnung: { $message }
+config-read-failed = config.json konnte nicht gelesen werden: { $error }
+data-dir-failed = Das Datenverzeichnis konnte nicht geöffnet werden: { $error }
+unknown-timezone = Unbekannte Zeitzone '{ $name }'
+invalid-command = Ungültiger Befehl
+unknown-command = Unbekannter Befehl '{ $name }'. Plugins werden aus einem Programm `task-manager-{ $name }` im PATH gestartet.
+unknown-theme = Unbekanntes Farbschema '{ $name }', erwartet: default, high-contrast, colorblind oder plain
+unknown-style = Unbekannte Farbe oder Auszeichnung '{ $style }'
+unknown-column = Unbekannte Spalte '{ $name }', erwartet: id, title, status, priority, project, due, tags, description, revision, slug, urgency, age, days-until-due oder blocked
+unknown-field = Unbekanntes berechnetes Feld '{ $name }', erwartet urgency, age, days-until-due oder blocked
+invalid-condition = Ungültige Bedingung '{ $condition }', erwartet ein Feld, einen Vergleich und eine Zahl wie urgency>=6
+this-store = dieser Speicher
+store-heading = { $name }:
+store-failed = Der Speicher { $name } konnte nicht gelesen werden: { $error }
+invalid-remote = Ungültiger entfernter Speicher '{ $url }', erwartet z. B. ssh://host/pfad/tasks.json
+remote-needs-json = Nur der JSON-Speicher kann auf einem Server liegen
+encryption-needs-json = Nur der JSON-Speicher kann verschlüsselt werden: encryption_key aus der config.json entfernen oder zum JSON-Speicher wechseln
+remote-ssh-failed = ssh konnte nicht ausgeführt werden: { $error }
+remote-read-failed = Der Speicher konnte nicht von { $host } gelesen werden (ssh endete mit { $status })
+remote-write-failed = Der Speicher konnte nicht nach { $host } geschrieben werden (ssh endete mit { $status })
+remote-locked = Der Speicher auf { $host } ist durch einen anderen Schreibvorgang gesperrt; bitte erneut versuchen
+remote-changed = Der entfernte Speicher wurde seit dem Lesen von jemand anderem geändert, daher wurde nichts gespeichert. Die Änderung wurde aufbewahrt: mit `sync conflicts` zusammenführen
+s3-no-credentials = Keine S3-Zugangsdaten: access_key und secret_key im Abschnitt s3 der config.json oder { $var } setzen
+s3-unsupported = Dieser Build unterstützt S3 nicht
+remote-unreachable = Der entfernte Speicher ist nicht erreichbar: { $error }
+remote-offline = Es wird offline mit der zuletzt gelesenen Kopie des entfernten Speichers gearbeitet. { $error }
+remote-queued = Lokal gespeichert; mit `sync flush` werden die Änderungen an den entfernten Speicher gesendet
+nothing-to-flush = Es warten keine Änderungen darauf, gesendet zu werden.
+no-remote = Es ist kein entfernter Speicher eingerichtet.
+flushed = Die offline vorgenommenen Änderungen wurden an den entfernten Speicher gesendet.
+key-generated = Neuer Schlüssel in { $path } gespeichert. "encryption_key": "{ $path }" in der config.json setzen, um den Speicher damit zu verschlüsseln, und die Datei auf jedes Gerät kopieren, das den Speicher nutzt: Ohne sie ist der Speicher nicht lesbar.
+key-exists = { $path } existiert bereits; die Datei erst entfernen, wenn wirklich ein neuer Schlüssel gewünscht ist, denn mit dem alten verschlüsselte Speicher sind dann nicht mehr lesbar
+key-read-failed = Der Schlüssel konnte nicht aus { $path } gelesen werden: { $error }
+key-invalid = { $path } enthält keinen Schlüssel; mit `key generate` einen erstellen
+store-encrypted = Der Speicher ist verschlüsselt; encryption_key in der config.json auf die Datei mit seinem Schlüssel setzen
+store-not-encrypted = Der Speicher ist nicht verschlüsselt, obwohl encryption_key gesetzt ist; wurde er vor dem Setzen des Schlüssels gespeichert, einmal `key encrypt` ausführen, um ihn zu verschlüsseln
+crdt-unsupported-value = Nicht unterstützter Wert im Aufgabenspeicher: { $value }
+no-encryption-key = Zuerst encryption_key in der config.json auf die Datei mit dem Schlüssel setzen, z. B. einem mit `key generate` erstellten
+store-encrypted-now = { $count ->
+    [one] Speicher mit { $count } Aufgabe verschlüsselt
+   *[other] Speicher mit { $count } Aufgaben verschlüsselt
+}
+store-decrypt-failed = Der Speicher konnte nicht entschlüsselt werden: Er wurde mit einem anderen Schlüssel verschlüsselt oder ist beschädigt
+store-changed-on-disk = Der Speicher wurde seit dem Lesen von einem anderen Programm geändert, daher wurde nichts gespeichert. Die Änderung wurde aufbewahrt: mit `sync conflicts` zusammenführen
+store-changed-prompt = Der Speicher wurde seit dem Lesen von einem anderen Programm geändert. Änderungen zusammenführen, Speicher überschreiben oder abbrechen? [z/ü/a]
+store-changed-merge-answers = z, zusammenführen
+store-changed-overwrite-answers = ü, überschreiben
+store-changed-abort-answers = a, abbrechen
+store-record-skipped = Eintrag { $record } (Zeile { $line }) des Speichers übersprungen, er ist keine gültige Aufgabe: { $error }
+store-has-malformed = Der Speicher enthält Einträge, die keine gültigen Aufgaben sind, daher wurde er nicht überschrieben. Mit `repair` lassen sie sich nach tasks.rejected.json verschieben
+store-valid = Jeder Eintrag des Speichers ist eine gültige Aufgabe.
+store-repaired = { $count ->
+    [one] Ein Eintrag wurde
+   *[other] { $count } Einträge wurden
+} nach { $file } verschoben.
+no-conflicts = Es gibt keine widersprüchlichen Änderungen aufzulösen.
+conflicts-need-terminal = Beim Auflösen von Konflikten wird gefragt, welche Änderungen bleiben sollen, dafür ist ein Terminal nötig
+conflicts-resolved = Die widersprüchlichen Änderungen wurden zusammengeführt und gespeichert.
+conflict-field = "{ $title }": { $field } wurde auf beiden Seiten geändert.
+conflict-local = lokal:
+conflict-remote = entfernt:
+conflict-prompt = Lokal oder entfernt behalten? [l/e, q zum Beenden]
+conflict-local-answers = l, lokal
+conflict-remote-answers = e, entfernt
+auth-s3-access-key = S3-Zugriffsschlüssel:
+auth-s3-secret-key = S3-Geheimschlüssel:
+auth-smtp-password = SMTP-Passwort:
+auth-logged-in = Die Zugangsdaten für { $service } liegen jetzt im Schlüsselbund.
+auth-cleared-on-restart = Der Kernel-Schlüsselbund vergisst sie beim Neustart des Rechners; dann `auth login { $service }` erneut ausführen.
+keyring-unsupported = Dieser Build unterstützt den Schlüsselbund nicht
+auth-logged-out = Die Zugangsdaten für { $service } wurden aus dem Schlüsselbund entfernt.
+auth-not-logged-in = Der Schlüsselbund enthält keine Zugangsdaten für { $service }.
+auth-failed = Der Schlüsselbund konnte nicht verwendet werden: { $error }
+s3-failed = S3 hat die Anfrage abgelehnt (Status { $status }{ $code ->
+    [none] {""}
+   *[other] , { $code }
+})
+column-id = #
+column-title = Titel
+column-status = Status
+column-priority = Priorität
+column-project = Projekt
+column-due = Fällig
+column-tags = Tags
+column-description = Beschreibung
+column-revision = Rev.
+column-slug = Kürzel
+column-urgency = Dringlichkeit
+column-age = Alter
+column-days-until-due = Tage übrig
+column-blocked = Blockiert
+unknown-filter = Kein WASM-Plugin stellt den Filter '{ $name }' bereit
+wasm-plugins-failed = WASM-Plugins konnten nicht geladen werden: { $error }
+wasm-plugins-unsupported = Dieser Build unterstützt keine WASM-Plugins
+clipboard-failed = Die Zwischenablage konnte nicht gelesen werden: { $error }
+clipboard-empty = Die Zwischenablage enthält keinen Text für eine Aufgabe
+clipboard-unsupported = Dieser Build unterstützt die Zwischenablage nicht
+task-not-found = Aufgabe nicht gefunden
+task-ambiguous = { $count ->
+    [one] '{ $reference }' passt auf keine Aufgabe; gemeint ist vielleicht { $titles }?
+   *[other] '{ $reference }' passt auf mehrere Aufgaben: { $titles }
+}
+task-candidates = { $count ->
+    [one] '{ $reference }' passt auf keine Aufgabe; gemeint ist vielleicht:
+   *[other] '{ $reference }' passt auf mehrere Aufgaben:
+}
+task-choose-prompt = Welche Aufgabe? [Nummer, q zum Beenden]
+task-number-not-listed = Keine Aufgabe wurde als Nummer { $number } aufgelistet; zuerst list ausführen
+save-failed = Aufgaben konnten nicht gespeichert werden
+invalid-hours = Ungültige Anzahl Stunden
+invalid-due-date = Ungültiges Fälligkeitsdatum, erwartet JJJJ-MM-TT
+invalid-due = Ungültiges Fälligkeitsdatum, erwartet JJJJ-MM-TT, JJJJ-MM-TT HH:MM oder eine Zahl von Arbeitstagen wie 3bd
+calendar-no-working-days = Der Kalender lässt keinen Wochentag zum Arbeiten übrig
+calendar-file-failed = Die Feiertagsdatei { $file } konnte nicht gelesen werden: { $error }
+nonexistent-due-time = Diese Uhrzeit gibt es in der Zeitzone nicht, weil die Uhren dann vorgestellt werden
+capture-no-title = Die Aufgabe braucht neben +Projekt, @Kontexten, #Tags, p: und due: einen Titel
+capture-invalid-priority = Ungültige Priorität "{ $priority }"; erwartet wird eine Zahl, z. B. p:4
+capture-invalid-due = Ungültiges Fälligkeitsdatum "{ $due }"; erwartet wird today, tomorrow, ein Wochentag, Arbeitstage wie 3bd oder JJJJ-MM-TT
+invalid-url = Ungültige URL; erwartet wird eine, die mit http://, https://, file:// oder mailto: beginnt
+revision-conflict = Die Aufgabe wurde zwischenzeitlich geändert: Sie ist bei Revision { $revision }, nicht { $expected }
+invalid-revision = Ungültige Revision; erwartet wird eine ganze Zahl
+no-url = Die Aufgabe hat keine URL, und ihre Beschreibung enthält auch keine
+open-failed = { $url } konnte nicht geöffnet werden: { $error }
+invalid-priority = Ungültige Priorität
+invalid-priority-value = Ungültige Priorität. Bitte eine ganze Zahl angeben.
+invalid-max-priority = Ungültige Höchstpriorität. Bitte eine ganze Zahl zwischen 1 und 255 angeben.
+invalid-days = Ungültige Anzahl Tage, erwartet eine positive ganze Zahl
+dependency-not-found = Abhängigkeit nicht gefunden
+dependency-cycle = Abhängigkeit würde einen Zyklus erzeugen
+missing-project-option = Bitte mit der Option --project ein Projekt angeben
+missing-status-option = Bitte mit der Option --status einen Status angeben
+missing-priority-option = Bitte mit der Option --priority eine Priorität angeben
+choose-report = Bitte einen Bericht wählen, z. B. `report accuracy`
+choose-key-command = Bitte einen Schlüsselbefehl wählen, z. B. `key generate`
+choose-sync-command = Bitte einen Synchronisierungsbefehl wählen, z. B. `sync conflicts` oder `sync flush`
+choose-auth-command = Bitte einen Anmeldebefehl wählen, z. B. `auth login s3`
+choose-project-command = Bitte einen Projektbefehl wählen, z. B. `project analyze Work`
+choose-statistic = Bitte eine Statistik wählen, z. B. `stats heatmap`
+choose-script-command = Bitte einen Skriptbefehl wählen, z. B. `script run report.rhai`
+choose-backup-command = Bitte einen Sicherungsbefehl wählen, z. B. `backup now`
+
+## Confirmations
+
+task-added = Aufgabe hinzugefügt!
+task-removed = Aufgabe entfernt!
+task-done = '{ $title }' ist erledigt!
+task-updated = Aufgabe aktualisiert!
+cascade-heading = { $count ->
+    [one] Mit dem Fälligkeitsdatum verschiebt sich auch diese abhängige Aufgabe:
+   *[other] Mit dem Fälligkeitsdatum verschieben sich auch diese { $count } abhängigen Aufgaben:
+}
+cascade-confirm = Deren Fälligkeitsdaten ebenfalls verschieben?
+external-id-exists = Es gibt schon eine Aufgabe mit der Referenz '{ $id }', mit --upsert wird sie aktualisiert
+time-logged = Zeit erfasst!
+reminders-added = { $count ->
+    [one] Erinnerung hinzugefügt!
+   *[other] { $count } Erinnerungen hinzugefügt!
+}
+reminders-removed = { $count ->
+    [one] Erinnerung entfernt!
+   *[other] { $count } Erinnerungen entfernt!
+}
+no-reminders = Diese Aufgabe hat keine Erinnerungen.
+reminder-not-found = Es gibt keine Erinnerung { $number }, die Nummern zeigt `reminders`
+invalid-reminder = Ungültige Erinnerung, erwartet eine Zeit vor der Fälligkeit wie "1d before" oder "2h before", oder JJJJ-MM-TT HH:MM
+reminder-without-due = Die Aufgabe hat kein Fälligkeitsdatum, Erinnerungen davor kommen erst, wenn sie eines hat
+reminder-before = { $amount } vorher
+reminder-due = Erinnerung: { $title }, { $due }
+reminder = Erinnerung: { $title }
+plan-task-added = Aufgabe zum heutigen Plan hinzugefügt!
+plan-cleared = Heutiger Plan geleert!
+task-delegated = Aufgabe an { $assignee } übergeben, nachfassen am { $date }.
+priorities-rebalanced = Prioritäten neu verteilt!
+priorities-balanced = Die Prioritäten sind bereits ausgeglichen.
+no-escalations = Keine Aufgaben zu eskalieren.
+escalated = { $title }: eskaliert durch Regel '{ $rule }'
+retention-archived = { $title }: archiviert, vor { $days } Tagen erledigt
+retention-trashed = { $title }: in den Papierkorb verschoben, vor { $days } Tagen abgebrochen
+digest-subject = Aufgaben für den { $date }
+digest-overdue = Überfällig
+digest-due-today = Heute fällig
+digest-completed-yesterday = Gestern erledigt
+digest-empty = Nichts ist überfällig oder heute fällig, und gestern wurde nichts erledigt.
+digest-sent = Die Übersicht wurde an { $count ->
+    [one] eine Adresse
+   *[other] { $count } Adressen
+} gesendet.
+digest-failed = Die Übersicht konnte nicht gesendet werden: { $error }
+digest-no-email = Zum Senden der Übersicht bitte smtp_host, username oder from sowie to im Abschnitt email von config.json angeben
+email-unsupported = Dieser Build unterstützt keinen E-Mail-Versand
+digest-no-password = Kein SMTP-Passwort: Bitte password im Abschnitt email von config.json angeben oder `auth login smtp` ausführen
+
+confirm-prompt = { $question } [j/N]
+confirm-yes-answers = j, ja
+duplicate-found = "{ $imported }" sieht aus wie die vorhandene Aufgabe "{ $existing }" ({ $percent } % ähnlich).
+duplicate-prompt = Überspringen, zusammenführen oder trotzdem anlegen? [Ü/z/a]
+duplicate-skip-answers = ü, u, überspringen
+duplicate-merge-answers = z, zusammenführen
+duplicate-create-answers = a, anlegen
+aborted = Abgebrochen, nichts wurde geändert.
+remove-heading = { $count ->
+    [one] Diese Aufgabe wird entfernt:
+   *[other] Diese { $count } Aufgaben werden entfernt:
+}
+remove-confirm = Entfernen?
+plan-clear-confirm = Heutigen Plan leeren?
+rebalance-confirm = Diese Prioritätsänderungen übernehmen?
+rank-question = Was ist wichtiger?
+rank-prompt = [1/2, q zum Beenden]
+rank-quit-answers = q, beenden
+rank-needs-terminal = Das Ranking fragt nach der Wahl zwischen Aufgaben und braucht dafür ein Terminal
+rank-nothing = Das Projekt "{ $project }" hat weniger als zwei offene Aufgaben zum Ordnen.
+rank-result = Die Aufgaben, die wichtigste zuerst, mit ihren neuen Prioritäten:
+triage-help = Eingabe behält einen Wert, s überspringt eine Aufgabe, q beendet.
+triage-project = Projekt
+triage-priority = Priorität
+triage-due = Fällig
+triage-contexts = Kontexte
+triage-skip-answers = s, skip, überspringen
+inbox-empty = Der Eingang ist leer.
+triage-done = { $count ->
+    [one] 1 Aufgabe einsortiert
+   *[other] { $count } Aufgaben einsortiert
+}, { $left ->
+    [0] der Eingang ist leer.
+    [one] 1 Aufgabe bleibt im Eingang.
+   *[other] { $left } Aufgaben bleiben im Eingang.
+}
+script-changes = Änderungen durch das Skript: { $added } hinzugefügt, { $changed } geändert, { $removed } entfernt.
+script-confirm = Diese Änderungen speichern?
+script-saved = Änderungen gespeichert!
+backup-taken = Sicherung { $name } gespeichert.
+backup-failed = Sicherung fehlgeschlagen: { $error }
+backup-restore-summary = Die Sicherung enthält { $count } Aufgaben und ersetzt die aktuellen { $current }.
+backup-restore-confirm = Wiederherstellen?
+backup-restored = Sicherung wiederhergestellt!
+archive-exported = { $count ->
+    [one] { $count } Datei wurde ins Archiv gepackt.
+   *[other] { $count } Dateien wurden ins Archiv gepackt.
+} Der Schlüssel eines verschlüsselten Speichers und die Zugangsdaten aus der config.json sind nicht enthalten; den Schlüssel separat kopieren. Pläne, Ziele und Gewohnheiten sind nicht verschlüsselt, also das Archiv privat halten.
+archive-imported = { $count ->
+    [one] { $count } Datei wurde aus dem Archiv wiederhergestellt.
+   *[other] { $count } Dateien wurden aus dem Archiv wiederhergestellt.
+}
+archive-not-found = Unter { $path } gibt es kein Archiv
+archive-import-confirm = Damit werden die Aufgaben, Einstellungen und anderen Dateien hier durch die aus dem Archiv ersetzt. Fortfahren?
+store-converted = { $count ->
+    [one] 1 Aufgabe nach { $file } kopiert.
+   *[other] { $count } Aufgaben nach { $file } kopiert.
+} Setze "store": "{ $store }" in config.json, um sie zu verwenden.
+
+timings-heading = Laufzeiten:
+timing-startup = Start: { $ms } ms
+timing-load = Aufgaben laden: { $ms } ms
+timing-plugins = Plugins laden: { $ms } ms
+timing-command = Befehl ausführen: { $ms } ms
+tasks-imported = { $count ->
+    [one] 1 Aufgabe importiert
+   *[other] { $count } Aufgaben importiert
+}{ $merged ->
+    [0] {""}
+   *[other] , { $merged } mit vorhandenen zusammengeführt
+}{ $skipped ->
+    [0] .
+   *[other] , { $skipped } bereits vorhandene übersprungen.
+}
+
+## Task details
+
+task-heading = Aufgabe { $index }: { $title }
+task-description = Beschreibung: { $description }
+task-priority = Priorität: { $priority }
+task-status = Status: { $status }
+task-project = Projekt: { $project }
+task-due = Fällig: { $date }
+task-due-relative = Fällig: { $date } ({ $relative })
+task-tags = Tags: { $tags }
+task-contexts = Kontexte: { $contexts }
+task-depends-on = Hängt ab von: { $tasks }
+task-waiting-on = Wartet auf: { $reason }
+task-delegated-to = Übergeben an: { $assignee }
+task-delegated-follow-up = Übergeben an: { $assignee } (nachfassen am { $date })
+task-estimate = Schätzung: { $estimate } h
+task-estimate-tracked = Schätzung: { $estimate } h, erfasst: { $tracked } h
+task-tracked = Erfasst: { $tracked } h
+task-completed = Erledigt: { $date }
+task-goal = Ziel: { $goal }
+task-goal-key-result = Ziel: { $goal } ({ $key_result })
+task-external-id = Referenz: { $id }
+task-url = URL: { $url }
+show-slug-revision = Kürzel: { $slug } (Revision { $revision })
+show-time-entries = Zeiteinträge
+show-hours = { $hours } Std.
+show-history = Verlauf
+show-history-added = Hinzugefügt
+show-history-started = Begonnen
+show-history-blocked = Blockiert
+show-history-completed = Erledigt
+show-history-reviewed = Überprüft
+show-history-updated = Zuletzt geändert
+show-dependencies = Abhängigkeiten
+show-missing = [?]
+due-today = heute fällig
+due-tomorrow = morgen fällig
+due-in = fällig in { $days ->
+    [one] { $days } Tag
+   *[other] { $days } Tagen
+}
+overdue-by = { $days ->
+    [one] { $days } Tag
+   *[other] { $days } Tage
+} überfällig
+
+due-in-minutes = fällig in { $minutes ->
+    [one] { $minutes } Minute
+   *[other] { $minutes } Minuten
+}
+due-in-hours = fällig in { $hours ->
+    [one] { $hours } Stunde
+   *[other] { $hours } Stunden
+}
+overdue-by-minutes = { $minutes ->
+    [one] { $minutes } Minute
+   *[other] { $minutes } Minuten
+} überfällig
+overdue-by-hours = { $hours ->
+    [one] { $hours } Stunde
+   *[other] { $hours } Stunden
+} überfällig
+## Views
+
+today-heading = Heute:
+today-entry = [{ $mark }] { $title } (Priorität { $priority })
+newly-unblocked-heading = Nicht mehr blockiert:
+suggestion-heading = Wie wäre es mit:
+nothing-to-suggest = Kein Vorschlag, es gibt keine bearbeitbaren Aufgaben.
+follow-up-entry = { $title } (übergeben an { $assignee }, nachfassen seit { $date })
+nobody = niemanden
+wip-status-exceeded = { $status } hat sein WIP-Limit von { $limit } erreicht
+wip-project-exceeded = { $project } hat sein WIP-Limit von { $limit } Aufgaben in Arbeit erreicht
+wip-limit-refused = { $reason }, erst eine Aufgabe abschließen oder --force verwenden
+board-column = { $status } ({ $count })
+board-column-limit = { $status } ({ $count }/{ $limit })
+board-column-over-limit = { $status } ({ $count }/{ $limit }), über dem WIP-Limit
+board-moved = '{ $title }' steht jetzt an Stelle { $place } von { $status }
+invalid-review-interval = Ungültiger Überprüfungsabstand, erwartet eine Zahl von Tagen oder Wochen wie 10d oder 2w, bis zu zehn Jahre, oder never
+project-not-found = Im Projekt '{ $project }' gibt es keine Aufgaben
+nothing-to-review = Nichts ist zur Überprüfung fällig.
+review-projects = Zu überprüfende Projekte:
+review-tasks = Zu überprüfende Aufgaben:
+review-last = zuletzt überprüft am { $date }
+review-never = nie überprüft
+open-count = { $count ->
+    [one] 1 offene Aufgabe
+   *[other] { $count } offene Aufgaben
+}
+reviewed = { $count ->
+    [one] Als überprüft markiert.
+   *[other] { $count } als überprüft markiert.
+}
+board-at-top = '{ $title }' steht bereits oben in der Spalte
+board-at-bottom = '{ $title }' steht bereits unten in der Spalte
+quadrant-do-first = Sofort erledigen, dringend und wichtig ({ $count }):
+quadrant-schedule = Einplanen, wichtig, aber nicht dringend ({ $count }):
+quadrant-delegate = Abgeben, dringend, aber nicht wichtig ({ $count }):
+quadrant-eliminate = Streichen, weder dringend noch wichtig ({ $count }):
+and-more = … und { $count } weitere
+invalid-top = Ungültige Anzahl, erwartet wird eine ganze Zahl
+goal-added = Ziel hinzugefügt!
+goal-exists = Es gibt schon ein Ziel namens '{ $name }'
+goal-not-found = Ziel nicht gefunden
+goals-unreadable = Die Ziele in goals.json können nicht gelesen werden; die Datei reparieren oder entfernen
+key-result-not-found = Das Ziel hat kein solches Schlüsselergebnis
+no-goals = Noch keine Ziele. Mit `goal add` kommt eines hinzu.
+goal-progress = { $goal }: { $progress } ({ $done } von { $total } Aufgaben erledigt)
+goal-percent = { $percent } %
+goal-no-tasks = keine verknüpften Aufgaben
+choose-goal-command = Bitte einen Zielbefehl wählen, z. B. `goal progress`
+habit-added = Gewohnheit hinzugefügt!
+habit-exists = Es gibt schon eine Gewohnheit namens '{ $name }'
+habit-not-found = Gewohnheit nicht gefunden
+invalid-frequency = Ungültige Häufigkeit, erwartet: day oder week
+habit-kept = { $name } eingehalten, Serie: { $streak }
+no-habits = Noch keine Gewohnheiten. Mit `habit add` kommt eine hinzu.
+habit-streak = { $name } ({ $every ->
+    [week] wöchentlich
+   *[day] täglich
+}): aktuelle Serie { $current }, längste { $longest }
+choose-habit-command = Bitte einen Gewohnheitsbefehl wählen, z. B. `habit streaks`
+stale-heading = Veraltet in { $project }:
+untouched-for = seit { $days ->
+    [one] { $days } Tag
+   *[other] { $days } Tagen
+} unberührt
+no-stale-tasks = Keine offene Aufgabe ist länger als { $days ->
+    [one] { $days } Tag
+   *[other] { $days } Tage
+} unberührt.
+stale-nag = { $count ->
+    [one] { $count } Aufgabe veraltet
+   *[other] { $count } Aufgaben veralten
+}, siehe `stale`.
+waiting-heading = Wartet auf { $reason }:
+waiting-on-tasks = Aufgaben { $tasks }
+no-reason-given = (kein Grund angegeben)
+blocked-for = seit { $days ->
+    [one] { $days } Tag
+   *[other] { $days } Tagen
+} blockiert
+blocked-since-unknown = blockiert seit unbekannt
+
+check-clean = Keine Probleme gefunden.
+check-no-due-date = kein Fälligkeitsdatum, obwohl { $project } termingebunden ist
+check-stale-in-progress = seit { $days } Tagen in Bearbeitung
+check-empty-description = leere Beschreibung
+check-missing-dependency = hängt von '{ $dependency }' ab, die es nicht gibt
+severity-error = Fehler
+severity-warning = Warnung
+severity-info = Hinweis
+
+## Reports
+
+accuracy-no-data = Keine erledigten Aufgaben mit Schätzung und erfasster Zeit.
+accuracy-project = Projekt: { $project } ({ $count ->
+    [one] { $count } Aufgabe
+   *[other] { $count } Aufgaben
+})
+accuracy-errors = Mittlere Abweichung: { $mean }%, Median: { $median }%
+accuracy-much-faster = viel schneller (< -50%)
+accuracy-faster = schneller (-50% bis -10%)
+accuracy-on-target = im Plan (innerhalb 10%)
+accuracy-slower = langsamer (10% bis 50%)
+accuracy-much-slower = viel langsamer (50%+)
+analyze-no-tasks = Das Projekt "{ $project }" hat keine offenen Aufgaben.
+analyze-summary = Projekt { $project }: { $remaining } h Arbeit übrig, mindestens { $length } h entlang des kritischen Pfads.
+analyze-path = Kritischer Pfad:
+analyze-slack = Offene Aufgaben:
+analyze-critical = kritisch, jeder Verzug verschiebt das Projektende
+analyze-can-slip = kann sich um { $hours } h verzögern
+analyze-unestimated = Ohne Schätzung, als keine Arbeit gezählt: { $tasks }
+feed-title = Aufgabenaktivität
+feed-author = Aufgabenverwaltung
+feed-added = Hinzugefügt: { $title }
+feed-completed = Erledigt: { $title }
+feed-summary = { $project }, Priorität { $priority }: { $description }
+patterns-weekdays = Erledigte Aufgaben nach Wochentag:
+patterns-hours = Erledigte Aufgaben nach Tageszeit:
+heatmap-total = { $count ->
+    [one] { $count } Aufgabe
+   *[other] { $count } Aufgaben
+} im letzten Jahr erledigt. Weniger { $legend } Mehr
+weekday-short = { $day ->
+    [mon] Mo
+    [tue] Di
+    [wed] Mi
+    [thu] Do
+    [fri] Fr
+    [sat] Sa
+   *[sun] So
+}
+month-short = { $month ->
+    [1] Jan
+    [2] Feb
+    [3] Mär
+    [4] Apr
+    [5] Mai
+    [6] Jun
+    [7] Jul
+    [8] Aug
+    [9] Sep
+    [10] Okt
+    [11] Nov
+   *[12] Dez
+}
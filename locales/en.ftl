@@ -0,0 +1,479 @@
+# English messages, also used for anything missing from a translation.
+
+## Errors
+
+error = Error: { $message }
+warning = Warning: { $message }
+config-read-failed = Failed to read config.json: { $error }
+data-dir-failed = Failed to open the data directory: { $error }
+unknown-timezone = Unknown timezone '{ $name }'
+invalid-command = Invalid command
+unknown-command = Unknown command '{ $name }'. Plugins are run from a `task-manager-{ $name }` executable on the PATH.
+unknown-theme = Unknown theme '{ $name }', expected default, high-contrast, colorblind or plain
+unknown-style = Unknown color or style '{ $style }'
+unknown-column = Unknown column '{ $name }', expected id, title, status, priority, project, due, tags, description, revision, slug, urgency, age, days-until-due or blocked
+unknown-field = Unknown computed field '{ $name }', expected urgency, age, days-until-due or blocked
+invalid-condition = Invalid condition '{ $condition }', expected a field, a comparison and a number such as urgency>=6
+this-store = this store
+store-heading = { $name }:
+store-failed = Could not read the store { $name }: { $error }
+invalid-remote = Invalid remote store '{ $url }', expected e.g. ssh://host/path/tasks.json
+remote-needs-json = Only the JSON store can be kept on a server
+encryption-needs-json = Only the JSON store can be encrypted: remove encryption_key from config.json or switch to the JSON store
+remote-ssh-failed = Could not run ssh: { $error }
+remote-read-failed = Could not read the store from { $host } (ssh exited with { $status })
+remote-write-failed = Could not write the store to { $host } (ssh exited with { $status })
+remote-locked = The store on { $host } is locked by another write; try again
+remote-changed = The remote store was changed by someone else since it was read, so nothing was saved. Your change was kept: run `sync conflicts` to merge it
+s3-no-credentials = No S3 credentials: set access_key and secret_key in the s3 section of config.json, or { $var }
+s3-unsupported = This build does not support S3
+remote-unreachable = The remote store cannot be reached: { $error }
+remote-offline = Working offline on the copy of the remote store last read. { $error }
+remote-queued = Saved locally; run `sync flush` to send the changes to the remote store
+nothing-to-flush = There are no changes waiting to be sent.
+no-remote = No remote store is configured.
+flushed = The changes made offline were sent to the remote store.
+key-generated = Saved a new key to { $path }. Set "encryption_key": "{ $path }" in config.json to encrypt the store with it, and copy the file to every device sharing the store: without it, the store cannot be read.
+key-exists = { $path } already exists; remove it first if you really want a new key, as stores encrypted with the old one can then no longer be read
+key-read-failed = Could not read the key from { $path }: { $error }
+key-invalid = { $path } does not hold a key; create one with `key generate`
+store-encrypted = The store is encrypted; set encryption_key in config.json to the file holding its key
+store-not-encrypted = The store is not encrypted although encryption_key is set; if it was saved before the key was set, run `key encrypt` once to encrypt it
+crdt-unsupported-value = Unsupported value in the task store: { $value }
+no-encryption-key = Set encryption_key in config.json to the file holding the key first, e.g. one made by `key generate`
+store-encrypted-now = { $count ->
+    [one] Encrypted the store, with { $count } task
+   *[other] Encrypted the store, with { $count } tasks
+}
+store-decrypt-failed = The store could not be decrypted: it was encrypted with another key, or it is damaged
+store-changed-on-disk = The store was changed by another program since it was read, so nothing was saved. Your change was kept: run `sync conflicts` to merge it
+store-changed-prompt = The store was changed by another program since it was read. Merge the changes, overwrite the store or abort? [m/o/a]
+store-changed-merge-answers = m, merge
+store-changed-overwrite-answers = o, overwrite
+store-changed-abort-answers = a, abort
+store-record-skipped = Skipped record { $record } (line { $line }) of the store, which is not a valid task: { $error }
+store-has-malformed = The store has records that are not valid tasks, so it was not saved over. Run `repair` to move them to tasks.rejected.json
+store-valid = Every record of the store is a valid task.
+store-repaired = { $count ->
+    [one] One record was
+   *[other] { $count } records were
+} moved to { $file }.
+no-conflicts = There are no conflicting changes to resolve.
+conflicts-need-terminal = Resolving conflicts asks which changes to keep, so it needs a terminal
+conflicts-resolved = The conflicting changes were merged and saved.
+conflict-field = "{ $title }": { $field } was changed on both sides.
+conflict-local = local:
+conflict-remote = remote:
+conflict-prompt = Keep local or remote? [l/r, q to stop]
+conflict-local-answers = l, local
+conflict-remote-answers = r, remote
+auth-s3-access-key = S3 access key:
+auth-s3-secret-key = S3 secret key:
+auth-smtp-password = SMTP password:
+auth-logged-in = The credentials for { $service } are kept in the keyring now.
+auth-cleared-on-restart = The kernel keyring forgets them when the machine restarts; run `auth login { $service }` again then.
+keyring-unsupported = This build does not support the keyring
+auth-logged-out = Removed the credentials for { $service } from the keyring.
+auth-not-logged-in = The keyring holds no credentials for { $service }.
+auth-failed = Could not use the keyring: { $error }
+s3-failed = S3 refused the request (status { $status }{ $code ->
+    [none] {""}
+   *[other] , { $code }
+})
+column-id = #
+column-title = Title
+column-status = Status
+column-priority = Priority
+column-project = Project
+column-due = Due
+column-tags = Tags
+column-description = Description
+column-revision = Rev
+column-slug = Slug
+column-urgency = Urgency
+column-age = Age
+column-days-until-due = Days left
+column-blocked = Blocked
+unknown-filter = No WASM plugin provides the filter '{ $name }'
+wasm-plugins-failed = Failed to load WASM plugins: { $error }
+wasm-plugins-unsupported = This build does not support WASM plugins
+clipboard-failed = Could not read the clipboard: { $error }
+clipboard-empty = The clipboard holds no text to make a task of
+clipboard-unsupported = This build does not support the clipboard
+task-not-found = Task not found
+task-ambiguous = { $count ->
+    [one] '{ $reference }' matches no task; did you mean { $titles }?
+   *[other] '{ $reference }' could be any of: { $titles }
+}
+task-candidates = { $count ->
+    [one] '{ $reference }' matches no task; did you mean:
+   *[other] '{ $reference }' could be any of:
+}
+task-choose-prompt = Which task? [number, q to stop]
+task-number-not-listed = No task was listed as number { $number }; run list first
+save-failed = Failed to save tasks
+invalid-hours = Invalid number of hours
+invalid-due-date = Invalid due date, expected YYYY-MM-DD
+invalid-due = Invalid due date, expected YYYY-MM-DD, YYYY-MM-DD HH:MM or a number of working days such as 3bd
+calendar-no-working-days = The calendar leaves no day of the week to work on
+calendar-file-failed = Could not read the holidays file { $file }: { $error }
+nonexistent-due-time = That time does not exist in the timezone, as the clocks go forward then
+capture-no-title = The task needs a title besides its +project, @contexts, #tags, p: and due:
+capture-invalid-priority = Invalid priority "{ $priority }"; expected a number, e.g. p:4
+capture-invalid-due = Invalid due date "{ $due }"; expected today, tomorrow, a weekday, working days such as 3bd or YYYY-MM-DD
+invalid-url = Invalid URL; expected one starting with http://, https://, file:// or mailto:
+revision-conflict = The task was changed in between: it is at revision { $revision }, not { $expected }
+invalid-revision = Invalid revision; expected a whole number
+no-url = The task has no URL, and there is none in its description
+open-failed = Could not open { $url }: { $error }
+invalid-priority = Invalid priority
+invalid-priority-value = Invalid priority value. Please provide a valid integer for priority.
+invalid-max-priority = Invalid maximum priority. Please provide an integer between 1 and 255.
+invalid-days = Invalid number of days, expected a positive integer
+dependency-not-found = Dependency not found
+dependency-cycle = Dependency would create a cycle
+missing-project-option = Please provide a project name with the --project option
+missing-status-option = Please provide a status with the --status option
+missing-priority-option = Please provide a priority with the --priority option
+choose-report = Please choose a report, e.g. `report accuracy`
+choose-key-command = Please choose a key command, e.g. `key generate`
+choose-sync-command = Please choose a sync command, e.g. `sync conflicts` or `sync flush`
+choose-auth-command = Please choose an auth command, e.g. `auth login s3`
+choose-project-command = Please choose a project command, e.g. `project analyze Work`
+choose-statistic = Please choose a statistic, e.g. `stats heatmap`
+choose-script-command = Please choose a script command, e.g. `script run report.rhai`
+choose-backup-command = Please choose a backup command, e.g. `backup now`
+
+## Confirmations
+
+task-added = Task added successfully!
+task-removed = Task removed successfully!
+task-done = Marked '{ $title }' as Done!
+task-updated = Task updated successfully!
+cascade-heading = { $count ->
+    [one] Moving the due date also moves this task depending on it:
+   *[other] Moving the due date also moves these { $count } tasks depending on it:
+}
+cascade-confirm = Move their due dates too?
+external-id-exists = A task with the reference '{ $id }' already exists, use --upsert to update it
+time-logged = Time logged successfully!
+reminders-added = { $count ->
+    [one] Reminder added!
+   *[other] { $count } reminders added!
+}
+reminders-removed = { $count ->
+    [one] Reminder removed!
+   *[other] { $count } reminders removed!
+}
+no-reminders = This task has no reminders.
+reminder-not-found = There is no reminder { $number }, see `reminders` for their numbers
+invalid-reminder = Invalid reminder, expected a time before the due date such as "1d before" or "2h before", or YYYY-MM-DD HH:MM
+reminder-without-due = The task has no due date, so reminders before it will not be given until it gets one
+reminder-before = { $amount } before
+reminder-due = Reminder: { $title }, { $due }
+reminder = Reminder: { $title }
+plan-task-added = Task added to today's plan!
+plan-cleared = Today's plan cleared!
+task-delegated = Task delegated to { $assignee }, follow up on { $date }.
+priorities-rebalanced = Priorities rebalanced successfully!
+priorities-balanced = Priorities are already balanced.
+no-escalations = No tasks to escalate.
+escalated = { $title }: escalated by rule '{ $rule }'
+retention-archived = { $title }: archived, done { $days } days ago
+retention-trashed = { $title }: moved to the trash, cancelled { $days } days ago
+digest-subject = Tasks for { $date }
+digest-overdue = Overdue
+digest-due-today = Due today
+digest-completed-yesterday = Completed yesterday
+digest-empty = Nothing is overdue or due today, and nothing was completed yesterday.
+digest-sent = The digest was sent to { $count ->
+    [one] one address
+   *[other] { $count } addresses
+}.
+digest-failed = Could not send the digest: { $error }
+digest-no-email = Set smtp_host, username or from, and to in the email section of config.json to send the digest
+email-unsupported = This build does not support email
+digest-no-password = No SMTP password: set password in the email section of config.json, or run `auth login smtp`
+
+confirm-prompt = { $question } [y/N]
+confirm-yes-answers = y, yes
+duplicate-found = "{ $imported }" looks like the existing task "{ $existing }" ({ $percent }% alike).
+duplicate-prompt = Skip, merge into it, or create anyway? [S/m/c]
+duplicate-skip-answers = s, skip
+duplicate-merge-answers = m, merge
+duplicate-create-answers = c, create
+aborted = Aborted, nothing was changed.
+remove-heading = { $count ->
+    [one] This task will be removed:
+   *[other] These { $count } tasks will be removed:
+}
+remove-confirm = Remove?
+plan-clear-confirm = Clear today's plan?
+rebalance-confirm = Apply these priority changes?
+rank-question = Which is more important?
+rank-prompt = [1/2, q to stop]
+rank-quit-answers = q, quit
+rank-needs-terminal = Ranking asks you to choose between tasks, so it needs a terminal
+rank-nothing = The project "{ $project }" has fewer than two open tasks to rank.
+rank-result = The tasks, most important first, with their new priorities:
+triage-help = Press Enter to keep a value, s to skip a task or q to stop.
+triage-project = Project
+triage-priority = Priority
+triage-due = Due
+triage-contexts = Contexts
+triage-skip-answers = s, skip
+inbox-empty = The Inbox is empty.
+triage-done = { $count ->
+    [one] 1 task triaged
+   *[other] { $count } tasks triaged
+}, { $left ->
+    [0] the Inbox is empty.
+    [one] 1 task left in the Inbox.
+   *[other] { $left } tasks left in the Inbox.
+}
+script-changes = Script changes: { $added } added, { $changed } changed, { $removed } removed.
+script-confirm = Save these changes?
+script-saved = Changes saved!
+backup-taken = Snapshot { $name } saved.
+backup-failed = Failed to take a snapshot: { $error }
+backup-restore-summary = The snapshot has { $count } tasks, replacing the current { $current }.
+backup-restore-confirm = Restore it?
+backup-restored = Snapshot restored!
+archive-exported = { $count ->
+    [one] { $count } file was bundled into the archive.
+   *[other] { $count } files were bundled into the archive.
+} The key of an encrypted store and the credentials in config.json are not included; copy the key separately. Plans, goals and habits are not encrypted, so keep the archive private.
+archive-imported = { $count ->
+    [one] { $count } file was restored from the archive.
+   *[other] { $count } files were restored from the archive.
+}
+archive-not-found = There is no archive at { $path }
+archive-import-confirm = This replaces the tasks, settings and other files here with those in the archive. Continue?
+store-converted = { $count ->
+    [one] Copied 1 task to { $file }.
+   *[other] Copied { $count } tasks to { $file }.
+} Set "store": "{ $store }" in config.json to use it.
+
+timings-heading = Timings:
+timing-startup = Startup: { $ms } ms
+timing-load = Loading tasks: { $ms } ms
+timing-plugins = Loading plugins: { $ms } ms
+timing-command = Running the command: { $ms } ms
+tasks-imported = { $count ->
+    [one] Imported 1 task
+   *[other] Imported { $count } tasks
+}{ $merged ->
+    [0] {""}
+   *[other] , merged { $merged } into existing tasks
+}{ $skipped ->
+    [0] .
+   *[other] , skipped { $skipped } that already exist.
+}
+
+## Task details
+
+task-heading = Task { $index }: { $title }
+task-description = Description: { $description }
+task-priority = Priority: { $priority }
+task-status = Status: { $status }
+task-project = Project: { $project }
+task-due = Due: { $date }
+task-due-relative = Due: { $date } ({ $relative })
+task-tags = Tags: { $tags }
+task-contexts = Contexts: { $contexts }
+task-depends-on = Depends on: { $tasks }
+task-waiting-on = Waiting on: { $reason }
+task-delegated-to = Delegated to: { $assignee }
+task-delegated-follow-up = Delegated to: { $assignee } (follow up on { $date })
+task-estimate = Estimate: { $estimate } h
+task-estimate-tracked = Estimate: { $estimate } h, tracked: { $tracked } h
+task-tracked = Tracked: { $tracked } h
+task-completed = Completed: { $date }
+task-goal = Goal: { $goal }
+task-goal-key-result = Goal: { $goal } ({ $key_result })
+task-external-id = Reference: { $id }
+task-url = URL: { $url }
+show-slug-revision = Slug: { $slug } (revision { $revision })
+show-time-entries = Time entries
+show-hours = { $hours } h
+show-history = History
+show-history-added = Added
+show-history-started = Started
+show-history-blocked = Blocked
+show-history-completed = Completed
+show-history-reviewed = Reviewed
+show-history-updated = Last changed
+show-dependencies = Dependencies
+show-missing = [?]
+due-today = due today
+due-tomorrow = due tomorrow
+due-in = due in { $days ->
+    [one] { $days } day
+   *[other] { $days } days
+}
+overdue-by = overdue by { $days ->
+    [one] { $days } day
+   *[other] { $days } days
+}
+
+due-in-minutes = due in { $minutes ->
+    [one] { $minutes } minute
+   *[other] { $minutes } minutes
+}
+due-in-hours = due in { $hours ->
+    [one] { $hours } hour
+   *[other] { $hours } hours
+}
+overdue-by-minutes = overdue by { $minutes ->
+    [one] { $minutes } minute
+   *[other] { $minutes } minutes
+}
+overdue-by-hours = overdue by { $hours ->
+    [one] { $hours } hour
+   *[other] { $hours } hours
+}
+## Views
+
+today-heading = Today:
+today-entry = [{ $mark }] { $title } (priority { $priority })
+newly-unblocked-heading = Newly unblocked:
+suggestion-heading = How about:
+nothing-to-suggest = Nothing to suggest, there are no actionable tasks.
+follow-up-entry = { $title } (delegated to { $assignee }, follow up since { $date })
+nobody = nobody
+wip-status-exceeded = { $status } is at its WIP limit of { $limit }
+wip-project-exceeded = { $project } is at its WIP limit of { $limit } tasks In Progress
+wip-limit-refused = { $reason }, finish a task first or use --force
+board-column = { $status } ({ $count })
+board-column-limit = { $status } ({ $count }/{ $limit })
+board-column-over-limit = { $status } ({ $count }/{ $limit }), over the WIP limit
+board-moved = Moved '{ $title }' to place { $place } of { $status }
+invalid-review-interval = Invalid review interval, expected a number of days or weeks such as 10d or 2w, up to ten years, or never
+project-not-found = There are no tasks in the project '{ $project }'
+nothing-to-review = Nothing is due for review.
+review-projects = Projects to review:
+review-tasks = Tasks to review:
+review-last = last reviewed { $date }
+review-never = never reviewed
+open-count = { $count ->
+    [one] 1 open task
+   *[other] { $count } open tasks
+}
+reviewed = { $count ->
+    [one] Marked as reviewed.
+   *[other] { $count } marked as reviewed.
+}
+board-at-top = '{ $title }' is already at the top of its column
+board-at-bottom = '{ $title }' is already at the bottom of its column
+quadrant-do-first = Do first, urgent and important ({ $count }):
+quadrant-schedule = Schedule, important but not urgent ({ $count }):
+quadrant-delegate = Delegate, urgent but not important ({ $count }):
+quadrant-eliminate = Drop, neither urgent nor important ({ $count }):
+and-more = … and { $count } more
+invalid-top = Invalid number of tasks, expected a whole number
+goal-added = Goal added!
+goal-exists = There is already a goal called '{ $name }'
+goal-not-found = Goal not found
+goals-unreadable = The goals in goals.json cannot be read; fix or remove the file
+key-result-not-found = The goal has no such key result
+no-goals = No goals yet. Add one with `goal add`.
+goal-progress = { $goal }: { $progress } ({ $done } of { $total } tasks done)
+goal-percent = { $percent }%
+goal-no-tasks = no linked tasks
+choose-goal-command = Please choose a goal command, e.g. `goal progress`
+habit-added = Habit added!
+habit-exists = There is already a habit called '{ $name }'
+habit-not-found = Habit not found
+invalid-frequency = Invalid frequency, expected day or week
+habit-kept = Kept { $name }, streak: { $streak }
+no-habits = No habits yet. Add one with `habit add`.
+habit-streak = { $name } (every { $every ->
+    [week] week
+   *[day] day
+}): current streak { $current }, longest { $longest }
+choose-habit-command = Please choose a habit command, e.g. `habit streaks`
+stale-heading = Going stale in { $project }:
+untouched-for = untouched for { $days ->
+    [one] { $days } day
+   *[other] { $days } days
+}
+no-stale-tasks = No open task has gone untouched for more than { $days ->
+    [one] { $days } day
+   *[other] { $days } days
+}.
+stale-nag = { $count ->
+    [one] { $count } task is going stale
+   *[other] { $count } tasks are going stale
+}, see `stale`.
+waiting-heading = Waiting on { $reason }:
+waiting-on-tasks = tasks { $tasks }
+no-reason-given = (no reason given)
+blocked-for = blocked { $days ->
+    [one] { $days } day
+   *[other] { $days } days
+}
+blocked-since-unknown = blocked since unknown
+
+check-clean = No issues found.
+check-no-due-date = no due date, but { $project } is deadline-driven
+check-stale-in-progress = In Progress for { $days } days
+check-empty-description = empty description
+check-missing-dependency = depends on '{ $dependency }', which does not exist
+severity-error = error
+severity-warning = warning
+severity-info = info
+
+## Reports
+
+accuracy-no-data = No finished tasks with both an estimate and tracked time.
+accuracy-project = Project: { $project } ({ $count ->
+    [one] { $count } task
+   *[other] { $count } tasks
+})
+accuracy-errors = Mean error: { $mean }%, median error: { $median }%
+accuracy-much-faster = much faster (< -50%)
+accuracy-faster = faster (-50% to -10%)
+accuracy-on-target = on target (within 10%)
+accuracy-slower = slower (10% to 50%)
+accuracy-much-slower = much slower (50%+)
+analyze-no-tasks = The project "{ $project }" has no open tasks.
+analyze-summary = Project { $project }: { $remaining } h of work left, taking at least { $length } h along its critical path.
+analyze-path = Critical path:
+analyze-slack = Open tasks:
+analyze-critical = critical, any slippage delays the project
+analyze-can-slip = can slip { $hours } h
+analyze-unestimated = Without an estimate, counted as no work: { $tasks }
+feed-title = Task activity
+feed-author = Task Manager
+feed-added = Added: { $title }
+feed-completed = Completed: { $title }
+feed-summary = { $project }, priority { $priority }: { $description }
+patterns-weekdays = Completed tasks by weekday:
+patterns-hours = Completed tasks by hour of the day:
+heatmap-total = { $count ->
+    [one] { $count } task
+   *[other] { $count } tasks
+} completed in the last year. Less { $legend } More
+weekday-short = { $day ->
+    [mon] Mon
+    [tue] Tue
+    [wed] Wed
+    [thu] Thu
+    [fri] Fri
+    [sat] Sat
+   *[sun] Sun
+}
+month-short = { $month ->
+    [1] Jan
+    [2] Feb
+    [3] Mar
+    [4] Apr
+    [5] May
+    [6] Jun
+    [7] Jul
+    [8] Aug
+    [9] Sep
+    [10] Oct
+    [11] Nov
+   *[12] Dec
+}
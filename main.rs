@@ -9,17 +9,120 @@
 //!
 //! ## Important Functions
 //!
-//! - `save_tasks(tasks: &[Task]) -> Result<(), Box<dyn Error>>`: Saves a vector of tasks to a JSON file.
-//! - `load_tasks() -> Result<Vec<Task>, Box<dyn Error>>`: Loads tasks from a JSON file.
-//! - `update_task(matches: &ArgMatches, tasks: &mut Vec<Task>) -> Result<(), &'static str>`: Updates a task based on command-line arguments.
-//! - `list_tasks_by_project(tasks: &[Task], project_name: &str)`: Lists all tasks with the same project name.
-//! - `list_tasks_by_status(tasks: &[Task], status: &str)`: Lists all tasks with the same status.
-//! - `list_tasks_by_priority(tasks: &[Task], priority: u8)`: Lists all tasks with the same priority number.
-//! - `main()`: The entry point of the application, which handles command-line arguments and performs corresponding actions on tasks.
+//! - `save_tasks(tasks: &[Task]) -> Result<(), Box<dyn Error>>`: Saves a vector of tasks to the configured store.
+//! - `load_tasks() -> Result<Vec<Task>, Box<dyn Error>>`: Loads tasks from the configured store.
+//! - `save_tasks_to(store: StoreKind, compression: Compression, key: Option<&crypto::Key>, tasks: &[Task]) -> Result<(), Box<dyn Error>>`: Saves tasks to a store of the given kind, as `convert` does.
+//! - `json_store_path(compression: Compression) -> String`: The file name of the JSON store for a compression setting.
+//! - `json_store_bytes(tasks: &[Task], compression: Compression, key: Option<&crypto::Key>) -> Result<Vec<u8>, Box<dyn Error>>`: Serializes tasks for the JSON store, compressed and maybe encrypted.
+//! - `json_store_tasks(data: Vec<u8>, key: Option<&crypto::Key>) -> Result<Vec<Task>, Box<dyn Error>>`: Reads the tasks from the JSON store, however it is compressed or encrypted.
+//! - `store_key(config: &Config) -> Result<Option<crypto::Key>, Box<dyn Error>>`: The key the JSON store is encrypted with, if any (see the `crypto` module).
+//! - `load_config() -> Result<Config, Box<dyn Error>>`: Loads the application settings from a JSON file.
+//! - `save_plans(plans: &DailyPlans) -> Result<(), Box<dyn Error>>`: Saves the daily plans to a JSON file.
+//! - `load_plans() -> Result<DailyPlans, Box<dyn Error>>`: Loads the daily plans from a JSON file.
+//! - `ask_duplicate(imported: &Task, existing: &Task, similarity: f64) -> OnDuplicate`: Asks whether to skip, merge or create an imported task that looks like an existing one.
+//! - `skip_malformed(malformed: repair::Malformed)`: Reports a record of the store that is not a valid task, and keeps the store from being saved over.
+//! - `save_or_report(tasks: &[Task]) -> bool`: Saves the tasks, reporting why when they could not be saved.
+//! - `save_over_changed_store(tasks: &[Task]) -> Result<bool, Box<dyn Error>>`: Asks whether to merge with, overwrite or leave a local store changed by another program since it was read.
+//! - `ask_side(conflict: &conflict::Conflict) -> Option<conflict::Side>`: Asks whether to keep the local or the remote value of a field changed on both sides.
+//! - `backup_before_change(config: &Config) -> bool`: Snapshots the stored tasks before a bulk change (see the `backup` module).
+//! - `confirm(question: &str, assume_yes: bool) -> bool`: Asks the user to confirm a destructive operation, unless `--yes` is given or stdin is not a terminal.
+//! - `find_task(tasks: &[Task], reference: &str) -> Option<usize>`: Finds the task a command refers to by title, slug, fragments of its title or number in the last listing, asking which one was meant when several match.
+//! - `render_task_details(task: &Task, tasks: &[Task], fmt: &Formatter) -> String`: Renders everything about a task for `show`, including its time entries, history and dependencies.
+//! - `cascade_due_dates(tasks: &[Task], title: &str, delta: Duration) -> Vec<(usize, NaiveDate)>`: The new due dates of the tasks depending on a task whose due date moves, for `update --cascade-dates`.
+//! - `update_task(title: &str, matches: &ArgMatches, tasks: &mut [Task], fmt: &Formatter) -> Result<(), &'static str>`: Updates a task based on command-line arguments.
+//! - `print_task(index: usize, task: &Task, fmt: &Formatter)`: Prints a numbered task with dates and numbers formatted for the configured locale.
+//! - `remote_store(config: &Config) -> Result<Option<remote::Remote>, Box<dyn Error>>`: The store configured to be kept on a server, if any (see the `remote` module).
+//! - `load_tasks_from(dir: &Path) -> Result<Vec<Task>, Box<dyn Error>>`: Loads the tasks of another data directory, for `list --all-stores`.
+//! - `stream_tasks() -> Result<TaskStream, Box<dyn Error>>`: Reads tasks one at a time, streaming them from the NDJSON store.
+//! - `print_matching_tasks(tasks: TaskStream, predicate: impl Fn(&Task) -> bool, fmt: &Formatter) -> Result<(), Box<dyn Error>>`: Prints the tasks a predicate matches as they are read.
+//! - `list_tasks_by_project(tasks: TaskStream, project_name: &str, fmt: &Formatter) -> Result<(), Box<dyn Error>>`: Lists all tasks with the same project name.
+//! - `list_tasks_by_status(tasks: TaskStream, status: &str, fmt: &Formatter) -> Result<(), Box<dyn Error>>`: Lists all tasks with the same status.
+//! - `list_tasks_by_priority(tasks: TaskStream, priority: u8, fmt: &Formatter) -> Result<(), Box<dyn Error>>`: Lists all tasks with the same priority number.
+//! - `apply_escalation_rules(tasks: &mut [Task], rules: &[EscalationRule], today: NaiveDate) -> Vec<String>`: Raises the urgency of tasks nearing their due date.
+//! - `unblock_ready_tasks(tasks: &mut [Task]) -> Vec<String>`: Moves Blocked tasks whose dependencies are all done back to Todo.
+//! - `rebalance_priorities(tasks: &mut [Task], project: &str, max_priority: u8) -> Vec<String>`: Spreads the priorities of a project's open tasks evenly.
+//! - `ranked_open_tasks(tasks: &[Task], project: &str) -> Vec<usize>`: Lists a project's open tasks by priority, then due date.
+//! - `assign_priorities(tasks: &mut [Task], ranked: &[usize], max_priority: u8) -> Vec<String>`: Gives ranked tasks priorities spread evenly from the highest down.
+//! - `rank_tasks(tasks: &[Task], indices: &[usize], prefer: impl FnMut(&Task, &Task) -> Option<bool>) -> Option<Vec<usize>>`: Orders tasks by importance from pairwise choices.
+//! - `ask_preference(first: &Task, second: &Task) -> Option<bool>`: Asks which of two tasks is more important, for `rank`.
+//! - `triage_task(task: &mut Task, fmt: &Formatter, input: &mut impl io::BufRead) -> Result<(), Stop>`: Asks for the project, priority, due date and contexts of an inbox task in `triage`.
+//! - `estimate_errors_by_project(tasks: &[Task]) -> Vec<(String, Vec<f64>)>`: Collects how far tracked time strayed from the estimate of finished tasks.
+//! - `completion_heatmap(tasks: &[Task], fmt: &Formatter, today: NaiveDate) -> String`: Renders completed tasks per day over the past year as a contribution grid.
+//! - `planned_tasks<'a>(tasks: &'a [Task], plan: &[String]) -> Vec<&'a Task>`: Looks up the tasks chosen for a day, in the order they were chosen.
+//! - `urgency(task: &Task, today: NaiveDate) -> f64`: Scores how pressing a task is from its priority and due date.
+//! - `board_columns<'a>(tasks: &'a [Task], project: Option<&str>, wip: &WipConfig) -> Vec<(String, Vec<&'a Task>)>`: Groups tasks into a board column per status, in their manual order.
+//! - `move_in_column(tasks: &mut [Task], index: usize, up: bool, project: Option<&str>) -> bool`: Moves a task one place up or down in its board column, as shown for a project or for all.
+//! - `check_wip_limits(exceeded: &[String], config: &WipConfig, force: bool) -> bool`: Warns about, or refuses, changes going over a WIP limit.
+//! - `priority_matrix<'a>(tasks: &'a [Task], project: Option<&str>, today: NaiveDate, config: &MatrixConfig) -> [Vec<&'a Task>; 4]`: Sorts the open tasks of a project, or of all, into the quadrants of the Eisenhower matrix.
+//! - `actionable_tasks(tasks: &[Task]) -> Vec<&Task>`: Lists the open tasks that can be worked on right now.
+//! - `completion_patterns(tasks: &[Task], fmt: &Formatter) -> ([usize; 7], [usize; 24])`: Counts completed tasks by weekday and hour of the day.
+//! - `bar_chart(rows: &[(String, usize)], width: usize, fmt: &Formatter) -> String`: Renders labelled counts as horizontal bars.
+//! - `waiting_groups(tasks: &[Task]) -> Vec<(String, Vec<&Task>)>`: Groups blocked tasks by who or what they are waiting on.
+//! - `last_activity(task: &Task) -> Option<DateTime<Utc>>`: Finds when a task was last added, edited, moved or worked on.
+//! - `is_stale(task: &Task, now: DateTime<Utc>, after_days: i64) -> bool`: Tells whether an open task has gone untouched for too long.
+//! - `stale_groups(tasks: &[Task], now: DateTime<Utc>, after_days: i64) -> Vec<(&str, Vec<&Task>)>`: Groups the stale tasks by project.
+//! - `parse_due_at(due: &str, fmt: &Formatter) -> Result<(NaiveDate, Option<DateTime<Utc>>), &'static str>`: Parses a due date, or a due time in the configured timezone.
+//! - `upsert_task(tasks: &mut Vec<Task>, task: Task) -> bool`: Adds a task, or updates the one with the same external reference.
+//! - `link_goal(task: &mut Task, goal: &str, key_result: Option<&str>, goals: &[Goal]) -> Result<(), &'static str>`: Links a task to a goal and one of its key results.
+//! - `task_url(task: &Task) -> Option<&str>`: Finds the link of a task, from its URL or else its description.
+//! - `open_url(url: &str) -> io::Result<()>`: Opens a link with the desktop's default application for it.
+//! - `mark_done(tasks: &mut [Task], index: usize) -> Vec<String>`: Marks a task Done and unblocks the tasks that depended on it.
+//! - `delegate_task(task: &mut Task, assignee: &str, follow_up: NaiveDate)`: Hands a task over to someone else until a follow-up date.
+//! - `due_follow_ups(tasks: &[Task], today: NaiveDate) -> Vec<&Task>`: Lists delegated tasks whose follow-up date has arrived.
+//! - `report_error(kind: ErrorKind, message: &str, title: Option<&str>)`: Reports an error as text, or as JSON on stderr with `--json`, and makes the command exit with status 1.
+//! - `report_warning(kind: ErrorKind, message: &str, title: Option<&str>)`: Reports an error the command works around, without making it exit with status 1.
+//! - `main()`: The entry point of the application, which runs the command and exits with status 1 if it reported an error.
+//! - `run()`: Handles command-line arguments and performs corresponding actions on tasks.
 //!
 //! ## Data Types
 //!
-//! - `Task`: Represents a task with title, description, priority, status, and project fields.
+//! - `Task`: Represents a task with title, description, priority, status, and project fields, plus an optional due date, tags and contexts.
+//! - `Config`: Application settings read from `config.json`.
+//! - `DailyPlans`: The titles of the tasks chosen for each day with `today add`.
+//! - `TimeEntry`: Hours worked on a task on a given day.
+//! - `Reminder`: When to remind of a task, given by `tick` (see the `reminder` module).
+//! - `EscalationRule`: A configured rule raising the priority of, and tagging, tasks close to their due date.
+//! - `EmailConfig`: The mail server and addresses `digest` sends its summary with (see the `digest` module).
+//! - `Place`: Where a context is, for `export --format geojson` (see the `places` module).
+//! - `ReviewConfig`: How often `review` reviews each project (see the `review` module).
+//! - `CalendarConfig`: The weekend days and holidays skipped by due dates given in working days (see the `calendar` module).
+//! - `RetentionConfig`: When `tick` moves finished tasks to the archive or the trash (see the `retention` module).
+//! - `WipConfig`: Limits on the tasks in a status or In Progress in a project (see the `wip` module).
+//! - `MatrixConfig`, `Quadrant`: The thresholds and quadrants of the Eisenhower matrix shown by `matrix`.
+//! - `StaleConfig`: When open tasks count as going stale, and whether `list` says so.
+//! - `StoreKind`: Selects between the plain JSON store, the conflict-free `crdt` store, the compact `binary` store and the streamed `ndjson` store.
+//! - `TaskStream`: Tasks read one at a time from the store.
+//! - `Timings`: How long each phase of a command took, shown with `--timings`.
+//! - `ErrorKind`: What went wrong, as reported with `--json`.
+//! - `Compression`: Selects whether the JSON store is compressed with gzip or zstd (see the `compress` module).
+//! - `S3Config`: Where and how to log in to the object storage keeping an `s3://` store (see the `s3` module).
+//! - `CheckConfig`, `Severity`: Rules and severities of the hygiene checks run by `check` (see the `check` module).
+//! - `Formatter`: Formats dates, relative times and numbers for the configured locale, timezone and week start, and priorities and statuses in the configured theme (see the `format` and `theme` modules).
+//!
+//! User-facing messages are looked up with the `t!` macro from the Fluent catalogs in `locales/` (see the `i18n` module), in the language of the display locale.
+//!
+//! Credentials, such as the keys of an S3 store, can be kept in the OS keyring with `auth login` (see the `secrets` module).
+//! The files live in the data directory, chosen per platform unless the current directory already holds a store (see the `platform` module).
+//!
+//! Unknown subcommands are run as plugins: executables named `task-manager-<name>` on the `PATH` (see the `plugin` module).
+//! `project analyze` finds the critical path through a project's dependencies (see the `critical` module).
+//! Sandboxed WASM plugins can hook into `add` and `list` (see the `wasm` module), and Rhai scripts run with `script run` can report on and edit the tasks (see the `script` module).
+//!
+//! `export --format atom` renders recently added and completed tasks as an Atom feed (see the `feed` module), and `export --format dot` or `mermaid` the dependencies between tasks as a graph (see the `graph` module).
+//! `add --from-clipboard` turns the text on the clipboard into a task (see the `clipboard` module).
+//! `capture` adds a task from a single line such as `Fix login bug +Backend @work p:4 due:friday` (see the `capture` module).
+//! `goal` connects tasks to objectives with key results and reports their progress (see the `goal` module).
+//! `habit` tracks recurring habits and their streaks, separately from the tasks (see the `habit` module).
+//! `list` and `export` pick tasks with the same filter flags (see the `query` module).
+//! `--where`, `list --sort` and `list --columns` also work on computed fields such as urgency and age (see the `computed` module).
+//! `list --table` and `list --columns` show the tasks as a table fitting the terminal (see the `table` module).
+//! `export --format opml` and `import --format opml` exchange the tasks with outliners (see the `opml` module).
+//! `export --format contexts` and `geojson` write the tasks by context with the places in `config.json`, for phones (see the `places` module).
+//! Imports look for likely duplicates of existing tasks by fuzzy matching their titles (see the `dedup` module).
+//! `export --archive` and `import --archive` move the whole data directory to another machine (see the `workspace` module).
+//! `open`, `update`, `done`, `remove` and other commands taking a title also accept the task's slug or fragments of its title (see the `slug` module), or its number in the last listing (see the `listing` module).
+//! While a remote store cannot be reached, commands work on the copy last read and `sync flush` sends their changes later (see the `offline` module).
+//! `review` lists the projects and tasks due for their regular review (see the `review` module).
 //!
 //! ## Traits
 //!
@@ -29,297 +132,5130 @@
 //!
 //! - `clap`: Used for parsing command-line arguments.
 //! - `serde`: Used for JSON serialization and deserialization.
+//! - `chrono`: Used for due dates.
+//! - `rand`: Used to pick a task in `suggest`.
+//! - `flate2`: Used to compress backups and the JSON store.
+//! - `zstd`: Used to compress the JSON store.
+//! - `rmp-serde`: Used by the binary MessagePack store.
+//! - `roxmltree`: Used to import OPML outlines.
+//! - `unicode-width`: Used to lay out tables.
+//! - `libc`: Used to read the terminal width on Unix.
+//! - `arboard`: Used to read the clipboard, with the default `clipboard` feature.
+//! - `rhai`: Used to run user scripts.
+//! - `wasmtime`: Used to run WASM plugins, with the default `wasm-plugins` feature.
+//! - `automerge`: Used by the conflict-free store that merges concurrent edits from synced folders.
+//! - `chrono-tz`: Used to display dates in the configured timezone.
+//! - `fluent-bundle`, `unic-langid`: Used to translate user-facing messages.
 
+mod backup;
+mod calendar;
+mod capture;
+mod check;
+mod clipboard;
+mod compress;
+mod computed;
+mod conflict;
+mod crdt;
+mod critical;
+mod crypto;
+mod dedup;
+mod digest;
+mod feed;
+mod format;
+mod goal;
+mod graph;
+mod habit;
+mod i18n;
+mod listing;
+mod offline;
+mod opml;
+mod places;
+mod platform;
+mod plugin;
+mod query;
+mod reminder;
+mod remote;
+mod repair;
+mod retention;
+mod review;
+mod s3;
+mod script;
+mod secrets;
+mod slug;
+mod table;
+mod theme;
+mod wasm;
+mod wip;
+mod workspace;
+
+use backup::BackupConfig;
+use calendar::CalendarConfig;
+use check::{CheckConfig, Severity};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
 use clap::ArgMatches;
-use clap::{App, Arg, SubCommand};
+use clap::{App, AppSettings, Arg, SubCommand};
+use compress::Compression;
+use computed::Sort;
+use dedup::OnDuplicate;
+use digest::EmailConfig;
+use format::{DisplayConfig, Formatter, Locale};
+use i18n::t;
+use places::Place;
+use query::Query;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use reminder::Reminder;
+use retention::RetentionConfig;
+use review::ReviewConfig;
+use s3::S3Config;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
 use std::error::Error;
 use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use table::Column;
+use wip::WipConfig;
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
 
-/// `Task`: Represents a task with title, description, priority, status, and project fields.
+/// `Task`: Represents a task with title, description, priority, status, and project fields, plus an optional due date and tags.
 struct Task {
     title: String,
+    /// The name the task goes by on the command line, made from its first title and kept when
+    /// the title changes (see the `slug` module).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
     description: String,
     priority: u8,
     status: String,
     project: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<NaiveDate>,
+    /// The time the task is due, for tasks due at a time rather than some time on `due`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Where or with what the task can be done, such as `work` or `phone`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    contexts: Vec<String>,
+    /// Names of the escalation rules already applied to this task, so each applies only once.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    escalations: Vec<String>,
+    /// Titles of the tasks that have to be done before this one can start.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<String>,
+    /// Set when the task was automatically unblocked and has not been shown by `list` since.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    newly_unblocked: bool,
+    /// Estimated effort in hours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    estimate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    time_entries: Vec<TimeEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reminders: Vec<Reminder>,
+    /// When the task was added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    /// When the task was marked done.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    completed_at: Option<DateTime<Utc>>,
+    /// When work on the task started, i.e. it was marked In Progress.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    started_at: Option<DateTime<Utc>>,
+    /// When the task was marked blocked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blocked_at: Option<DateTime<Utc>>,
+    /// The person or event a blocked task is waiting on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    waiting_on: Option<String>,
+    /// The person a task was delegated to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    /// When to check back on a delegated task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    follow_up: Option<NaiveDate>,
+    /// When the task was last edited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+    /// How many times the task was changed, so `update --if-revision` can tell whether someone
+    /// else changed it in between.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    revision: u64,
+    /// How often the task is reviewed, in days (see the `review` module).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    review_every: Option<u32>,
+    /// When the task was last marked reviewed with `review mark`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_reviewed: Option<DateTime<Utc>>,
+    /// The place of the task in its board column, set with `board move-up` and `move-down`.
+    /// Tasks without one follow those with one, in the order they were added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    order: Option<u32>,
+    /// The name of the goal the task works towards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    goal: Option<String>,
+    /// The key result of the goal the task works towards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_result: Option<String>,
+    /// The key of the task in the system it is imported from, such as `JIRA-123`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+    /// A link to what the task is about, such as a pull request, opened with `open`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    /// Fields this version does not know, e.g. written by a newer version or another tool, kept
+    /// so that saving the task does not drop them.
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
 }
 
-/// Saves a vector of tasks to a JSON file.
-fn save_tasks(tasks: &[Task]) -> Result<(), Box<dyn Error>> {
-    let serialized = serde_json::to_string_pretty(tasks)?;
-    fs::write("tasks.json", serialized)?;
-    Ok(())
+/// `TimeEntry`: Hours worked on a task on a given day.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct TimeEntry {
+    date: NaiveDate,
+    hours: f64,
 }
 
-/// Loads tasks from a JSON file.
-fn load_tasks() -> Result<Vec<Task>, Box<dyn Error>> {
-    let contents = fs::read_to_string("tasks.json").unwrap_or_default();
-    let tasks: Vec<Task> = serde_json::from_str(&contents)?;
-    Ok(tasks)
+/// The file `key generate` saves a key to when `encryption_key` names none.
+const DEFAULT_KEY_FILE: &str = "tasks.key";
+
+/// The project captured tasks are filed in when the capture line names none.
+const INBOX_PROJECT: &str = "Inbox";
+
+/// `DailyPlans`: The titles of the tasks chosen for each day with `today add`.
+type DailyPlans = BTreeMap<NaiveDate, Vec<String>>;
+
+/// `Config`: Application settings read from `config.json`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct Config {
+    store: StoreKind,
+    escalation: Vec<EscalationRule>,
+    display: DisplayConfig,
+    check: CheckConfig,
+    backup: BackupConfig,
+    /// Directory to load WASM plugins from.
+    wasm_plugins: Option<String>,
+    /// How the JSON store is compressed.
+    compression: Compression,
+    stale: StaleConfig,
+    matrix: MatrixConfig,
+    wip: WipConfig,
+    /// Other data directories by name, listed together with this one by `list --all-stores`.
+    stores: BTreeMap<String, String>,
+    /// Where the JSON store is kept remotely, as in `ssh://host/path/tasks.json` or
+    /// `s3://bucket/tasks.json` (see the `remote` module).
+    remote: Option<String>,
+    s3: S3Config,
+    /// The file holding the key the JSON store is encrypted with (see the `crypto` module).
+    encryption_key: Option<String>,
+    retention: RetentionConfig,
+    calendar: CalendarConfig,
+    email: EmailConfig,
+    /// Where the contexts of tasks are, by context, for `export --format geojson`.
+    places: BTreeMap<String, Vec<Place>>,
+    review: ReviewConfig,
 }
 
-/// Lists all tasks with the same project name.
-fn list_tasks_by_project(tasks: &[Task], project_name: &str) {
-    let filtered_tasks: Vec<&Task> = tasks
-        .iter()
-        .filter(|task| task.project == project_name)
-        .collect();
+/// `MatrixConfig`: Where `matrix` draws the line between urgent and not, and important and not.
+///
+/// For example `{ "urgent_within_days": 3, "important_priority": 3 }` takes tasks due within 3
+/// days, or overdue, as urgent and tasks of priority 3 and up as important.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+struct MatrixConfig {
+    urgent_within_days: i64,
+    important_priority: u8,
+}
 
-    for (index, task) in filtered_tasks.iter().enumerate() {
-        println!("Task {}: {:#?}", index + 1, task);
+impl Default for MatrixConfig {
+    fn default() -> Self {
+        MatrixConfig {
+            urgent_within_days: 3,
+            important_priority: 3,
+        }
     }
 }
 
-/// Lists all tasks with the same status.
-fn list_tasks_by_status(tasks: &[Task], status: &str) {
-    let filtered_tasks: Vec<&Task> = tasks.iter().filter(|task| task.status == status).collect();
+/// `Quadrant`: A quadrant of the Eisenhower matrix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quadrant {
+    /// Urgent and important.
+    DoFirst,
+    /// Important, but not urgent.
+    Schedule,
+    /// Urgent, but not important.
+    Delegate,
+    /// Neither urgent nor important.
+    Eliminate,
+}
+
+impl Quadrant {
+    const ALL: [Quadrant; 4] = [
+        Quadrant::DoFirst,
+        Quadrant::Schedule,
+        Quadrant::Delegate,
+        Quadrant::Eliminate,
+    ];
+
+    /// The quadrant of a task, from its due date and priority.
+    fn of(task: &Task, today: NaiveDate, config: &MatrixConfig) -> Quadrant {
+        let urgent = task
+            .due
+            .is_some_and(|due| (due - today).num_days() <= config.urgent_within_days);
+        let important = task.priority >= config.important_priority;
+        match (urgent, important) {
+            (true, true) => Quadrant::DoFirst,
+            (false, true) => Quadrant::Schedule,
+            (true, false) => Quadrant::Delegate,
+            (false, false) => Quadrant::Eliminate,
+        }
+    }
 
-    for (index, task) in filtered_tasks.iter().enumerate() {
-        println!("Task {}: {:#?}", index + 1, task);
+    fn heading(self) -> &'static str {
+        match self {
+            Quadrant::DoFirst => "quadrant-do-first",
+            Quadrant::Schedule => "quadrant-schedule",
+            Quadrant::Delegate => "quadrant-delegate",
+            Quadrant::Eliminate => "quadrant-eliminate",
+        }
     }
 }
 
-/// Lists all tasks with the same priority number.
-fn list_tasks_by_priority(tasks: &[Task], priority: u8) {
-    let filtered_tasks: Vec<&Task> = tasks
-        .iter()
-        .filter(|task| task.priority == priority)
-        .collect();
+/// `StaleConfig`: When open tasks count as going stale.
+///
+/// For example `{ "after_days": 30, "nag": true }` lists tasks untouched for more than 30 days in
+/// `stale`, and counts them at the top of `list`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+struct StaleConfig {
+    after_days: i64,
+    nag: bool,
+}
 
-    for (index, task) in filtered_tasks.iter().enumerate() {
-        println!("Task {}: {:#?}", index + 1, task);
+impl Default for StaleConfig {
+    fn default() -> Self {
+        StaleConfig {
+            after_days: 14,
+            nag: false,
+        }
     }
 }
 
-/// Updates a task based on command-line arguments.
-fn update_task(matches: &ArgMatches, tasks: &mut Vec<Task>) -> Result<(), &'static str> {
-    let title = matches.value_of("title").unwrap();
+/// `EscalationRule`: Raises the priority of, and tags, tasks that are close to their due date.
+///
+/// For example `{ "name": "due-soon", "within_days": 2, "raise_priority": 1, "add_tag": "urgent" }`.
+#[derive(Debug, Deserialize, Serialize)]
+struct EscalationRule {
+    name: String,
+    within_days: i64,
+    #[serde(default)]
+    raise_priority: u8,
+    #[serde(default)]
+    add_tag: Option<String>,
+}
 
-    if let Some(task) = tasks.iter_mut().find(|t| t.title == title) {
-        if let Some(new_description) = matches.value_of("description") {
-            task.description = new_description.to_string();
+/// `StoreKind`: The format tasks are persisted in.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum StoreKind {
+    /// A plain `tasks.json` file.
+    #[default]
+    Json,
+    /// A conflict-free `tasks.automerge` document for stores synced between machines.
+    Crdt,
+    /// A compact MessagePack `tasks.msgpack` file for very large task sets.
+    Binary,
+    /// A `tasks.ndjson` file with one task per line, which listings read one task at a time.
+    Ndjson,
+}
+
+/// The file the binary store is kept in.
+const BINARY_STORE_FILE: &str = "tasks.msgpack";
+/// The file the NDJSON store is kept in.
+const NDJSON_STORE_FILE: &str = "tasks.ndjson";
+
+/// `Timings`: How long each phase of a command took, printed to stderr with `--timings` once the
+/// command finishes.
+struct Timings {
+    enabled: bool,
+    last: Instant,
+    phases: Vec<(&'static str, std::time::Duration)>,
+}
+
+impl Timings {
+    fn new() -> Timings {
+        Timings {
+            enabled: false,
+            last: Instant::now(),
+            phases: Vec::new(),
         }
-        if let Some(new_priority) = matches.value_of("priority") {
-            task.priority = new_priority.parse::<u8>().map_err(|_| "Invalid priority")?;
+    }
+
+    /// Ends a phase, which started when the previous one ended.
+    fn lap(&mut self, phase: &'static str) {
+        let now = Instant::now();
+        self.phases.push((phase, now - self.last));
+        self.last = now;
+    }
+
+    /// Ends the command and gives the report, once, if `--timings` was given.
+    fn report(&mut self) -> Vec<String> {
+        if !self.enabled {
+            return Vec::new();
         }
-        if let Some(new_status) = matches.value_of("status") {
-            task.status = new_status.to_string();
+        self.enabled = false;
+        self.lap("timing-command");
+        let mut lines = vec![t!("timings-heading")];
+        for (phase, duration) in &self.phases {
+            let ms = format!("{:.1}", duration.as_secs_f64() * 1000.0);
+            lines.push(format!("  {}", t!(phase, ms = ms)));
         }
-        if let Some(new_project) = matches.value_of("project") {
-            task.project = new_project.to_string();
+        lines
+    }
+
+    /// Prints the report and exits, which skips dropping the timings.
+    fn exit(&mut self, code: i32) -> ! {
+        for line in self.report() {
+            eprintln!("{}", line);
         }
-        save_tasks(tasks).map_err(|_| "Failed to save tasks")?;
-        Ok(())
-    } else {
-        Err("Task not found")
+        std::process::exit(code)
     }
 }
 
-/// The entry point of the application, which handles command-line arguments and performs corresponding actions on tasks.
-fn main() {
-    let matches = App::new("Task Manager")
-        .version("1.0")
-        .author("Me")
-        .about("A console-based task management application")
-        .subcommand(
-            SubCommand::with_name("add")
-                .about("Add a new task")
-                .arg(
-                    Arg::with_name("title")
-                        .index(1)
-                        .required(true)
-                        .takes_value(true),
-                )
-                .arg(
-                    Arg::with_name("description")
-                        .index(2)
-                        .required(true)
-                        .takes_value(true),
-                )
-                .arg(
-                    Arg::with_name("priority")
-                        .index(3)
-                        .required(true)
-                        .takes_value(true),
-                )
-                .arg(
-                    Arg::with_name("status")
-                        .index(4)
-                        .required(true)
-                        .takes_value(true),
-                )
-                .arg(
-                    Arg::with_name("project")
-                        .index(5)
-                        .required(true)
-                        .takes_value(true),
-                ),
-        )
-        .subcommand(
-            SubCommand::with_name("remove").about("Remove a task").arg(
-                Arg::with_name("title")
-                    .index(1)
-                    .required(true)
-                    .takes_value(true),
-            ),
-        )
-        .subcommand(SubCommand::with_name("list").about("List all tasks"))
-        .subcommand(
-            SubCommand::with_name("search")
-                .about("Search for tasks by title or description")
-                .arg(
-                    Arg::with_name("query")
-                        .index(1)
-                        .required(true)
-                        .takes_value(true),
-                ),
-        )
-        .subcommand(
-            SubCommand::with_name("list-by-project")
-                .about("List tasks by project")
-                .arg(
-                    Arg::with_name("project")
-                        .long("project")
-                        .takes_value(true)
-                        .required(true),
-                ),
-        )
-        .subcommand(
-            SubCommand::with_name("list-by-status")
-                .about("List tasks by status")
-                .arg(
-                    Arg::with_name("status")
-                        .long("status")
-                        .takes_value(true)
-                        .required(true),
-                ),
-        )
-        .subcommand(
-            SubCommand::with_name("list-by-priority")
-                .about("List tasks by priority")
-                .arg(
-                    Arg::with_name("priority")
-                        .long("priority")
-                        .takes_value(true)
-                        .required(true),
-                ),
-        )
-        .subcommand(
-            SubCommand::with_name("update")
-                .about("Update a task")
-                .arg(
-                    Arg::with_name("title")
-                        .index(1)
-                        .required(true)
-                        .takes_value(true),
-                )
-                .arg(
-                    Arg::with_name("description")
-                        .long("description")
-                        .takes_value(true),
-                )
-                .arg(
-                    Arg::with_name("priority")
-                        .long("priority")
-                        .takes_value(true),
-                )
-                .arg(Arg::with_name("status").long("status").takes_value(true))
-                .arg(Arg::with_name("project").long("project").takes_value(true)),
-        )
-        .get_matches();
+impl Drop for Timings {
+    fn drop(&mut self) {
+        for line in self.report() {
+            eprintln!("{}", line);
+        }
+    }
+}
 
-    let mut tasks = load_tasks().unwrap_or_else(|_| vec![]);
+/// Set with `--json` to report errors as JSON on stderr.
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
 
-    match matches.subcommand() {
-        ("add", Some(sub_m)) => {
-            let title = sub_m.value_of("title").unwrap();
-            let description = sub_m.value_of("description").unwrap();
-            let priority = sub_m.value_of("priority").unwrap().parse::<u8>().unwrap();
-            let status = sub_m.value_of("status").unwrap();
-            let project = sub_m.value_of("project").unwrap();
+/// Set once an error is reported, so the command exits with status 1.
+static FAILED: AtomicBool = AtomicBool::new(false);
 
-            let new_task = Task {
-                title: title.to_string(),
-                description: description.to_string(),
-                priority,
-                status: status.to_string(),
-                project: project.to_string(),
-            };
+/// `ErrorKind`: What went wrong, as reported to machine consumers with `--json`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+enum ErrorKind {
+    NotFound,
+    InvalidArgument,
+    Conflict,
+    Config,
+    Store,
+    Plugin,
+    Io,
+    Failed,
+}
 
-            tasks.push(new_task);
-            save_tasks(&tasks).unwrap();
-            println!("Task added successfully!");
+impl ErrorKind {
+    /// The kind of an error given as a message id. Ids not known here are plain failures.
+    fn of(id: &str) -> ErrorKind {
+        match id {
+            "task-not-found"
+            | "dependency-not-found"
+            | "goal-not-found"
+            | "key-result-not-found" => ErrorKind::NotFound,
+            "dependency-cycle" => ErrorKind::Conflict,
+            "save-failed" | "goals-unreadable" => ErrorKind::Store,
+            "nonexistent-due-time" => ErrorKind::InvalidArgument,
+            id if id.starts_with("invalid-") => ErrorKind::InvalidArgument,
+            _ => ErrorKind::Failed,
         }
-        ("remove", Some(sub_m)) => {
-            let title = sub_m.value_of("title").unwrap();
-            tasks.retain(|task| task.title != title);
-            save_tasks(&tasks).unwrap();
-            println!("Task removed successfully!");
+    }
+}
+
+/// Warns about the WIP limits a change goes over. Returns whether to go ahead with it, which is
+/// only refused when limits are enforced and not forced.
+fn check_wip_limits(exceeded: &[String], config: &WipConfig, force: bool) -> bool {
+    if exceeded.is_empty() {
+        return true;
+    }
+    if config.warn_only || force {
+        for message in exceeded {
+            println!("{}", t!("warning", message = message.as_str()));
         }
-        ("list", _) => {
-            for (index, task) in tasks.iter().enumerate() {
-                println!("Task {}: {:#?}", index + 1, task);
-            }
+        return true;
+    }
+    for message in exceeded {
+        let message = t!("wip-limit-refused", reason = message.as_str());
+        report_error(ErrorKind::Conflict, &message, None);
+    }
+    false
+}
+
+/// Reports an error, about the task with the given title if any: as text on stdout, or with
+/// `--json` as an object like `{"error": "NotFound", "message": "…", "title": "…"}` on stderr.
+/// The command then exits with status 1.
+fn report_error(kind: ErrorKind, message: &str, title: Option<&str>) {
+    FAILED.store(true, Ordering::Relaxed);
+    report_warning(kind, message, title);
+}
+
+/// Reports an error the command works around, such as an unreachable server whose store is
+/// worked on offline, like `report_error` but without failing the command.
+fn report_warning(kind: ErrorKind, message: &str, title: Option<&str>) {
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        eprintln!("{}", error_json(kind, message, title));
+    } else {
+        println!("{}", t!("error", message = message));
+    }
+}
+
+/// An error as it is reported with `--json`.
+fn error_json(kind: ErrorKind, message: &str, title: Option<&str>) -> serde_json::Value {
+    let mut error = serde_json::json!({ "error": kind, "message": message });
+    if let Some(title) = title {
+        error["title"] = serde_json::Value::from(title);
+    }
+    error
+}
+
+/// Finds the task a command refers to by title, slug, fragments of its title or its number in
+/// the last listing, reporting when there is none. When several tasks match, the user picks one,
+/// or without a terminal to ask on, they are reported.
+fn find_task(tasks: &[Task], reference: &str) -> Option<usize> {
+    let exact = tasks
+        .iter()
+        .any(|task| task.title == reference || task.slug.as_deref() == Some(reference));
+    if let Some(number) = reference.parse::<usize>().ok().filter(|_| !exact) {
+        let Some(listed) = listing::lookup(number) else {
+            let message = t!("task-number-not-listed", number = number);
+            report_error(ErrorKind::NotFound, &message, Some(reference));
+            return None;
+        };
+        let index = tasks
+            .iter()
+            .position(|task| task.slug.as_ref() == Some(&listed) || task.title == listed);
+        if index.is_none() {
+            report_error(ErrorKind::NotFound, &t!("task-not-found"), Some(reference));
         }
-        ("list-by-project", Some(sub_m)) => {
-            if let Some(project_name) = sub_m.value_of("project") {
-                list_tasks_by_project(&tasks, project_name);
-            } else {
-                println!("Please provide a project name with the --project option");
-            }
+        return index;
+    }
+    match slug::find(tasks, reference) {
+        slug::Found::One(index) => Some(index),
+        slug::Found::None => {
+            report_error(ErrorKind::NotFound, &t!("task-not-found"), Some(reference));
+            None
         }
-        ("list-by-status", Some(sub_m)) => {
-            if let Some(status) = sub_m.value_of("status") {
-                list_tasks_by_status(&tasks, status);
-            } else {
-                println!("Please provide a status with the --status option");
+        slug::Found::Candidates(candidates) if io::stdin().is_terminal() => {
+            ask_task(tasks, reference, &candidates)
+        }
+        slug::Found::Candidates(candidates) => {
+            let titles: Vec<&str> = candidates
+                .iter()
+                .map(|&i| tasks[i].title.as_str())
+                .collect();
+            let message = t!(
+                "task-ambiguous",
+                reference = reference,
+                count = titles.len(),
+                titles = titles.join(", ")
+            );
+            report_error(ErrorKind::InvalidArgument, &message, Some(reference));
+            None
+        }
+    }
+}
+
+/// Asks which of the tasks a reference could mean was meant.
+fn ask_task(tasks: &[Task], reference: &str, candidates: &[usize]) -> Option<usize> {
+    println!(
+        "{}",
+        t!(
+            "task-candidates",
+            reference = reference,
+            count = candidates.len()
+        )
+    );
+    for (number, &index) in candidates.iter().enumerate() {
+        println!("  {}. {}", number + 1, tasks[index].title);
+    }
+    loop {
+        print!("{} ", t!("task-choose-prompt"));
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() || answer.is_empty() {
+            return None;
+        }
+        let answer = answer.trim().to_lowercase();
+        if let Some(number) = answer
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=candidates.len()).contains(n))
+        {
+            return Some(candidates[number - 1]);
+        }
+        if t!("rank-quit-answers")
+            .split(',')
+            .any(|a| a.trim() == answer)
+        {
+            println!("{}", t!("aborted"));
+            return None;
+        }
+    }
+}
+
+/// `TaskStream`: Tasks read one at a time from the store.
+type TaskStream = Box<dyn Iterator<Item = Result<Task, Box<dyn Error>>>>;
+
+/// Loads the application settings from a JSON file, falling back to the defaults when it is missing.
+fn load_config() -> Result<Config, Box<dyn Error>> {
+    let contents = fs::read_to_string("config.json").unwrap_or_default();
+    if contents.trim().is_empty() {
+        return Ok(Config::default());
+    }
+    let config: Config = serde_json::from_str(&contents)?;
+    Ok(config)
+}
+
+/// Saves a vector of tasks to the configured store.
+fn save_tasks(tasks: &[Task]) -> Result<(), Box<dyn Error>> {
+    if repair::has_skipped() {
+        return Err(t!("store-has-malformed").into());
+    }
+    let config = load_config()?;
+    let key = store_key(&config)?;
+    if let Some(remote) = remote_store(&config)? {
+        let bytes = json_store_bytes(tasks, config.compression, key.as_ref())?;
+        if offline::is_queueing() {
+            offline::queue(&bytes)?;
+            println!("{}", t!("remote-queued"));
+            return Ok(());
+        }
+        return match remote.push(&bytes) {
+            Ok(()) => offline::remember(&bytes, remote::version()),
+            Err(err) if err.is::<remote::Changed>() => {
+                // Keep the refused change for `sync conflicts`
+                fs::write(conflict::CONFLICT_FILE, &bytes)?;
+                Err(err)
             }
+            Err(err) if err.is::<remote::Unreachable>() => {
+                report_warning(ErrorKind::Store, &err.to_string(), None);
+                offline::queue(&bytes)?;
+                println!("{}", t!("remote-queued"));
+                Ok(())
+            }
+            Err(err) => Err(err),
+        };
+    }
+    if conflict::changed_on_disk() {
+        // Keep the refused change for `sync conflicts`, or for merging right away
+        let bytes = json_store_bytes(tasks, config.compression, key.as_ref())?;
+        fs::write(conflict::CONFLICT_FILE, bytes)?;
+        return Err(Box::new(conflict::ChangedOnDisk));
+    }
+    save_tasks_to(config.store, config.compression, key.as_ref(), tasks)?;
+    if let Some(path) = local_store_path(&config) {
+        conflict::remember(&path);
+    }
+    Ok(())
+}
+
+/// The file the local store is kept in, unless it is a CRDT store, which merges changes made
+/// by others instead.
+fn local_store_path(config: &Config) -> Option<String> {
+    match config.store {
+        StoreKind::Json => Some(json_store_path(config.compression)),
+        StoreKind::Binary => Some(String::from(BINARY_STORE_FILE)),
+        StoreKind::Ndjson => Some(String::from(NDJSON_STORE_FILE)),
+        StoreKind::Crdt => None,
+    }
+}
+
+/// Saves a vector of tasks to a store of the given kind, e.g. to convert between stores.
+fn save_tasks_to(
+    store: StoreKind,
+    compression: Compression,
+    key: Option<&crypto::Key>,
+    tasks: &[Task],
+) -> Result<(), Box<dyn Error>> {
+    if key.is_some() && store != StoreKind::Json {
+        return Err(t!("encryption-needs-json").into());
+    }
+    match store {
+        StoreKind::Crdt => return crdt::save_tasks(tasks),
+        StoreKind::Binary => {
+            fs::write(BINARY_STORE_FILE, rmp_serde::to_vec_named(tasks)?)?;
+            return Ok(());
         }
-        ("list-by-priority", Some(sub_m)) => {
-            if let Some(priority) = sub_m.value_of("priority") {
-                if let Ok(priority) = priority.parse::<u8>() {
-                    list_tasks_by_priority(&tasks, priority);
-                } else {
-                    println!(
-                        "Invalid priority value. Please provide a valid integer for priority."
-                    );
+        StoreKind::Ndjson => {
+            let file = fs::File::create(NDJSON_STORE_FILE)?;
+            return write_ndjson(io::BufWriter::new(file), tasks);
+        }
+        StoreKind::Json => {}
+    }
+    let path = json_store_path(compression);
+    fs::write(&path, json_store_bytes(tasks, compression, key)?)?;
+    // Remove the store left behind by a previous compression setting, so it cannot be read instead
+    for stale in Compression::ALL.map(json_store_path) {
+        if stale != path && Path::new(&stale).exists() {
+            fs::remove_file(stale)?;
+        }
+    }
+    Ok(())
+}
+
+/// Serializes tasks for the JSON store, compressed and, given a key, encrypted.
+fn json_store_bytes(
+    tasks: &[Task],
+    compression: Compression,
+    key: Option<&crypto::Key>,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let serialized = serde_json::to_string_pretty(tasks)?;
+    let compressed = compress::compress(serialized.as_bytes(), compression)?;
+    Ok(match key {
+        Some(key) => crypto::encrypt(&compressed, key),
+        None => compressed,
+    })
+}
+
+/// Reads the tasks from the JSON store, however it is compressed or encrypted. Records that are
+/// not valid tasks are skipped (see the `repair` module).
+fn json_store_tasks(data: Vec<u8>, key: Option<&crypto::Key>) -> Result<Vec<Task>, Box<dyn Error>> {
+    let contents = String::from_utf8(compress::decompress(&crypto::decrypt(data, key)?)?)?;
+    if contents.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let (tasks, malformed) = repair::parse_array(&contents)?;
+    malformed.into_iter().for_each(skip_malformed);
+    Ok(tasks)
+}
+
+/// Reports a record of the store that is not a valid task, and remembers it was skipped so the
+/// store is not saved over.
+fn skip_malformed(malformed: repair::Malformed) {
+    let message = t!(
+        "store-record-skipped",
+        record = malformed.record,
+        line = malformed.line,
+        error = malformed.error.as_str()
+    );
+    if repair::skip(malformed) {
+        report_error(ErrorKind::Store, &message, None);
+    }
+}
+
+/// The key the JSON store is encrypted with, if one is configured (see the `crypto` module).
+/// Only the JSON store can be encrypted, so a key for another store is refused rather than
+/// leaving it in plaintext.
+fn store_key(config: &Config) -> Result<Option<crypto::Key>, Box<dyn Error>> {
+    if config.encryption_key.is_some() && config.store != StoreKind::Json {
+        return Err(t!("encryption-needs-json").into());
+    }
+    let key = config.encryption_key.as_deref().map(crypto::Key::load);
+    Ok(key.transpose()?)
+}
+
+/// Loads tasks from the configured store.
+fn load_tasks() -> Result<Vec<Task>, Box<dyn Error>> {
+    let config = load_config()?;
+    if let Some(remote) = remote_store(&config)? {
+        let key = store_key(&config)?;
+        // Changes made offline are worked on until `sync flush` sends them
+        if let Some(pending) = offline::pending() {
+            return json_store_tasks(pending, key.as_ref());
+        }
+        let contents = match remote.fetch() {
+            Ok(contents) => {
+                offline::remember(&contents, remote::version())?;
+                contents
+            }
+            Err(err) if err.is::<remote::Unreachable>() => {
+                let Some(cached) = offline::go_offline() else {
+                    return Err(err);
+                };
+                let message = t!("remote-offline", error = err.to_string());
+                report_warning(ErrorKind::Store, &message, None);
+                cached
+            }
+            Err(err) => return Err(err),
+        };
+        return json_store_tasks(contents, key.as_ref());
+    }
+    if let Some(path) = local_store_path(&config) {
+        conflict::remember(&path);
+    }
+    match config.store {
+        StoreKind::Crdt => return crdt::load_tasks(),
+        StoreKind::Binary if !Path::new(BINARY_STORE_FILE).exists() => return Ok(vec![]),
+        StoreKind::Binary => return Ok(rmp_serde::from_slice(&fs::read(BINARY_STORE_FILE)?)?),
+        StoreKind::Ndjson => return stream_tasks()?.collect(),
+        StoreKind::Json => {}
+    }
+    // Fall back to a store saved with another compression setting
+    let path = std::iter::once(config.compression)
+        .chain(Compression::ALL)
+        .map(json_store_path)
+        .find(|path| Path::new(path).exists());
+    let Some(path) = path else {
+        return Ok(vec![]);
+    };
+    conflict::remember(&path);
+    json_store_tasks(fs::read(path)?, store_key(&config)?.as_ref())
+}
+
+/// The remote store configured, if any. Only the JSON store can be kept remotely.
+fn remote_store(config: &Config) -> Result<Option<remote::Remote>, Box<dyn Error>> {
+    let Some(url) = &config.remote else {
+        return Ok(None);
+    };
+    if config.store != StoreKind::Json {
+        return Err(t!("remote-needs-json").into());
+    }
+    Ok(Some(remote::Remote::parse(url, &config.s3)?))
+}
+
+/// Loads the tasks of another data directory, from the store configured there.
+fn load_tasks_from(dir: &Path) -> Result<Vec<Task>, Box<dyn Error>> {
+    let here = env::current_dir()?;
+    env::set_current_dir(dir)?;
+    let tasks = load_tasks();
+    env::set_current_dir(here)?;
+    tasks
+}
+
+/// Reads the tasks one at a time. Only the NDJSON store is actually streamed; other stores are
+/// loaded whole first.
+fn stream_tasks() -> Result<TaskStream, Box<dyn Error>> {
+    let config = load_config()?;
+    if config.store != StoreKind::Ndjson || config.remote.is_some() {
+        return Ok(Box::new(load_tasks()?.into_iter().map(Ok)));
+    }
+    if !Path::new(NDJSON_STORE_FILE).exists() {
+        return Ok(Box::new(std::iter::empty()));
+    }
+    let file = fs::File::open(NDJSON_STORE_FILE)?;
+    Ok(read_ndjson(io::BufReader::new(file)))
+}
+
+/// Deserializes tasks one at a time from newline-delimited JSON, skipping lines that are not
+/// valid tasks.
+fn read_ndjson(reader: impl io::BufRead + 'static) -> TaskStream {
+    let lines = reader.lines().enumerate();
+    Box::new(lines.filter_map(|(index, line)| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => match repair::parse_line(index + 1, &line) {
+            Ok(task) => Some(Ok(task)),
+            Err(malformed) => {
+                skip_malformed(malformed);
+                None
+            }
+        },
+        Err(err) => Some(Err(err.into())),
+    }))
+}
+
+/// Serializes tasks as newline-delimited JSON, one task per line.
+fn write_ndjson(mut writer: impl Write, tasks: &[Task]) -> Result<(), Box<dyn Error>> {
+    for task in tasks {
+        serde_json::to_writer(&mut writer, task)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// The file name of the JSON store, e.g. `tasks.json.gz` with gzip compression.
+fn json_store_path(compression: Compression) -> String {
+    format!("tasks.json{}", compression.extension())
+}
+
+/// Saves the daily plans to a JSON file.
+fn save_plans(plans: &DailyPlans) -> Result<(), Box<dyn Error>> {
+    let serialized = serde_json::to_string_pretty(plans)?;
+    fs::write("today.json", serialized)?;
+    Ok(())
+}
+
+/// Loads the daily plans from a JSON file.
+fn load_plans() -> Result<DailyPlans, Box<dyn Error>> {
+    let contents = match fs::read_to_string("today.json") {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+    if contents.trim().is_empty() {
+        return Ok(DailyPlans::new());
+    }
+    let plans: DailyPlans = serde_json::from_str(&contents)?;
+    Ok(plans)
+}
+
+/// Looks up the tasks chosen for a day, in the order they were chosen. Tasks that were removed
+/// since are skipped.
+fn planned_tasks<'a>(tasks: &'a [Task], plan: &[String]) -> Vec<&'a Task> {
+    plan.iter()
+        .filter_map(|title| tasks.iter().find(|task| task.title == *title))
+        .collect()
+}
+
+/// Scores how pressing a task is: its priority, plus up to 7 points as the due date approaches
+/// within the next two weeks, plus 10 points once it is overdue.
+fn urgency(task: &Task, today: NaiveDate) -> f64 {
+    let due_bonus = match task.due.map(|due| (due - today).num_days()) {
+        Some(days) if days < 0 => 10.0,
+        Some(days) if days <= 14 => (14 - days) as f64 / 2.0,
+        _ => 0.0,
+    };
+    task.priority as f64 + due_bonus
+}
+
+/// Groups the tasks of a project, or of all projects, into a board column per status: the usual
+/// statuses first, then any others, plus empty columns for statuses with a WIP limit.
+fn board_columns<'a>(
+    tasks: &'a [Task],
+    project: Option<&str>,
+    wip: &WipConfig,
+) -> Vec<(String, Vec<&'a Task>)> {
+    let mut columns: Vec<(String, Vec<&Task>)> = ["Todo", "In Progress", "Blocked", "Waiting"]
+        .iter()
+        .map(|status| (status.to_string(), Vec::new()))
+        .collect();
+    for task in tasks
+        .iter()
+        .filter(|task| project.is_none_or(|p| task.project == p))
+    {
+        match columns
+            .iter_mut()
+            .find(|(status, _)| status.eq_ignore_ascii_case(&task.status))
+        {
+            Some((_, column)) => column.push(task),
+            None => columns.push((task.status.clone(), vec![task])),
+        }
+    }
+    // Done goes last, however the first done task spelled it
+    if let Some(index) = columns.iter().position(|(status, _)| is_done(status)) {
+        let done = columns.remove(index);
+        columns.push(done);
+    }
+    columns.retain(|(status, column)| !column.is_empty() || wip.status_limit(status).is_some());
+    for (_, column) in &mut columns {
+        column.sort_by_key(|task| task.order.unwrap_or(u32::MAX));
+    }
+    columns
+}
+
+/// Moves a task one place up or down in its board column, numbering the places of the column
+/// as they are shown. With a project, as on `board --project`, the task changes places with the
+/// next task of that project, passing over the tasks not shown. Every task whose place changes is
+/// touched, so the new order syncs. Returns false when the task is already at that end of the
+/// column.
+fn move_in_column(tasks: &mut [Task], index: usize, up: bool, project: Option<&str>) -> bool {
+    let status = tasks[index].status.clone();
+    let mut column: Vec<usize> = (0..tasks.len())
+        .filter(|&i| tasks[i].status.eq_ignore_ascii_case(&status))
+        .collect();
+    column.sort_by_key(|&i| tasks[i].order.unwrap_or(u32::MAX));
+    let place = column.iter().position(|&i| i == index).unwrap();
+    let shown = |i: usize| project.is_none_or(|p| tasks[i].project == p);
+    let other = if up {
+        column[..place].iter().rposition(|&i| shown(i))
+    } else {
+        let below = column[place + 1..].iter().position(|&i| shown(i));
+        below.map(|offset| place + 1 + offset)
+    };
+    let Some(other) = other else {
+        return false;
+    };
+    column.swap(place, other);
+    for (order, &i) in (1..).zip(&column) {
+        if tasks[i].order != Some(order) {
+            tasks[i].order = Some(order);
+            touch(&mut tasks[i]);
+        }
+    }
+    true
+}
+
+/// Sorts the open tasks of a project, or of all projects, into the quadrants of the Eisenhower
+/// matrix, in the order of `Quadrant::ALL`, the most urgent first within a quadrant.
+fn priority_matrix<'a>(
+    tasks: &'a [Task],
+    project: Option<&str>,
+    today: NaiveDate,
+    config: &MatrixConfig,
+) -> [Vec<&'a Task>; 4] {
+    let mut quadrants: [Vec<&Task>; 4] = Default::default();
+    let open = tasks.iter().filter(|task| !is_done(&task.status));
+    for task in open.filter(|task| project.is_none_or(|p| task.project == p)) {
+        let quadrant = Quadrant::of(task, today, config);
+        let index = Quadrant::ALL.iter().position(|q| *q == quadrant).unwrap();
+        quadrants[index].push(task);
+    }
+    for quadrant in quadrants.iter_mut() {
+        quadrant.sort_by(|a, b| urgency(b, today).total_cmp(&urgency(a, today)));
+    }
+    quadrants
+}
+
+/// Lists the open tasks that can be worked on right now: not done, blocked or delegated, and not
+/// waiting on an unfinished dependency.
+fn actionable_tasks(tasks: &[Task]) -> Vec<&Task> {
+    tasks
+        .iter()
+        .filter(|task| {
+            !is_done(&task.status) && !is_blocked(&task.status) && !is_waiting(&task.status)
+        })
+        .filter(|task| {
+            !task.depends_on.iter().any(|dep| {
+                tasks
+                    .iter()
+                    .any(|other| other.title == *dep && !is_done(&other.status))
+            })
+        })
+        .collect()
+}
+
+/// Prints a numbered task with dates and numbers formatted for the configured locale.
+fn print_task(index: usize, task: &Task, fmt: &Formatter) {
+    listing::show(index, task);
+    println!(
+        "{}",
+        t!(
+            "task-heading",
+            index = index,
+            title = fmt.priority(task.priority, &task.title)
+        )
+    );
+    for detail in task_details(task, fmt) {
+        println!("    {}", detail);
+    }
+}
+
+/// Describes the fields of a task that are set, one line each.
+fn task_details(task: &Task, fmt: &Formatter) -> Vec<String> {
+    let mut details = vec![
+        t!("task-description", description = task.description.as_str()),
+        t!(
+            "task-priority",
+            priority = fmt.priority_label(task.priority)
+        ),
+        t!("task-status", status = fmt.status(&task.status)),
+        t!("task-project", project = task.project.as_str()),
+    ];
+    if let Some(due_at) = task.due_at {
+        if is_done(&task.status) {
+            details.push(t!("task-due", date = fmt.datetime(due_at)));
+        } else {
+            details.push(t!(
+                "task-due-relative",
+                date = fmt.datetime(due_at),
+                relative = fmt.relative_due_at(due_at, Utc::now())
+            ));
+        }
+    } else if let Some(due) = task.due {
+        if is_done(&task.status) {
+            details.push(t!("task-due", date = fmt.date(due)));
+        } else {
+            let relative = fmt.relative_due(due, fmt.today());
+            details.push(t!(
+                "task-due-relative",
+                date = fmt.date(due),
+                relative = relative
+            ));
+        }
+    }
+    if !task.tags.is_empty() {
+        details.push(t!("task-tags", tags = task.tags.join(", ")));
+    }
+    if !task.contexts.is_empty() {
+        let contexts: Vec<String> = task.contexts.iter().map(|c| format!("@{}", c)).collect();
+        details.push(t!("task-contexts", contexts = contexts.join(", ")));
+    }
+    if !task.depends_on.is_empty() {
+        details.push(t!("task-depends-on", tasks = task.depends_on.join(", ")));
+    }
+    if let Some(waiting_on) = &task.waiting_on {
+        details.push(t!("task-waiting-on", reason = waiting_on.as_str()));
+    }
+    if let Some(assignee) = &task.assignee {
+        details.push(match task.follow_up {
+            Some(date) => t!(
+                "task-delegated-follow-up",
+                assignee = assignee.as_str(),
+                date = fmt.date(date)
+            ),
+            None => t!("task-delegated-to", assignee = assignee.as_str()),
+        });
+    }
+    let tracked: f64 = task.time_entries.iter().map(|entry| entry.hours).sum();
+    match (task.estimate, task.time_entries.is_empty()) {
+        (Some(estimate), true) => {
+            details.push(t!("task-estimate", estimate = fmt.number(estimate, 2)))
+        }
+        (Some(estimate), false) => details.push(t!(
+            "task-estimate-tracked",
+            estimate = fmt.number(estimate, 2),
+            tracked = fmt.number(tracked, 2)
+        )),
+        (None, false) => details.push(t!("task-tracked", tracked = fmt.number(tracked, 2))),
+        (None, true) => {}
+    }
+    if let Some(completed_at) = task.completed_at {
+        details.push(t!("task-completed", date = fmt.datetime(completed_at)));
+    }
+    match (&task.goal, &task.key_result) {
+        (Some(goal), Some(key_result)) => details.push(t!(
+            "task-goal-key-result",
+            goal = goal.as_str(),
+            key_result = key_result.as_str()
+        )),
+        (Some(goal), None) => details.push(t!("task-goal", goal = goal.as_str())),
+        _ => {}
+    }
+    if let Some(external_id) = &task.external_id {
+        details.push(t!("task-external-id", id = external_id.as_str()));
+    }
+    if let Some(url) = &task.url {
+        details.push(t!("task-url", url = url.as_str()));
+    }
+    details
+}
+
+/// Renders everything about a task: its fields, time entries, history and the tasks it depends
+/// on or that depend on it.
+fn render_task_details(task: &Task, tasks: &[Task], fmt: &Formatter) -> String {
+    let mut text = format!("{}\n", fmt.priority(task.priority, &task.title));
+    let slug = task
+        .slug
+        .clone()
+        .unwrap_or_else(|| slug::slugify(&task.title));
+    let mut details = vec![t!(
+        "show-slug-revision",
+        slug = slug,
+        revision = task.revision
+    )];
+    details.extend(task_details(task, fmt));
+    for (name, value) in &task.extra {
+        details.push(format!("{}: {}", name, value));
+    }
+    for detail in details {
+        text.push_str(&format!("    {}\n", detail));
+    }
+
+    if !task.time_entries.is_empty() {
+        text.push('\n');
+        text.push_str(&format!("{}\n", t!("show-time-entries")));
+        for entry in &task.time_entries {
+            let hours = fmt.number(entry.hours, 2);
+            text.push_str(&format!(
+                "    {}  {}\n",
+                fmt.date(entry.date),
+                t!("show-hours", hours = hours)
+            ));
+        }
+    }
+
+    let mut history: Vec<(DateTime<Utc>, &str)> = [
+        (task.created_at, "show-history-added"),
+        (task.started_at, "show-history-started"),
+        (task.blocked_at, "show-history-blocked"),
+        (task.completed_at, "show-history-completed"),
+        (task.last_reviewed, "show-history-reviewed"),
+    ]
+    .into_iter()
+    .filter_map(|(at, id)| Some((at?, id)))
+    .collect();
+    history.sort_by_key(|(at, _)| *at);
+    // The last change may be a few moments older than what it changed
+    history.extend(task.updated_at.map(|at| (at, "show-history-updated")));
+    if !history.is_empty() {
+        text.push('\n');
+        text.push_str(&format!("{}\n", t!("show-history")));
+        for (at, id) in history {
+            text.push_str(&format!("    {}  {}\n", fmt.datetime(at), t!(id)));
+        }
+    }
+
+    let dependents: Vec<&Task> = tasks
+        .iter()
+        .filter(|other| other.depends_on.contains(&task.title))
+        .collect();
+    if !task.depends_on.is_empty() || !dependents.is_empty() {
+        text.push('\n');
+        text.push_str(&format!("{}\n", t!("show-dependencies")));
+        let branch = fmt.glyph("└─", "`-");
+        for title in &task.depends_on {
+            match tasks.iter().find(|other| &other.title == title) {
+                Some(dependency) => text.push_str(&format!(
+                    "    {} {}\n",
+                    fmt.status(&dependency.status),
+                    title
+                )),
+                None => text.push_str(&format!("    {} {}\n", t!("show-missing"), title)),
+            }
+        }
+        text.push_str(&format!("    {} {}\n", branch, task.title));
+        for dependent in dependents {
+            text.push_str(&format!(
+                "       {} {} {}\n",
+                branch,
+                fmt.status(&dependent.status),
+                dependent.title
+            ));
+        }
+    }
+    text
+}
+
+/// Prints the tasks a predicate matches as a numbered list, as they are read from the store.
+fn print_matching_tasks(
+    tasks: TaskStream,
+    predicate: impl Fn(&Task) -> bool,
+    fmt: &Formatter,
+) -> Result<(), Box<dyn Error>> {
+    let mut index = 0;
+    for task in tasks {
+        let task = task?;
+        if predicate(&task) {
+            index += 1;
+            print_task(index, &task, fmt);
+        }
+    }
+    listing::save()
+}
+
+/// Lists all tasks with the same project name.
+fn list_tasks_by_project(
+    tasks: TaskStream,
+    project_name: &str,
+    fmt: &Formatter,
+) -> Result<(), Box<dyn Error>> {
+    print_matching_tasks(tasks, |task| task.project == project_name, fmt)
+}
+
+/// Lists all tasks with the same status.
+fn list_tasks_by_status(
+    tasks: TaskStream,
+    status: &str,
+    fmt: &Formatter,
+) -> Result<(), Box<dyn Error>> {
+    print_matching_tasks(tasks, |task| task.status == status, fmt)
+}
+
+/// Lists all tasks with the same priority number.
+fn list_tasks_by_priority(
+    tasks: TaskStream,
+    priority: u8,
+    fmt: &Formatter,
+) -> Result<(), Box<dyn Error>> {
+    print_matching_tasks(tasks, |task| task.priority == priority, fmt)
+}
+
+/// Returns true when a status marks the task as finished.
+fn is_done(status: &str) -> bool {
+    status.eq_ignore_ascii_case("done")
+}
+
+/// Returns true when a status marks the task as being worked on.
+fn is_in_progress(status: &str) -> bool {
+    status.eq_ignore_ascii_case("in progress")
+}
+
+/// Returns true when a status marks the task as blocked.
+fn is_blocked(status: &str) -> bool {
+    status.eq_ignore_ascii_case("blocked")
+}
+
+/// Returns true when a status marks the task as waiting for someone else to finish it.
+fn is_waiting(status: &str) -> bool {
+    status.eq_ignore_ascii_case("waiting")
+}
+
+/// Records that a task was edited: when, and that it is at a new revision.
+fn touch(task: &mut Task) {
+    task.updated_at = Some(Utc::now());
+    task.revision += 1;
+}
+
+/// Tells whether a number is zero, to leave unchanged revisions out of the store.
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+/// Changes the status of a task, recording when it was started, completed or blocked.
+///
+/// Leaving the blocked status also clears what the task was waiting on, and leaving a status its
+/// place in the board column.
+fn set_status(task: &mut Task, status: &str) {
+    if !is_done(status) {
+        task.completed_at = None;
+    } else if task.completed_at.is_none() {
+        task.completed_at = Some(Utc::now());
+    }
+    if !is_in_progress(status) {
+        task.started_at = None;
+    } else if task.started_at.is_none() {
+        task.started_at = Some(Utc::now());
+    }
+    if !is_blocked(status) {
+        task.blocked_at = None;
+        task.waiting_on = None;
+    } else if task.blocked_at.is_none() {
+        task.blocked_at = Some(Utc::now());
+    }
+    // A task moving to another column goes to the end of it
+    if !task.status.eq_ignore_ascii_case(status) {
+        task.order = None;
+    }
+    task.status = status.to_string();
+}
+
+/// Parses a number of hours given on the command line.
+fn parse_hours(hours: &str) -> Result<f64, &'static str> {
+    match hours.parse::<f64>() {
+        Ok(hours) if hours.is_finite() && hours >= 0.0 => Ok(hours),
+        _ => Err("invalid-hours"),
+    }
+}
+
+/// Parses a due date given on the command line.
+fn parse_due(due: &str) -> Result<NaiveDate, &'static str> {
+    NaiveDate::parse_from_str(due, "%Y-%m-%d").map_err(|_| "invalid-due-date")
+}
+
+/// Parses a due date, a number of working days from today such as `3bd`, or a due time such as
+/// `2024-07-01 17:00` in the configured timezone. Returns the date and, for a time, the instant
+/// it is due.
+fn parse_due_at(
+    due: &str,
+    fmt: &Formatter,
+) -> Result<(NaiveDate, Option<DateTime<Utc>>), &'static str> {
+    if let Ok(date) = parse_due(due) {
+        return Ok((date, None));
+    }
+    if let Some(days) = calendar::parse_working_days(due) {
+        let due = fmt.calendar().add_working_days(fmt.today(), days);
+        return Ok((due.ok_or("invalid-due")?, None));
+    }
+    let local =
+        NaiveDateTime::parse_from_str(due.trim(), "%Y-%m-%d %H:%M").map_err(|_| "invalid-due")?;
+    let due_at = fmt.utc(local).ok_or("nonexistent-due-time")?;
+    Ok((local.date(), Some(due_at)))
+}
+
+/// Raises the urgency of open tasks that are within a rule's window of their due date.
+///
+/// Each rule is applied to a task at most once. Returns a description of every change made.
+fn apply_escalation_rules(
+    tasks: &mut [Task],
+    rules: &[EscalationRule],
+    today: NaiveDate,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    for task in tasks.iter_mut().filter(|task| !is_done(&task.status)) {
+        let Some(due) = task.due else { continue };
+        for rule in rules {
+            if (due - today).num_days() > rule.within_days || task.escalations.contains(&rule.name)
+            {
+                continue;
+            }
+            task.priority = task.priority.saturating_add(rule.raise_priority);
+            if let Some(tag) = &rule.add_tag {
+                if !task.tags.contains(tag) {
+                    task.tags.push(tag.clone());
+                }
+            }
+            task.escalations.push(rule.name.clone());
+            task.revision += 1;
+            changes.push(t!(
+                "escalated",
+                title = task.title.as_str(),
+                rule = rule.name.as_str()
+            ));
+        }
+    }
+    changes
+}
+
+/// Returns true when `from` depends on `target`, directly or through other tasks.
+fn depends_on_transitively(tasks: &[Task], from: &str, target: &str) -> bool {
+    let mut pending = vec![from];
+    let mut visited = Vec::new();
+    while let Some(title) = pending.pop() {
+        if title == target {
+            return true;
+        }
+        if visited.contains(&title) {
+            continue;
+        }
+        visited.push(title);
+        if let Some(task) = tasks.iter().find(|t| t.title == title) {
+            pending.extend(task.depends_on.iter().map(String::as_str));
+        }
+    }
+    false
+}
+
+/// The new due dates of the open tasks depending on a task, directly or through other tasks,
+/// when its due date moves by `delta`.
+fn cascade_due_dates(tasks: &[Task], title: &str, delta: Duration) -> Vec<(usize, NaiveDate)> {
+    tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| task.title != title && !is_done(&task.status))
+        .filter(|(_, task)| depends_on_transitively(tasks, &task.title, title))
+        .filter_map(|(index, task)| Some((index, task.due?.checked_add_signed(delta)?)))
+        .collect()
+}
+
+/// Moves a time due by whole days on the local clock, so it stays at the same time of day across
+/// a change to or from daylight saving time.
+fn shift_due_at(due_at: DateTime<Utc>, delta: Duration, fmt: &Formatter) -> DateTime<Utc> {
+    let local = fmt.local_datetime(due_at) + delta;
+    // A time the clocks skip on the new day moves by the same amount of time instead
+    fmt.utc(local).unwrap_or(due_at + delta)
+}
+
+/// Moves Blocked tasks whose dependencies are all done (or removed) back to Todo, unless they are
+/// also waiting on someone or something else.
+///
+/// The tasks are flagged so the next `list` shows them as newly unblocked. Returns their titles.
+fn unblock_ready_tasks(tasks: &mut [Task]) -> Vec<String> {
+    let open_titles: Vec<String> = tasks
+        .iter()
+        .filter(|task| !is_done(&task.status))
+        .map(|task| task.title.clone())
+        .collect();
+
+    let mut unblocked = Vec::new();
+    for task in tasks.iter_mut() {
+        if is_blocked(&task.status)
+            && task.waiting_on.is_none()
+            && !task.depends_on.is_empty()
+            && !task.depends_on.iter().any(|dep| open_titles.contains(dep))
+        {
+            set_status(task, "Todo");
+            task.newly_unblocked = true;
+            task.revision += 1;
+            unblocked.push(task.title.clone());
+        }
+    }
+    unblocked
+}
+
+/// Renumbers the priorities of a project's open tasks so they spread evenly over `1..=max_priority`.
+///
+/// The current ordering is kept: tasks are ranked by priority, then by due date, and the ranking
+/// is split into equally sized groups. Returns a description of every priority that changed.
+fn rebalance_priorities(tasks: &mut [Task], project: &str, max_priority: u8) -> Vec<String> {
+    let ranked = ranked_open_tasks(tasks, project);
+    assign_priorities(tasks, &ranked, max_priority)
+}
+
+/// Lists the indices of a project's open tasks, the most important first: by priority, then by
+/// due date.
+fn ranked_open_tasks(tasks: &[Task], project: &str) -> Vec<usize> {
+    let mut ranked: Vec<usize> = (0..tasks.len())
+        .filter(|&i| tasks[i].project == project && !is_done(&tasks[i].status))
+        .collect();
+    ranked.sort_by(|&a, &b| {
+        tasks[b]
+            .priority
+            .cmp(&tasks[a].priority)
+            .then_with(|| tasks[a].due.is_none().cmp(&tasks[b].due.is_none()))
+            .then_with(|| tasks[a].due.cmp(&tasks[b].due))
+    });
+    ranked
+}
+
+/// Gives ranked tasks priorities from `max_priority` for the first down to 1, splitting the
+/// ranking into equally sized groups. Returns a description of every priority that changed.
+fn assign_priorities(tasks: &mut [Task], ranked: &[usize], max_priority: u8) -> Vec<String> {
+    let mut changes = Vec::new();
+    let count = ranked.len();
+    for (rank, &index) in ranked.iter().enumerate() {
+        let group = (rank * max_priority as usize / count) as u8;
+        let priority = max_priority - group;
+        let task = &mut tasks[index];
+        if task.priority != priority {
+            changes.push(format!("{}: {} -> {}", task.title, task.priority, priority));
+            task.priority = priority;
+            task.revision += 1;
+        }
+    }
+    changes
+}
+
+/// Orders tasks by importance from pairwise choices, the most important first. `prefer` tells
+/// whether its first task is more important than its second, or `None` to stop ranking.
+///
+/// Each task is inserted into the tasks ranked so far by binary search, so a backlog of 40 tasks
+/// takes about 170 choices rather than one per pair.
+fn rank_tasks(
+    tasks: &[Task],
+    indices: &[usize],
+    mut prefer: impl FnMut(&Task, &Task) -> Option<bool>,
+) -> Option<Vec<usize>> {
+    let mut ranked: Vec<usize> = Vec::with_capacity(indices.len());
+    for &index in indices {
+        let (mut low, mut high) = (0, ranked.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            if prefer(&tasks[index], &tasks[ranked[middle]])? {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        ranked.insert(low, index);
+    }
+    Some(ranked)
+}
+
+/// Asks which of two tasks is more important, telling whether it is the first, or `None` when
+/// the user stops ranking.
+fn ask_preference(first: &Task, second: &Task) -> Option<bool> {
+    println!("{}", t!("rank-question"));
+    println!("  1) {}", first.title);
+    println!("  2) {}", second.title);
+    loop {
+        print!("{} ", t!("rank-prompt"));
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() || answer.is_empty() {
+            return None;
+        }
+        match answer.trim() {
+            "1" => return Some(true),
+            "2" => return Some(false),
+            answer
+                if t!("rank-quit-answers")
+                    .split(',')
+                    .any(|a| a.trim() == answer) =>
+            {
+                return None
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `Stop`: Why `triage` stopped asking about a task.
+enum Stop {
+    /// The task is left as it is, to triage later.
+    Skip,
+    /// Triage ends here.
+    Quit,
+}
+
+/// Asks for a field of a task in `triage`, showing its current value, and reads the answer from
+/// `input`. Returns the new value, or `None` for an empty answer, which keeps the current one. An
+/// answer that cannot be read is asked again.
+fn ask_field<T>(
+    input: &mut impl io::BufRead,
+    label: &str,
+    current: &str,
+    parse: impl Fn(&str) -> Result<T, &'static str>,
+) -> Result<Option<T>, Stop> {
+    loop {
+        print!("  {} [{}] ", label, current);
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if input.read_line(&mut answer).is_err() || answer.is_empty() {
+            return Err(Stop::Quit);
+        }
+        let answer = answer.trim();
+        let lowercase = answer.to_lowercase();
+        let answers = |id: &str| t!(id).split(',').any(|a| a.trim() == lowercase);
+        if answer.is_empty() {
+            return Ok(None);
+        } else if answers("rank-quit-answers") {
+            return Err(Stop::Quit);
+        } else if answers("triage-skip-answers") {
+            return Err(Stop::Skip);
+        }
+        match parse(answer) {
+            Ok(value) => return Ok(Some(value)),
+            Err(err) => println!("  {}", t!(err)),
+        }
+    }
+}
+
+/// Walks the user through filing an inbox task: asks for its project, priority, due date and
+/// contexts, then changes those given. Nothing is changed when the user skips the task or quits
+/// before the last question.
+fn triage_task(task: &mut Task, fmt: &Formatter, input: &mut impl io::BufRead) -> Result<(), Stop> {
+    let due = match (task.due_at, task.due) {
+        (Some(due_at), _) => fmt.datetime(due_at),
+        (None, Some(due)) => fmt.date(due),
+        (None, None) => String::from("-"),
+    };
+    let contexts: Vec<String> = task.contexts.iter().map(|c| format!("@{}", c)).collect();
+    let project = ask_field(input, &t!("triage-project"), &task.project, |answer| {
+        Ok(answer.to_string())
+    })?;
+    let priority = ask_field(
+        input,
+        &t!("triage-priority"),
+        &task.priority.to_string(),
+        |answer| answer.parse::<u8>().map_err(|_| "invalid-priority"),
+    )?;
+    let due = ask_field(input, &t!("triage-due"), &due, |answer| {
+        parse_due_at(answer, fmt)
+    })?;
+    let contexts = ask_field(
+        input,
+        &t!("triage-contexts"),
+        &contexts.join(" "),
+        |answer| {
+            Ok(answer
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(|context| context.trim_start_matches('@'))
+                .filter(|context| !context.is_empty())
+                .map(String::from)
+                .collect())
+        },
+    )?;
+
+    if let Some(project) = project {
+        task.project = project;
+    }
+    if let Some(priority) = priority {
+        task.priority = priority;
+    }
+    if let Some((due, due_at)) = due {
+        task.due = Some(due);
+        task.due_at = due_at;
+    }
+    if let Some(contexts) = contexts {
+        task.contexts = contexts;
+    }
+    touch(task);
+    Ok(())
+}
+
+/// Collects, per project, the relative error between the estimate and the tracked time of every
+/// finished task that has both. An error of `0.5` means the task took 50% longer than estimated.
+fn estimate_errors_by_project(tasks: &[Task]) -> Vec<(String, Vec<f64>)> {
+    let mut projects: Vec<(String, Vec<f64>)> = Vec::new();
+    for task in tasks.iter().filter(|task| is_done(&task.status)) {
+        let Some(estimate) = task.estimate.filter(|estimate| *estimate > 0.0) else {
+            continue;
+        };
+        if task.time_entries.is_empty() {
+            continue;
+        }
+        let actual: f64 = task.time_entries.iter().map(|entry| entry.hours).sum();
+        let error = (actual - estimate) / estimate;
+        match projects
+            .iter_mut()
+            .find(|(project, _)| *project == task.project)
+        {
+            Some((_, errors)) => errors.push(error),
+            None => projects.push((task.project.clone(), vec![error])),
+        }
+    }
+    projects.sort_by(|a, b| a.0.cmp(&b.0));
+    projects
+}
+
+/// Prints the distribution of estimate errors for each project.
+fn print_accuracy_report(tasks: &[Task]) {
+    let projects = estimate_errors_by_project(tasks);
+    if projects.is_empty() {
+        println!("{}", t!("accuracy-no-data"));
+        return;
+    }
+
+    let buckets: [(String, f64, f64); 5] = [
+        (t!("accuracy-much-faster"), f64::NEG_INFINITY, -0.5),
+        (t!("accuracy-faster"), -0.5, -0.1),
+        (t!("accuracy-on-target"), -0.1, 0.1),
+        (t!("accuracy-slower"), 0.1, 0.5),
+        (t!("accuracy-much-slower"), 0.5, f64::INFINITY),
+    ];
+    let width = buckets
+        .iter()
+        .map(|(label, _, _)| label.chars().count())
+        .max()
+        .unwrap_or_default();
+    for (project, mut errors) in projects {
+        errors.sort_by(|a, b| a.total_cmp(b));
+        let mean = errors.iter().sum::<f64>() / errors.len() as f64;
+        let middle = errors.len() / 2;
+        let median = if errors.len() % 2 == 0 {
+            (errors[middle - 1] + errors[middle]) / 2.0
+        } else {
+            errors[middle]
+        };
+        println!(
+            "{}",
+            t!("accuracy-project", project = project, count = errors.len())
+        );
+        println!(
+            "  {}",
+            t!(
+                "accuracy-errors",
+                mean = format!("{:+.0}", mean * 100.0),
+                median = format!("{:+.0}", median * 100.0)
+            )
+        );
+        for (label, low, high) in &buckets {
+            let count = errors.iter().filter(|e| *e >= low && *e < high).count();
+            println!(
+                "  {:<width$} {:>3} {}",
+                label,
+                count,
+                "#".repeat(count),
+                width = width
+            );
+        }
+    }
+}
+
+/// Renders the number of tasks completed per day over the past year as a contribution grid,
+/// with one column per week and one row per weekday, starting on the configured week start.
+fn completion_heatmap(tasks: &[Task], fmt: &Formatter, today: NaiveDate) -> String {
+    let mut completions: HashMap<NaiveDate, usize> = HashMap::new();
+    for completed_at in tasks.iter().filter_map(|task| task.completed_at) {
+        *completions.entry(fmt.local_date(completed_at)).or_default() += 1;
+    }
+
+    let start = today - Duration::weeks(52);
+    let start = start - Duration::days(start.weekday().days_since(fmt.week_start()) as i64);
+    let weeks = (today - start).num_days() as usize / 7 + 1;
+    let levels = [("·", "."), ("░", "-"), ("▒", "+"), ("▓", "*"), ("█", "#")];
+    let levels = levels.map(|(unicode, ascii)| fmt.glyph(unicode, ascii));
+    let glyph = |count: usize| match count {
+        0 => levels[0],
+        1 => levels[1],
+        2..=3 => levels[2],
+        4..=5 => levels[3],
+        _ => levels[4],
+    };
+
+    let mut months = vec![' '; weeks];
+    let mut week = 0;
+    while week < weeks {
+        let first_day = start + Duration::weeks(week as i64);
+        if week == 0 || (first_day - Duration::weeks(1)).month() != first_day.month() {
+            let label = t!("month-short", month = first_day.month());
+            let len = label.chars().count();
+            if week + len <= weeks {
+                months.splice(week..week + len, label.chars());
+                week += len;
+                continue;
+            }
+        }
+        week += 1;
+    }
+
+    let mut grid = format!("    {}\n", months.iter().collect::<String>());
+    let mut weekday = fmt.week_start();
+    for row in 0..7 {
+        let label = t!("weekday-short", day = weekday.to_string().to_lowercase());
+        grid.push_str(&format!("{:<3} ", label));
+        weekday = weekday.succ();
+        for week in 0..weeks {
+            let day = start + Duration::days((week * 7 + row) as i64);
+            if day <= today {
+                grid.push_str(glyph(completions.get(&day).copied().unwrap_or(0)));
+            }
+        }
+        grid.push('\n');
+    }
+
+    let total: usize = completions
+        .iter()
+        .filter(|(day, _)| **day >= start && **day <= today)
+        .map(|(_, count)| count)
+        .sum();
+    let legend = levels.join(" ");
+    grid.push_str(&format!(
+        "\n{}\n",
+        t!("heatmap-total", count = total, legend = legend)
+    ));
+    grid
+}
+
+/// Counts completed tasks by the weekday, Monday first, and the hour of the day they were
+/// completed at in the configured timezone.
+fn completion_patterns(tasks: &[Task], fmt: &Formatter) -> ([usize; 7], [usize; 24]) {
+    let mut weekdays = [0; 7];
+    let mut hours = [0; 24];
+    for completed_at in tasks.iter().filter_map(|task| task.completed_at) {
+        let local = fmt.local_datetime(completed_at);
+        weekdays[local.weekday().num_days_from_monday() as usize] += 1;
+        hours[local.hour() as usize] += 1;
+    }
+    (weekdays, hours)
+}
+
+/// Renders labelled counts as horizontal bars, the longest `width` characters long.
+fn bar_chart(rows: &[(String, usize)], width: usize, fmt: &Formatter) -> String {
+    let max = rows
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let label_width = rows.iter().map(|(label, _)| label.chars().count()).max();
+    let mut chart = String::new();
+    for (label, count) in rows {
+        // Any completions at all get at least a sliver of a bar
+        let len = (count * width).div_ceil(max);
+        chart.push_str(&format!(
+            "{:<label_width$} {} {}\n",
+            label,
+            fmt.glyph("█", "#").repeat(len),
+            count,
+            label_width = label_width.unwrap_or(0)
+        ));
+    }
+    chart
+}
+
+/// Groups blocked tasks by who or what they are waiting on, oldest blockage first within a group.
+///
+/// Tasks blocked without a reason are grouped by their unfinished dependencies, if any.
+fn waiting_groups(tasks: &[Task]) -> Vec<(String, Vec<&Task>)> {
+    let mut groups: Vec<(String, Vec<&Task>)> = Vec::new();
+    for task in tasks.iter().filter(|task| is_blocked(&task.status)) {
+        let reason = match &task.waiting_on {
+            Some(waiting_on) => waiting_on.clone(),
+            None if !task.depends_on.is_empty() => {
+                t!("waiting-on-tasks", tasks = task.depends_on.join(", "))
+            }
+            None => t!("no-reason-given"),
+        };
+        match groups.iter_mut().find(|(r, _)| *r == reason) {
+            Some((_, group)) => group.push(task),
+            None => groups.push((reason, vec![task])),
+        }
+    }
+    for (_, group) in groups.iter_mut() {
+        group.sort_by_key(|task| task.blocked_at);
+    }
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+    groups
+}
+
+/// When a task was last touched: added, edited, moved to another status or worked on.
+fn last_activity(task: &Task) -> Option<DateTime<Utc>> {
+    let worked_on = task
+        .time_entries
+        .iter()
+        .filter_map(|entry| entry.date.and_hms_opt(0, 0, 0))
+        .map(|start| start.and_utc());
+    [
+        task.created_at,
+        task.updated_at,
+        task.started_at,
+        task.completed_at,
+        task.blocked_at,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(worked_on)
+    .max()
+}
+
+/// Whether an open task has not been touched for more than `after_days` days. Tasks from before
+/// activity was recorded never are.
+fn is_stale(task: &Task, now: DateTime<Utc>, after_days: i64) -> bool {
+    !is_done(&task.status)
+        && last_activity(task).is_some_and(|last| (now - last).num_days() > after_days)
+}
+
+/// Groups the stale tasks by project, the longest untouched first within a group.
+fn stale_groups(tasks: &[Task], now: DateTime<Utc>, after_days: i64) -> Vec<(&str, Vec<&Task>)> {
+    let mut groups: BTreeMap<&str, Vec<&Task>> = BTreeMap::new();
+    for task in tasks.iter().filter(|task| is_stale(task, now, after_days)) {
+        groups.entry(&task.project).or_default().push(task);
+    }
+    for group in groups.values_mut() {
+        group.sort_by_key(|task| last_activity(task));
+    }
+    groups.into_iter().collect()
+}
+
+/// Adds a task or, if a task with the same external reference exists, updates that one instead,
+/// keeping its history. Returns whether an existing task was updated.
+///
+/// The due date, estimate and tags are only replaced when the new task has them.
+fn upsert_task(tasks: &mut Vec<Task>, task: Task) -> bool {
+    let existing = task.external_id.as_ref().and_then(|id| {
+        tasks
+            .iter()
+            .position(|other| other.external_id.as_ref() == Some(id))
+    });
+    let Some(index) = existing else {
+        tasks.push(task);
+        return false;
+    };
+
+    let old_title = std::mem::replace(&mut tasks[index].title, task.title.clone());
+    if old_title != task.title {
+        for dependency in tasks
+            .iter_mut()
+            .flat_map(|other| other.depends_on.iter_mut())
+        {
+            if *dependency == old_title {
+                *dependency = task.title.clone();
+            }
+        }
+    }
+    let existing = &mut tasks[index];
+    touch(existing);
+    existing.description = task.description;
+    existing.priority = task.priority;
+    existing.project = task.project;
+    if existing.status != task.status {
+        set_status(existing, &task.status);
+    }
+    if task.due.is_some() {
+        existing.due = task.due;
+        existing.due_at = task.due_at;
+    }
+    if task.estimate.is_some() {
+        existing.estimate = task.estimate;
+    }
+    if !task.tags.is_empty() {
+        existing.tags = task.tags;
+    }
+    if task.goal.is_some() {
+        existing.goal = task.goal;
+        existing.key_result = task.key_result;
+    }
+    if task.url.is_some() {
+        existing.url = task.url;
+    }
+    true
+}
+
+/// Links a task to a goal and, optionally, one of its key results.
+fn link_goal(
+    task: &mut Task,
+    goal: &str,
+    key_result: Option<&str>,
+    goals: &[goal::Goal],
+) -> Result<(), &'static str> {
+    let found = goals
+        .iter()
+        .find(|g| g.name == goal)
+        .ok_or("goal-not-found")?;
+    if let Some(key_result) = key_result {
+        if !found.key_results.iter().any(|kr| kr == key_result) {
+            return Err("key-result-not-found");
+        }
+    }
+    task.goal = Some(goal.to_string());
+    task.key_result = key_result.map(String::from);
+    Ok(())
+}
+
+/// Tells whether text is a link `open` can hand to the browser.
+fn is_url(text: &str) -> bool {
+    ["http://", "https://", "file://", "mailto:"]
+        .iter()
+        .any(|scheme| text.starts_with(scheme) && text.len() > scheme.len())
+}
+
+/// The link of a task: its URL, or else the first link in its description.
+fn task_url(task: &Task) -> Option<&str> {
+    task.url.as_deref().or_else(|| {
+        task.description
+            .split_whitespace()
+            .map(|word| {
+                word.trim_start_matches(|c| "<([\"'".contains(c))
+                    .trim_end_matches(|c| ">)]\"'.,;:!?".contains(c))
+            })
+            .find(|word| is_url(word))
+    })
+}
+
+/// Opens a link with the desktop's default application for it.
+fn open_url(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    let status = command.arg(url).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(status.to_string()))
+    }
+}
+
+/// Marks a task Done, as `update --status Done` does, and moves the Blocked tasks that were only
+/// waiting for it back to Todo. Returns their titles.
+fn mark_done(tasks: &mut [Task], index: usize) -> Vec<String> {
+    set_status(&mut tasks[index], "Done");
+    touch(&mut tasks[index]);
+    unblock_ready_tasks(tasks)
+}
+
+/// Hands a task over to someone else: sets the assignee, marks it Waiting and records when to
+/// follow up on it.
+fn delegate_task(task: &mut Task, assignee: &str, follow_up: NaiveDate) {
+    task.assignee = Some(assignee.to_string());
+    task.follow_up = Some(follow_up);
+    touch(task);
+    set_status(task, "Waiting");
+}
+
+/// Lists the open delegated tasks whose follow-up date has arrived, the longest overdue first.
+fn due_follow_ups(tasks: &[Task], today: NaiveDate) -> Vec<&Task> {
+    let mut due: Vec<&Task> = tasks
+        .iter()
+        .filter(|task| !is_done(&task.status) && task.follow_up.is_some_and(|date| date <= today))
+        .collect();
+    due.sort_by_key(|task| task.follow_up);
+    due
+}
+
+/// Asks the user to confirm a destructive operation.
+///
+/// The question is skipped, and taken as answered yes, with `--yes` or when stdin is not a
+/// terminal, so scripts and pipes never wait for an answer.
+fn confirm(question: &str, assume_yes: bool) -> bool {
+    if assume_yes || !io::stdin().is_terminal() {
+        return true;
+    }
+    print!("{} ", t!("confirm-prompt", question = question));
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    is_yes(&answer)
+}
+
+/// Tells whether an answer to a question means yes, in the language messages are shown in.
+fn is_yes(answer: &str) -> bool {
+    let answer = answer.trim().to_lowercase();
+    t!("confirm-yes-answers")
+        .split(',')
+        .any(|yes| yes.trim() == answer)
+}
+
+/// Asks what to do with an imported task that looks like an existing one. Without a terminal to
+/// ask on, the task is skipped.
+fn ask_duplicate(imported: &Task, existing: &Task, similarity: f64) -> OnDuplicate {
+    if !io::stdin().is_terminal() {
+        return OnDuplicate::Skip;
+    }
+    println!(
+        "{}",
+        t!(
+            "duplicate-found",
+            imported = imported.title.as_str(),
+            existing = existing.title.as_str(),
+            percent = (similarity * 100.0).round()
+        )
+    );
+    loop {
+        print!("{} ", t!("duplicate-prompt"));
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() || answer.is_empty() {
+            return OnDuplicate::Skip;
+        }
+        let answer = answer.trim().to_lowercase();
+        let answers = |id: &str| t!(id).split(',').any(|a| a.trim() == answer);
+        if answer.is_empty() || answers("duplicate-skip-answers") {
+            return OnDuplicate::Skip;
+        } else if answers("duplicate-merge-answers") {
+            return OnDuplicate::Merge;
+        } else if answers("duplicate-create-answers") {
+            return OnDuplicate::Create;
+        }
+    }
+}
+
+/// Saves the tasks, returning false after reporting why when they could not be saved, e.g.
+/// because a remote store changed in the meantime.
+fn save_or_report(tasks: &[Task]) -> bool {
+    match save_tasks(tasks) {
+        Ok(()) => true,
+        Err(err) if err.is::<conflict::ChangedOnDisk>() && io::stdin().is_terminal() => {
+            match save_over_changed_store(tasks) {
+                Ok(saved) => saved,
+                Err(err) => {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                    false
+                }
+            }
+        }
+        Err(err) => {
+            report_error(ErrorKind::Store, &err.to_string(), None);
+            false
+        }
+    }
+}
+
+/// Asks how to save tasks over a local store another program changed since it was read: by
+/// merging the two field by field, by overwriting it, or not at all. Returns whether the tasks
+/// were saved.
+fn save_over_changed_store(tasks: &[Task]) -> Result<bool, Box<dyn Error>> {
+    // Some(true) to merge, Some(false) to overwrite
+    let merge = loop {
+        print!("{} ", t!("store-changed-prompt"));
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            break None;
+        }
+        let answer = answer.trim().to_lowercase();
+        let answers = |id: &str| t!(id).split(',').any(|a| a.trim() == answer);
+        if answers("store-changed-merge-answers") {
+            break Some(true);
+        } else if answers("store-changed-overwrite-answers") {
+            break Some(false);
+        } else if answers("store-changed-abort-answers") {
+            break None;
+        }
+    };
+    let saved = match merge {
+        Some(true) => {
+            let key = store_key(&load_config()?)?;
+            let ours = json_store_tasks(fs::read(conflict::CONFLICT_FILE)?, key.as_ref())?;
+            match conflict::merge(ours, load_tasks()?, ask_side) {
+                Some(merged) => {
+                    save_tasks(&merged)?;
+                    true
+                }
+                None => false,
+            }
+        }
+        Some(false) => {
+            conflict::forget();
+            save_tasks(tasks)?;
+            true
+        }
+        None => false,
+    };
+    fs::remove_file(conflict::CONFLICT_FILE)?;
+    if !saved {
+        println!("{}", t!("aborted"));
+    }
+    Ok(saved)
+}
+
+/// Asks whether to keep the local or the remote value of a conflicting field, or `None` when the
+/// user stops resolving.
+fn ask_side(conflict: &conflict::Conflict) -> Option<conflict::Side> {
+    let value = |value: &serde_json::Value| match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Null => String::from("-"),
+        other => other.to_string(),
+    };
+    println!(
+        "{}",
+        t!(
+            "conflict-field",
+            title = conflict.title,
+            field = conflict.field
+        )
+    );
+    println!("  {} {}", t!("conflict-local"), value(conflict.local));
+    println!("  {} {}", t!("conflict-remote"), value(conflict.remote));
+    loop {
+        print!("{} ", t!("conflict-prompt"));
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() || answer.is_empty() {
+            return None;
+        }
+        let answer = answer.trim().to_lowercase();
+        let answers = |id: &str| t!(id).split(',').any(|a| a.trim() == answer);
+        if answers("conflict-local-answers") {
+            return Some(conflict::Side::Local);
+        } else if answers("conflict-remote-answers") {
+            return Some(conflict::Side::Remote);
+        } else if answers("rank-quit-answers") {
+            return None;
+        }
+    }
+}
+
+/// Snapshots the stored tasks before a bulk change, so the change can be undone with
+/// `backup restore`. Returns false, after reporting why, when no snapshot could be taken and the
+/// change should not go ahead.
+fn backup_before_change(config: &Config) -> bool {
+    let snapshot = load_tasks().and_then(|tasks| {
+        let plans = load_plans()?;
+        let key = store_key(config)?;
+        backup::create(&config.backup, &tasks, &plans, Utc::now(), key.as_ref())
+    });
+    match snapshot {
+        Ok(name) => {
+            println!("{}", t!("backup-taken", name = name));
+            true
+        }
+        Err(err) => {
+            let message = t!("backup-failed", error = err.to_string());
+            report_error(ErrorKind::Io, &message, None);
+            false
+        }
+    }
+}
+
+/// Updates a task based on command-line arguments.
+///
+/// Errors are message ids, to be shown with `t!`.
+fn update_task(
+    title: &str,
+    matches: &ArgMatches,
+    tasks: &mut [Task],
+    fmt: &Formatter,
+) -> Result<(), &'static str> {
+    let new_dependencies: Vec<&str> = matches
+        .values_of("depends-on")
+        .map(|deps| deps.collect())
+        .unwrap_or_default();
+    for dep in &new_dependencies {
+        if !tasks.iter().any(|t| t.title == *dep) {
+            return Err("dependency-not-found");
+        }
+        if depends_on_transitively(tasks, dep, title) {
+            return Err("dependency-cycle");
+        }
+    }
+    let has_open_dependency = new_dependencies
+        .iter()
+        .any(|dep| tasks.iter().any(|t| t.title == *dep && !is_done(&t.status)));
+
+    if let Some(task) = tasks.iter_mut().find(|t| t.title == title) {
+        touch(task);
+        if let Some(new_description) = matches.value_of("description") {
+            task.description = new_description.to_string();
+        }
+        if let Some(new_priority) = matches.value_of("priority") {
+            task.priority = new_priority.parse::<u8>().map_err(|_| "invalid-priority")?;
+        }
+        if let Some(new_status) = matches.value_of("status") {
+            set_status(task, new_status);
+        }
+        if let Some(new_project) = matches.value_of("project") {
+            task.project = new_project.to_string();
+        }
+        if let Some(new_due) = matches.value_of("due") {
+            let (due, due_at) = parse_due_at(new_due, fmt)?;
+            task.due = Some(due);
+            task.due_at = due_at;
+        }
+        if let Some(new_estimate) = matches.value_of("estimate") {
+            task.estimate = Some(parse_hours(new_estimate)?);
+        }
+        if let Some(every) = matches.value_of("review-every") {
+            task.review_every = match every {
+                "never" => None,
+                every => Some(review::parse_interval(every).ok_or("invalid-review-interval")?),
+            };
+        }
+        if let Some(new_tags) = matches.values_of("tag") {
+            for tag in new_tags {
+                if !task.tags.iter().any(|t| t == tag) {
+                    task.tags.push(tag.to_string());
+                }
+            }
+        }
+        for dep in new_dependencies {
+            if !task.depends_on.iter().any(|d| d == dep) {
+                task.depends_on.push(dep.to_string());
+            }
+        }
+        if has_open_dependency && !is_blocked(&task.status) {
+            set_status(task, "Blocked");
+        }
+        if let Some(goal) = matches.value_of("goal") {
+            let goals = goal::load().map_err(|_| "goals-unreadable")?;
+            link_goal(task, goal, matches.value_of("key-result"), &goals)?;
+        }
+        if let Some(url) = matches.value_of("url") {
+            if !is_url(url) {
+                return Err("invalid-url");
+            }
+            task.url = Some(url.to_string());
+        }
+        if let Some(waiting_on) = matches.value_of("waiting-on") {
+            if !is_blocked(&task.status) {
+                set_status(task, "Blocked");
+            }
+            task.waiting_on = Some(waiting_on.to_string());
+        }
+        unblock_ready_tasks(tasks);
+        save_tasks(tasks).map_err(|_| "save-failed")?;
+        Ok(())
+    } else {
+        Err("task-not-found")
+    }
+}
+
+/// The entry point of the application, which runs the command and exits with status 1 if it
+/// reported an error, so wrappers can tell it failed.
+fn main() {
+    run();
+    if FAILED.load(Ordering::Relaxed) {
+        std::process::exit(1);
+    }
+}
+
+/// Handles command-line arguments and performs corresponding actions on tasks.
+fn run() {
+    let mut timings = Timings::new();
+    let matches = App::new("Task Manager")
+        .version("1.0")
+        .author("Me")
+        .about("A console-based task management application")
+        .setting(AppSettings::AllowExternalSubcommands)
+        .arg(
+            Arg::with_name("yes")
+                .short("y")
+                .long("yes")
+                .global(true)
+                .help("Don't ask for confirmation before destructive operations"),
+        )
+        .arg(
+            Arg::with_name("timings")
+                .long("timings")
+                .global(true)
+                .help("Print how long each phase of the command took"),
+        )
+        .arg(
+            Arg::with_name("ascii")
+                .long("ascii")
+                .global(true)
+                .help("Show ASCII instead of Unicode glyphs"),
+        )
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .global(true)
+                .help("Report errors as JSON on stderr"),
+        )
+        .subcommand(
+            SubCommand::with_name("add")
+                .about("Add a new task")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required_unless("from-clipboard")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("description")
+                        .index(2)
+                        .required_unless("from-clipboard")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("priority")
+                        .index(3)
+                        .required_unless("from-clipboard")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("status")
+                        .index(4)
+                        .required_unless("from-clipboard")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("project")
+                        .index(5)
+                        .required_unless("from-clipboard")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("from-clipboard")
+                        .long("from-clipboard")
+                        .conflicts_with("title")
+                        .help("Take the title from the first line on the clipboard and the description from the rest, filing the task as a priority 1 Todo in the Inbox project"),
+                )
+                .arg(Arg::with_name("due").long("due").takes_value(true))
+                .arg(
+                    Arg::with_name("estimate")
+                        .long("estimate")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("tag")
+                        .long("tag")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("external-id")
+                        .long("external-id")
+                        .takes_value(true)
+                        .help("The key of the task in another system, such as JIRA-123"),
+                )
+                .arg(
+                    Arg::with_name("url")
+                        .long("url")
+                        .takes_value(true)
+                        .help("A link to what the task is about, opened with `open`"),
+                )
+                .arg(
+                    Arg::with_name("upsert")
+                        .long("upsert")
+                        .requires("external-id")
+                        .help("Update the task with the same external id instead of failing"),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Add the task even if it goes over a WIP limit"),
+                )
+                .arg(
+                    Arg::with_name("goal")
+                        .long("goal")
+                        .takes_value(true)
+                        .help("Link the task to a goal"),
+                )
+                .arg(
+                    Arg::with_name("key-result")
+                        .long("key-result")
+                        .takes_value(true)
+                        .requires("goal")
+                        .help("Link the task to a key result of its goal"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("capture")
+                .about("Add a task from one line, e.g. \"Fix login bug +Backend @work p:4 due:friday\"")
+                .arg(
+                    Arg::with_name("line")
+                        .index(1)
+                        .required(true)
+                        .multiple(true),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Add the task even if it goes over a WIP limit"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("triage")
+                .about("Go through the open tasks in the Inbox, giving each a project, priority, due date and contexts"),
+        )
+        .subcommand(
+            SubCommand::with_name("remove").about("Remove a task").arg(
+                Arg::with_name("title")
+                    .index(1)
+                    .required(true)
+                    .takes_value(true),
+            ),
+        )
+        .subcommand(
+            SubCommand::with_name("show")
+                .about("Show everything about a task")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("done").about("Mark a task as Done").arg(
+                Arg::with_name("title")
+                    .index(1)
+                    .required(true)
+                    .takes_value(true),
+            ),
+        )
+        .subcommand(
+            SubCommand::with_name("list")
+                .about("List all tasks")
+                .arg(
+                    Arg::with_name("filter")
+                        .long("filter")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Only show tasks kept by this WASM plugin filter"),
+                )
+                .arg(
+                    Arg::with_name("table")
+                        .long("table")
+                        .help("Show the tasks as a table fitting the terminal"),
+                )
+                .arg(
+                    Arg::with_name("columns")
+                        .long("columns")
+                        .takes_value(true)
+                        .help("Show a table with exactly these columns, e.g. id,title,due"),
+                )
+                .arg(
+                    Arg::with_name("all-stores")
+                        .long("all-stores")
+                        .alias("all-contexts")
+                        .conflicts_with("filter")
+                        .help("List the tasks of this and every other configured store"),
+                )
+                .arg(
+                    Arg::with_name("sort")
+                        .long("sort")
+                        .takes_value(true)
+                        .allow_hyphen_values(true)
+                        .help("Sort by a computed field, e.g. -urgency for the most urgent first"),
+                )
+                .args(&query::args()),
+        )
+        .subcommand(
+            SubCommand::with_name("search")
+                .about("Search for tasks by title or description")
+                .arg(
+                    Arg::with_name("query")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list-by-project")
+                .about("List tasks by project")
+                .arg(
+                    Arg::with_name("project")
+                        .long("project")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list-by-status")
+                .about("List tasks by status")
+                .arg(
+                    Arg::with_name("status")
+                        .long("status")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list-by-priority")
+                .about("List tasks by priority")
+                .arg(
+                    Arg::with_name("priority")
+                        .long("priority")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("update")
+                .about("Update a task")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("description")
+                        .long("description")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("priority")
+                        .long("priority")
+                        .takes_value(true),
+                )
+                .arg(Arg::with_name("status").long("status").takes_value(true))
+                .arg(Arg::with_name("project").long("project").takes_value(true))
+                .arg(Arg::with_name("due").long("due").takes_value(true))
+                .arg(
+                    Arg::with_name("cascade-dates")
+                        .long("cascade-dates")
+                        .requires("due")
+                        .help("Move the due dates of the tasks depending on this one as much"),
+                )
+                .arg(
+                    Arg::with_name("review-every")
+                        .long("review-every")
+                        .takes_value(true)
+                        .help("How often to review the task, e.g. 10d or 2w, or never"),
+                )
+                .arg(
+                    Arg::with_name("estimate")
+                        .long("estimate")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("tag")
+                        .long("tag")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("depends-on")
+                        .long("depends-on")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("waiting-on")
+                        .long("waiting-on")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("url")
+                        .long("url")
+                        .takes_value(true)
+                        .help("A link to what the task is about, opened with `open`"),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .help("Update the task even if it goes over a WIP limit"),
+                )
+                .arg(
+                    Arg::with_name("if-revision")
+                        .long("if-revision")
+                        .takes_value(true)
+                        .help("Only update the task if it is still at this revision"),
+                )
+                .arg(
+                    Arg::with_name("goal")
+                        .long("goal")
+                        .takes_value(true)
+                        .help("Link the task to a goal"),
+                )
+                .arg(
+                    Arg::with_name("key-result")
+                        .long("key-result")
+                        .takes_value(true)
+                        .requires("goal")
+                        .help("Link the task to a key result of its goal"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("open")
+                .about("Open the link of a task in the browser")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("log-time")
+                .about("Record hours worked on a task")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("hours")
+                        .index(2)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(Arg::with_name("date").long("date").takes_value(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("remind")
+                .about("Add reminders to a task, given by tick")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("at")
+                        .long("at")
+                        .required(true)
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("When to remind, e.g. \"1d before\", \"2h before\" or \"2024-07-01 09:00\""),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("reminders")
+                .about("List the reminders of a task, or remove them")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("remove")
+                        .long("remove")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("The number of a reminder to remove, as listed"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("report")
+                .about("Show reports about your tasks")
+                .subcommand(
+                    SubCommand::with_name("accuracy")
+                        .about("Compare estimates with tracked time per project"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("project")
+                .about("Look into a project")
+                .subcommand(
+                    SubCommand::with_name("analyze")
+                        .about("Show the work left in a project and its critical path")
+                        .arg(Arg::with_name("name").required(true).index(1)),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("today")
+                .about("Plan the tasks to focus on today")
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Add a task to today's plan")
+                        .arg(
+                            Arg::with_name("title")
+                                .index(1)
+                                .required(true)
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(SubCommand::with_name("list").about("List today's plan"))
+                .subcommand(SubCommand::with_name("clear").about("Clear today's plan")),
+        )
+        .subcommand(
+            SubCommand::with_name("goal")
+                .about("Set goals with key results and follow their progress")
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Add a goal")
+                        .arg(
+                            Arg::with_name("name")
+                                .index(1)
+                                .required(true)
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("period")
+                                .long("period")
+                                .takes_value(true)
+                                .help("The period the goal is set for, e.g. 2024-Q3"),
+                        )
+                        .arg(
+                            Arg::with_name("key-result")
+                                .long("key-result")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("progress")
+                        .about("Show how far along each goal is from its linked tasks"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("habit")
+                .about("Track recurring habits and their streaks")
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Start tracking a habit")
+                        .arg(
+                            Arg::with_name("name")
+                                .index(1)
+                                .required(true)
+                                .takes_value(true),
+                        )
+                        .arg(
+                            Arg::with_name("every")
+                                .long("every")
+                                .takes_value(true)
+                                .default_value("day")
+                                .help("How often to keep the habit: day or week"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("done")
+                        .about("Record that a habit was kept")
+                        .arg(
+                            Arg::with_name("name")
+                                .index(1)
+                                .required(true)
+                                .takes_value(true),
+                        )
+                        .arg(Arg::with_name("date").long("date").takes_value(true)),
+                )
+                .subcommand(SubCommand::with_name("streaks").about("Show the streak of each habit")),
+        )
+        .subcommand(
+            SubCommand::with_name("delegate")
+                .about("Delegate a task to someone and follow up on it later")
+                .arg(
+                    Arg::with_name("title")
+                        .index(1)
+                        .required(true)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("follow-up")
+                        .long("follow-up")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("follow-ups")
+                .about("List delegated tasks that are due for a follow-up"),
+        )
+        .subcommand(
+            SubCommand::with_name("waiting")
+                .about("List blocked tasks grouped by what they are waiting on"),
+        )
+        .subcommand(
+            SubCommand::with_name("review")
+                .about("List the projects and tasks due for review")
+                .subcommand(
+                    SubCommand::with_name("mark")
+                        .about("Record that tasks or projects were reviewed")
+                        .arg(
+                            Arg::with_name("title")
+                                .index(1)
+                                .multiple(true)
+                                .required_unless("project"),
+                        )
+                        .arg(
+                            Arg::with_name("project")
+                                .long("project")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("board")
+                .about("Show the tasks in a column per status, marking columns over their WIP limit")
+                .arg(Arg::with_name("project").long("project").takes_value(true))
+                .subcommand(
+                    SubCommand::with_name("move-up")
+                        .about("Move a task one place up in its board column")
+                        .arg(
+                            Arg::with_name("title")
+                                .index(1)
+                                .required(true)
+                                .takes_value(true),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("move-down")
+                        .about("Move a task one place down in its board column")
+                        .arg(
+                            Arg::with_name("title")
+                                .index(1)
+                                .required(true)
+                                .takes_value(true),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("matrix")
+                .about("Sort open tasks into urgent and important quadrants for planning")
+                .arg(Arg::with_name("project").long("project").takes_value(true))
+                .arg(
+                    Arg::with_name("top")
+                        .long("top")
+                        .takes_value(true)
+                        .default_value("3")
+                        .help("How many tasks to show per quadrant"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("stale")
+                .about("List open tasks nobody has touched for a while, by project")
+                .arg(
+                    Arg::with_name("days")
+                        .long("days")
+                        .takes_value(true)
+                        .help("Days without activity after which a task is stale"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("suggest")
+                .about("Suggest a task to work on, favouring urgent ones")
+                .arg(Arg::with_name("project").long("project").takes_value(true))
+                .arg(
+                    Arg::with_name("context")
+                        .long("context")
+                        .takes_value(true)
+                        .help("Only consider tasks that can be done in a context, e.g. work"),
+                )
+                .arg(Arg::with_name("random").long("random")),
+        )
+        .subcommand(
+            SubCommand::with_name("backup")
+                .about("Take, list and restore snapshots of the tasks")
+                .subcommand(SubCommand::with_name("now").about("Take a snapshot"))
+                .subcommand(SubCommand::with_name("list").about("List the snapshots"))
+                .subcommand(
+                    SubCommand::with_name("restore")
+                        .about("Restore a snapshot")
+                        .arg(
+                            Arg::with_name("name")
+                                .index(1)
+                                .required(true)
+                                .takes_value(true),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("Export recent activity as an Atom feed, task dependencies as a graph, the tasks as an outline or by context and place, or the whole workspace as an archive")
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .required_unless("archive")
+                        .possible_values(&["atom", "dot", "mermaid", "opml", "contexts", "geojson"]),
+                )
+                .arg(
+                    Arg::with_name("archive")
+                        .long("archive")
+                        .takes_value(true)
+                        .conflicts_with_all(&["format", "output"])
+                        .help("Bundle the whole data directory into this .tar.zst archive"),
+                )
+                .args(&query::args())
+                .arg(
+                    Arg::with_name("days")
+                        .long("days")
+                        .takes_value(true)
+                        .default_value("30")
+                        .help("How many days of activity to include in the feed"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .help("File to write to instead of standard output"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import")
+                .about("Import tasks from an OPML outline, or restore a workspace archive")
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .required_unless("archive")
+                        .possible_values(&["opml"]),
+                )
+                .arg(Arg::with_name("file").index(1).required_unless("archive"))
+                .arg(
+                    Arg::with_name("archive")
+                        .long("archive")
+                        .takes_value(true)
+                        .conflicts_with_all(&["format", "file"])
+                        .help("Restore the data directory from this archive made by export --archive"),
+                )
+
+                .arg(
+                    Arg::with_name("on-duplicate")
+                        .long("on-duplicate")
+                        .takes_value(true)
+                        .possible_values(&["ask", "skip", "merge", "create"])
+                        .default_value("ask")
+                        .help("What to do with tasks that look like existing ones"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("convert")
+                .about("Copy the tasks into a store of another kind")
+                .arg(
+                    Arg::with_name("store")
+                        .index(1)
+                        .required(true)
+                        .possible_values(&["json", "binary", "crdt", "ndjson"])
+                        .help("The kind of store to write"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("sync")
+                .about("Reconcile the tasks with a remote store")
+                .subcommand(
+                    SubCommand::with_name("conflicts")
+                        .about("Merge a change the remote store refused, field by field"),
+                )
+                .subcommand(
+                    SubCommand::with_name("flush")
+                        .about("Send the changes made while the remote store was unreachable"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("auth")
+                .about("Keep credentials in the OS keyring")
+                .subcommand(
+                    SubCommand::with_name("login")
+                        .about("Ask for the credentials of a service and keep them in the keyring")
+                        .arg(
+                            Arg::with_name("service")
+                                .required(true)
+                                .possible_values(&secrets::Login::NAMES),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("logout")
+                        .about("Remove the credentials of a service from the keyring")
+                        .arg(
+                            Arg::with_name("service")
+                                .required(true)
+                                .possible_values(&secrets::Login::NAMES),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("key")
+                .about("Manage the key the store is encrypted with")
+                .subcommand(
+                    SubCommand::with_name("generate")
+                        .about("Create a new key in the file named by encryption_key"),
+                )
+                .subcommand(
+                    SubCommand::with_name("encrypt")
+                        .about("Encrypt a store saved before encryption_key was set"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("script")
+                .about("Run user scripts against the tasks")
+                .subcommand(
+                    SubCommand::with_name("run").about("Run a Rhai script").arg(
+                        Arg::with_name("file")
+                            .index(1)
+                            .required(true)
+                            .takes_value(true),
+                    ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("stats")
+                .about("Show statistics about your tasks")
+                .subcommand(
+                    SubCommand::with_name("heatmap")
+                        .about("Show completed tasks per day over the past year"),
+                )
+                .subcommand(
+                    SubCommand::with_name("patterns")
+                        .about("Show on which weekdays and at which hours tasks get completed"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("rebalance")
+                .about("Spread the priorities of a project's open tasks evenly")
+                .arg(
+                    Arg::with_name("project")
+                        .long("project")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("max")
+                        .long("max")
+                        .takes_value(true)
+                        .default_value("5"),
+                )
+                .arg(Arg::with_name("dry-run").long("dry-run")),
+        )
+        .subcommand(
+            SubCommand::with_name("rank")
+                .about("Rank a project's open tasks by choosing between pairs, and set their priorities")
+                .arg(
+                    Arg::with_name("project")
+                        .long("project")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("max")
+                        .long("max")
+                        .takes_value(true)
+                        .default_value("5"),
+                ),
+        )
+        .subcommand(SubCommand::with_name("plugins").about("List the installed plugins"))
+        .subcommand(SubCommand::with_name("check").about("Flag tasks that need attention"))
+        .subcommand(
+            SubCommand::with_name("repair")
+                .about("Move records of the store that are not valid tasks to tasks.rejected.json")
+                .arg(
+                    Arg::with_name("check")
+                        .long("check")
+                        .help("Only report such records, failing when there are any"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("digest")
+                .about("Email a summary of overdue, due and just completed tasks")
+                .arg(
+                    Arg::with_name("print")
+                        .long("print")
+                        .help("Print the digest instead of emailing it"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("tick")
+                .about("Apply escalation rules to tasks nearing their due date, give reminders and move old finished tasks out of the store"),
+        )
+        .get_matches();
+    JSON_ERRORS.store(matches.is_present("json"), Ordering::Relaxed);
+
+    // Paths given on the command line are relative to where the command was run
+    let launch_dir = env::current_dir().unwrap_or_default();
+    let data_dir_error = platform::enter_data_dir().err();
+    let (mut config, config_error) = match load_config() {
+        Ok(config) => (config, None),
+        Err(err) => (Config::default(), Some(err)),
+    };
+    i18n::init(Locale::resolve(&config.display));
+    if let Some(err) = data_dir_error {
+        let message = t!("data-dir-failed", error = err.to_string());
+        report_error(ErrorKind::Io, &message, None);
+    }
+    if let Some(err) = config_error {
+        let message = t!("config-read-failed", error = err.to_string());
+        report_error(ErrorKind::Config, &message, None);
+    }
+    if matches.is_present("ascii") {
+        config.display.ascii = Some(true);
+    }
+    let fmt = Formatter::new(&config.display).unwrap_or_else(|err| {
+        report_error(ErrorKind::Config, &err, None);
+        Formatter::default()
+    });
+    let fmt = match calendar::Calendar::new(&config.calendar) {
+        Ok(calendar) => fmt.with_calendar(calendar),
+        Err(err) => {
+            report_error(ErrorKind::Config, &err, None);
+            fmt
+        }
+    };
+    timings.enabled = matches.is_present("timings");
+    timings.lap("timing-startup");
+    // Listings only read the tasks, so they stream them from the store instead of loading them all
+    let streamed = matches!(
+        matches.subcommand_name(),
+        Some("list" | "search" | "list-by-project" | "list-by-status" | "list-by-priority")
+    );
+    // A store that cannot be read, e.g. without its key, must not be saved over as if it were empty
+    let mut tasks = if streamed || matches!(matches.subcommand_name(), Some("key" | "auth")) {
+        vec![]
+    } else {
+        match load_tasks() {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                report_error(ErrorKind::Store, &err.to_string(), None);
+                return;
+            }
+        }
+    };
+    slug::assign(&mut tasks);
+    timings.lap("timing-load");
+    let wasm = config.wasm_plugins.as_ref().and_then(|dir| {
+        wasm::PluginHost::load(Path::new(dir))
+            .map_err(|err| {
+                let message = t!("wasm-plugins-failed", error = err.to_string());
+                report_error(ErrorKind::Plugin, &message, None);
+            })
+            .ok()
+    });
+    timings.lap("timing-plugins");
+
+    match matches.subcommand() {
+        ("add", Some(sub_m)) => {
+            let from_clipboard = if sub_m.is_present("from-clipboard") {
+                let text = match clipboard::read() {
+                    Ok(text) => text,
+                    Err(err) => {
+                        let message = t!("clipboard-failed", error = err.to_string());
+                        report_error(ErrorKind::Io, &message, None);
+                        return;
+                    }
+                };
+                match clipboard::split(&text) {
+                    Some(fields) => Some(fields),
+                    None => {
+                        report_error(ErrorKind::InvalidArgument, &t!("clipboard-empty"), None);
+                        return;
+                    }
+                }
+            } else {
+                None
+            };
+            let (title, description) = match &from_clipboard {
+                Some((title, description)) => (title.as_str(), description.as_str()),
+                None => (
+                    sub_m.value_of("title").unwrap(),
+                    sub_m.value_of("description").unwrap(),
+                ),
+            };
+            let priority = match sub_m.value_of("priority").map(str::parse::<u8>) {
+                None => 1,
+                Some(Ok(priority)) => priority,
+                Some(Err(_)) => {
+                    let message = t!("invalid-priority-value");
+                    report_error(ErrorKind::InvalidArgument, &message, None);
+                    return;
+                }
+            };
+            let status = sub_m.value_of("status").unwrap_or("Todo");
+            let project = sub_m.value_of("project").unwrap_or(INBOX_PROJECT);
+            let due = sub_m.value_of("due").map(|due| parse_due_at(due, &fmt));
+            let (due, due_at) = match due.transpose() {
+                Ok(Some((due, due_at))) => (Some(due), due_at),
+                Ok(None) => (None, None),
+                Err(err) => {
+                    report_error(ErrorKind::InvalidArgument, &t!(err), None);
+                    return;
+                }
+            };
+            let tags = sub_m
+                .values_of("tag")
+                .map(|tags| tags.map(String::from).collect())
+                .unwrap_or_default();
+            let estimate = match sub_m.value_of("estimate").map(parse_hours).transpose() {
+                Ok(estimate) => estimate,
+                Err(err) => {
+                    report_error(ErrorKind::InvalidArgument, &t!(err), None);
+                    return;
+                }
+            };
+
+            let mut new_task = Task {
+                title: title.to_string(),
+                description: description.to_string(),
+                priority,
+                status: status.to_string(),
+                project: project.to_string(),
+                due,
+                due_at,
+                tags,
+                estimate,
+                created_at: Some(Utc::now()),
+                completed_at: is_done(status).then(Utc::now),
+                started_at: is_in_progress(status).then(Utc::now),
+                blocked_at: is_blocked(status).then(Utc::now),
+                external_id: sub_m.value_of("external-id").map(String::from),
+                url: sub_m.value_of("url").map(String::from),
+                ..Default::default()
+            };
+            if new_task.url.as_deref().is_some_and(|url| !is_url(url)) {
+                report_error(ErrorKind::InvalidArgument, &t!("invalid-url"), None);
+                return;
+            }
+            if let Some(goal) = sub_m.value_of("goal") {
+                let goals = match goal::load() {
+                    Ok(goals) => goals,
+                    Err(err) => {
+                        report_error(ErrorKind::Store, &err.to_string(), None);
+                        return;
+                    }
+                };
+                let key_result = sub_m.value_of("key-result");
+                if let Err(err) = link_goal(&mut new_task, goal, key_result, &goals) {
+                    report_error(ErrorKind::of(err), &t!(err), None);
+                    return;
+                }
+            }
+            if let Some(id) = &new_task.external_id {
+                let exists = tasks
+                    .iter()
+                    .any(|task| task.external_id.as_ref() == Some(id));
+                if exists && !sub_m.is_present("upsert") {
+                    let message = t!("external-id-exists", id = id.as_str());
+                    report_error(ErrorKind::Conflict, &message, None);
+                    return;
+                }
+            }
+            // An upserted task keeps its place, even under another title
+            let current = new_task.external_id.as_ref().and_then(|id| {
+                tasks
+                    .iter()
+                    .find(|task| task.external_id.as_ref() == Some(id))
+            });
+            let current_title = current.map_or(title, |task| task.title.as_str());
+            let exceeded = wip::exceeded(&tasks, current_title, status, project, &config.wip);
+            if !check_wip_limits(&exceeded, &config.wip, sub_m.is_present("force")) {
+                return;
+            }
+            if let Some(host) = &wasm {
+                if let Err(err) = host.on_add(&mut new_task) {
+                    report_error(ErrorKind::Plugin, &err.to_string(), None);
+                }
+            }
+
+            if upsert_task(&mut tasks, new_task) {
+                unblock_ready_tasks(&mut tasks);
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                println!("{}", t!("task-updated"));
+            } else {
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                println!("{}", t!("task-added"));
+            }
+        }
+        ("open", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            let task = &tasks[index];
+            let title = task.title.as_str();
+            let Some(url) = task_url(task) else {
+                report_error(ErrorKind::NotFound, &t!("no-url"), Some(title));
+                return;
+            };
+            if let Err(err) = open_url(url) {
+                let message = t!("open-failed", url = url, error = err.to_string());
+                report_error(ErrorKind::Failed, &message, Some(title));
+            }
+        }
+        ("capture", Some(sub_m)) => {
+            let line: Vec<&str> = sub_m.values_of("line").unwrap().collect();
+            let capture = match capture::parse(&line.join(" "), fmt.today(), fmt.calendar()) {
+                Ok(capture) => capture,
+                Err(err) => {
+                    report_error(ErrorKind::InvalidArgument, &err, None);
+                    return;
+                }
+            };
+            let project = capture
+                .project
+                .unwrap_or_else(|| String::from(INBOX_PROJECT));
+            let exceeded = wip::exceeded(&tasks, &capture.title, "Todo", &project, &config.wip);
+            if !check_wip_limits(&exceeded, &config.wip, sub_m.is_present("force")) {
+                return;
+            }
+            let mut new_task = Task {
+                title: capture.title,
+                priority: capture.priority.unwrap_or(1),
+                status: String::from("Todo"),
+                project,
+                due: capture.due,
+                tags: capture.tags,
+                contexts: capture.contexts,
+                created_at: Some(Utc::now()),
+                ..Default::default()
+            };
+            if let Some(host) = &wasm {
+                if let Err(err) = host.on_add(&mut new_task) {
+                    report_error(ErrorKind::Plugin, &err.to_string(), None);
+                }
+            }
+            tasks.push(new_task);
+            if !save_or_report(&tasks) {
+                return;
+            }
+            println!("{}", t!("task-added"));
+            print_task(tasks.len(), &tasks[tasks.len() - 1], &fmt);
+        }
+        ("triage", _) => {
+            let inbox: Vec<usize> = (0..tasks.len())
+                .filter(|&i| tasks[i].project == INBOX_PROJECT && !is_done(&tasks[i].status))
+                .collect();
+            if inbox.is_empty() {
+                println!("{}", t!("inbox-empty"));
+                return;
+            }
+            println!("{}", t!("triage-help"));
+            let mut triaged = 0;
+            for (number, &index) in inbox.iter().enumerate() {
+                let task = &mut tasks[index];
+                println!();
+                println!("{}/{} {}", number + 1, inbox.len(), task.title);
+                if !task.description.is_empty() {
+                    println!("  {}", task.description);
+                }
+                match triage_task(task, &fmt, &mut io::stdin().lock()) {
+                    Ok(()) => triaged += 1,
+                    Err(Stop::Skip) => continue,
+                    Err(Stop::Quit) => break,
+                }
+            }
+            if triaged > 0 && !save_or_report(&tasks) {
+                return;
+            }
+            let left = tasks
+                .iter()
+                .filter(|task| task.project == INBOX_PROJECT && !is_done(&task.status))
+                .count();
+            println!("{}", t!("triage-done", count = triaged, left = left));
+        }
+        ("remove", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            let title = tasks[index].title.clone();
+            let affected: Vec<&Task> = tasks.iter().filter(|task| task.title == title).collect();
+            println!("{}", t!("remove-heading", count = affected.len()));
+            for task in &affected {
+                println!("  {}", task.title);
+            }
+            if !confirm(&t!("remove-confirm"), sub_m.is_present("yes")) {
+                println!("{}", t!("aborted"));
+                return;
+            }
+
+            tasks.retain(|task| task.title != title);
+            unblock_ready_tasks(&mut tasks);
+            if !save_or_report(&tasks) {
+                return;
+            }
+            println!("{}", t!("task-removed"));
+        }
+        ("show", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            print!("{}", render_task_details(&tasks[index], &tasks, &fmt));
+        }
+        ("done", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            mark_done(&mut tasks, index);
+            if !save_or_report(&tasks) {
+                return;
+            }
+            println!("{}", t!("task-done", title = tasks[index].title.as_str()));
+        }
+        ("list", Some(sub_m)) => {
+            let filters: Vec<&str> = sub_m
+                .values_of("filter")
+                .map(|filters| filters.collect())
+                .unwrap_or_default();
+            let columns = match sub_m.value_of("columns") {
+                Some(names) => {
+                    let columns: Result<Vec<Column>, String> = names
+                        .split(',')
+                        .map(|name| {
+                            Column::parse(name).ok_or_else(|| t!("unknown-column", name = name))
+                        })
+                        .collect();
+                    match columns {
+                        Ok(columns) => Some(columns),
+                        Err(message) => {
+                            report_error(ErrorKind::InvalidArgument, &message, None);
+                            return;
+                        }
+                    }
+                }
+                None => None,
+            };
+            let query = match Query::from_matches(sub_m, fmt.today()) {
+                Ok(query) => query,
+                Err(message) => {
+                    report_error(ErrorKind::InvalidArgument, &message, None);
+                    return;
+                }
+            };
+            let sort = match sub_m.value_of("sort").map(Sort::parse).transpose() {
+                Ok(sort) => sort,
+                Err(message) => {
+                    report_error(ErrorKind::InvalidArgument, &message, None);
+                    return;
+                }
+            };
+            let table = sub_m.is_present("table") || columns.is_some();
+            if sub_m.is_present("all-stores") {
+                let stores = std::iter::once((t!("this-store"), load_tasks())).chain(
+                    config
+                        .stores
+                        .iter()
+                        .map(|(name, dir)| (name.clone(), load_tasks_from(Path::new(dir)))),
+                );
+                for (name, tasks) in stores {
+                    let tasks = match tasks {
+                        Ok(tasks) => tasks,
+                        Err(err) => {
+                            let message = t!("store-failed", name = name, error = err.to_string());
+                            report_error(ErrorKind::Store, &message, None);
+                            continue;
+                        }
+                    };
+                    let mut rows: Vec<&Task> =
+                        tasks.iter().filter(|task| query.matches(task)).collect();
+                    if let Some(sort) = sort {
+                        sort.apply(&mut rows, fmt.today());
+                    }
+                    if rows.is_empty() {
+                        continue;
+                    }
+                    println!("{}", t!("store-heading", name = name));
+                    if table {
+                        let width = table::terminal_width();
+                        let (columns, fixed) = match &columns {
+                            Some(columns) => (columns.as_slice(), true),
+                            None => (fmt.columns(), false),
+                        };
+                        print!("{}", table::render(&rows, columns, width, fixed, &fmt));
+                    } else {
+                        for (index, task) in rows.iter().enumerate() {
+                            print_task(index + 1, task, &fmt);
+                        }
+                    }
+                    println!();
+                }
+                return;
+            }
+            let plans = match load_plans() {
+                Ok(plans) => plans,
+                Err(err) => {
+                    report_error(ErrorKind::Io, &err.to_string(), None);
+                    return;
+                }
+            };
+            let today = fmt.today();
+            let plan = plans.get(&today).map_or(&[][..], Vec::as_slice);
+
+            // A first pass keeps only the tasks in today's plan and the newly unblocked ones, and
+            // notes what Blocked tasks wait for to tell whether any can be unblocked
+            let mut in_plan: Vec<Task> = Vec::new();
+            let mut unblocked: Vec<String> = Vec::new();
+            let mut open_titles: HashSet<String> = HashSet::new();
+            let mut dependencies: Vec<Vec<String>> = Vec::new();
+            let mut stale = 0;
+            let now = Utc::now();
+            let first_pass = stream_tasks().and_then(|stream| {
+                for task in stream {
+                    let task = task?;
+                    if config.stale.nag && is_stale(&task, now, config.stale.after_days) {
+                        stale += 1;
+                    }
+                    if task.newly_unblocked {
+                        unblocked.push(task.title.clone());
+                    }
+                    if !is_done(&task.status) {
+                        open_titles.insert(task.title.clone());
+                    }
+                    if is_blocked(&task.status) && task.waiting_on.is_none() {
+                        dependencies.push(task.depends_on.clone());
+                    }
+                    if plan.contains(&task.title) {
+                        in_plan.push(task);
+                    }
+                }
+                Ok(())
+            });
+            if let Err(err) = first_pass {
+                report_error(ErrorKind::Store, &err.to_string(), None);
+                return;
+            }
+            // Dependencies may have been finished elsewhere, e.g. by a sync, so the tasks ready now
+            // are unblocked before they are listed
+            let ready = dependencies.iter().any(|depends_on| {
+                !depends_on.is_empty() && !depends_on.iter().any(|dep| open_titles.contains(dep))
+            });
+            if ready {
+                match load_tasks() {
+                    Ok(mut tasks) => {
+                        unblocked.extend(unblock_ready_tasks(&mut tasks));
+                        if !save_or_report(&tasks) {
+                            return;
+                        }
+                    }
+                    Err(err) => {
+                        report_error(ErrorKind::Store, &err.to_string(), None);
+                        return;
+                    }
+                }
+            }
+
+            if stale > 0 {
+                println!("{}", t!("stale-nag", count = stale));
+                println!();
+            }
+
+            let planned = planned_tasks(&in_plan, plan);
+            if !planned.is_empty() {
+                println!("{}", t!("today-heading"));
+                for task in &planned {
+                    let mark = if is_done(&task.status) { "x" } else { " " };
+                    let entry = t!(
+                        "today-entry",
+                        mark = mark,
+                        title = task.title.as_str(),
+                        priority = task.priority
+                    );
+                    println!("  {}", entry);
+                }
+                println!();
+            }
+
+            if !unblocked.is_empty() {
+                println!("{}", t!("newly-unblocked-heading"));
+                for title in &unblocked {
+                    println!("  {}", title);
+                }
+                println!();
+            }
+
+            let print_row = |index: usize, task: &Task| {
+                print_task(index, task, &fmt);
+                if let Some(host) = &wasm {
+                    match host.on_list_render(task) {
+                        Ok(lines) => lines.iter().for_each(|line| println!("    {}", line)),
+                        Err(err) => report_error(ErrorKind::Plugin, &err.to_string(), None),
+                    }
+                }
+            };
+            let mut index = 0;
+            let mut rows: Vec<Task> = Vec::new();
+            let second_pass = stream_tasks().and_then(|stream| {
+                for task in stream {
+                    let task = task?;
+                    if !query.matches(&task) {
+                        continue;
+                    }
+                    let mut keep = true;
+                    for name in &filters {
+                        let kept = match &wasm {
+                            Some(host) => host.filter(name, &task)?,
+                            None => None,
+                        };
+                        match kept {
+                            Some(kept) => keep &= kept,
+                            None => return Err(t!("unknown-filter", name = *name).into()),
+                        }
+                    }
+                    if !keep {
+                        continue;
+                    }
+                    // Sorted tasks can only be shown once all were read
+                    if table || sort.is_some() {
+                        rows.push(task);
+                        continue;
+                    }
+                    index += 1;
+                    print_row(index, &task);
+                }
+                Ok(())
+            });
+            if let Err(err) = second_pass {
+                report_error(ErrorKind::Failed, &err.to_string(), None);
+                return;
+            }
+            if let Some(sort) = sort {
+                sort.apply(&mut rows, today);
+            }
+            if !table {
+                for (index, task) in rows.iter().enumerate() {
+                    print_row(index + 1, task);
+                }
+            } else {
+                let rows: Vec<&Task> = rows.iter().collect();
+                let width = table::terminal_width();
+                match &columns {
+                    Some(columns) => print!("{}", table::render(&rows, columns, width, true, &fmt)),
+                    None => print!(
+                        "{}",
+                        table::render(&rows, fmt.columns(), width, false, &fmt)
+                    ),
+                }
+                for (index, task) in rows.iter().enumerate() {
+                    listing::show(index + 1, task);
+                }
+            }
+            if let Err(err) = listing::save() {
+                report_error(ErrorKind::Io, &err.to_string(), None);
+            }
+
+            if !unblocked.is_empty() {
+                if let Ok(mut tasks) = load_tasks() {
+                    for task in tasks.iter_mut() {
+                        task.newly_unblocked = false;
+                    }
+                    save_or_report(&tasks);
+                }
+            }
+        }
+        ("list-by-project", Some(sub_m)) => {
+            if let Some(project_name) = sub_m.value_of("project") {
+                let result = stream_tasks()
+                    .and_then(|tasks| list_tasks_by_project(tasks, project_name, &fmt));
+                if let Err(err) = result {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                }
+            } else {
+                report_error(
+                    ErrorKind::InvalidArgument,
+                    &t!("missing-project-option"),
+                    None,
+                );
+            }
+        }
+        ("list-by-status", Some(sub_m)) => {
+            if let Some(status) = sub_m.value_of("status") {
+                let result =
+                    stream_tasks().and_then(|tasks| list_tasks_by_status(tasks, status, &fmt));
+                if let Err(err) = result {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                }
+            } else {
+                report_error(
+                    ErrorKind::InvalidArgument,
+                    &t!("missing-status-option"),
+                    None,
+                );
+            }
+        }
+        ("list-by-priority", Some(sub_m)) => {
+            if let Some(priority) = sub_m.value_of("priority") {
+                if let Ok(priority) = priority.parse::<u8>() {
+                    let result = stream_tasks()
+                        .and_then(|tasks| list_tasks_by_priority(tasks, priority, &fmt));
+                    if let Err(err) = result {
+                        report_error(ErrorKind::Store, &err.to_string(), None);
+                    }
+                } else {
+                    report_error(
+                        ErrorKind::InvalidArgument,
+                        &t!("invalid-priority-value"),
+                        None,
+                    );
+                }
+            } else {
+                report_error(
+                    ErrorKind::InvalidArgument,
+                    &t!("missing-priority-option"),
+                    None,
+                );
+            }
+        }
+        ("search", Some(sub_m)) => {
+            let query = sub_m.value_of("query").unwrap().to_lowercase();
+            let matches_query = |task: &Task| {
+                task.title.to_lowercase().contains(&query)
+                    || task.description.to_lowercase().contains(&query)
+            };
+            let result =
+                stream_tasks().and_then(|tasks| print_matching_tasks(tasks, matches_query, &fmt));
+            if let Err(err) = result {
+                report_error(ErrorKind::Store, &err.to_string(), None);
+            }
+        }
+
+        ("update", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            let title = tasks[index].title.clone();
+            let title = title.as_str();
+            if let Some(expected) = sub_m.value_of("if-revision") {
+                let Ok(expected) = expected.parse::<u64>() else {
+                    report_error(ErrorKind::InvalidArgument, &t!("invalid-revision"), None);
+                    return;
+                };
+                let current = tasks.iter().find(|task| task.title == title);
+                if let Some(current) = current.filter(|task| task.revision != expected) {
+                    let message = t!(
+                        "revision-conflict",
+                        expected = expected,
+                        revision = current.revision
+                    );
+                    report_error(ErrorKind::Conflict, &message, Some(title));
+                    return;
+                }
+            }
+            if let Some(task) = tasks.iter().find(|task| task.title == title) {
+                let status = sub_m.value_of("status").unwrap_or(&task.status);
+                let project = sub_m.value_of("project").unwrap_or(&task.project);
+                let exceeded = wip::exceeded(&tasks, title, status, project, &config.wip);
+                if !check_wip_limits(&exceeded, &config.wip, sub_m.is_present("force")) {
+                    return;
+                }
+            }
+            if sub_m.is_present("cascade-dates") {
+                let Ok((due, _)) = parse_due_at(sub_m.value_of("due").unwrap(), &fmt) else {
+                    report_error(ErrorKind::InvalidArgument, &t!("invalid-due"), None);
+                    return;
+                };
+                let shifted = match tasks[index].due {
+                    Some(old) if old != due => cascade_due_dates(&tasks, title, due - old),
+                    _ => vec![],
+                };
+                if !shifted.is_empty() {
+                    println!("{}", t!("cascade-heading", count = shifted.len()));
+                    for (dependent, new_due) in &shifted {
+                        let task = &tasks[*dependent];
+                        let old_due = fmt.date(task.due.unwrap());
+                        println!("  {}: {} -> {}", task.title, old_due, fmt.date(*new_due));
+                    }
+                    if confirm(&t!("cascade-confirm"), sub_m.is_present("yes")) {
+                        let delta = due - tasks[index].due.unwrap();
+                        for (dependent, new_due) in shifted {
+                            let task = &mut tasks[dependent];
+                            task.due = Some(new_due);
+                            task.due_at =
+                                task.due_at.map(|due_at| shift_due_at(due_at, delta, &fmt));
+                            touch(task);
+                        }
+                    }
+                }
+            }
+            if let Err(err) = update_task(title, sub_m, &mut tasks, &fmt) {
+                report_error(ErrorKind::of(err), &t!(err), Some(title));
+            } else {
+                println!("{}", t!("task-updated"));
+            }
+        }
+        ("log-time", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            let entry = parse_hours(sub_m.value_of("hours").unwrap()).and_then(|hours| {
+                let date = match sub_m.value_of("date") {
+                    Some(date) => parse_due(date)?,
+                    None => fmt.today(),
+                };
+                Ok(TimeEntry { date, hours })
+            });
+            match entry {
+                Err(err) => report_error(ErrorKind::InvalidArgument, &t!(err), None),
+                Ok(entry) => {
+                    let task = &mut tasks[index];
+                    task.time_entries.push(entry);
+                    touch(task);
+                    if !save_or_report(&tasks) {
+                        return;
+                    }
+                    println!("{}", t!("time-logged"));
+                }
+            }
+        }
+        ("remind", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            let reminders: Result<Vec<Reminder>, _> = sub_m
+                .values_of("at")
+                .unwrap()
+                .map(|at| Reminder::parse(at, &fmt))
+                .collect();
+            let reminders = match reminders {
+                Ok(reminders) => reminders,
+                Err(err) => {
+                    report_error(ErrorKind::InvalidArgument, &t!(err), None);
+                    return;
+                }
+            };
+            let task = &mut tasks[index];
+            let count = reminders.len();
+            if reminders.iter().any(|r| r.time(task, &fmt).is_none()) {
+                println!("{}", t!("warning", message = t!("reminder-without-due")));
+            }
+            task.reminders.extend(reminders);
+            touch(task);
+            if !save_or_report(&tasks) {
+                return;
+            }
+            println!("{}", t!("reminders-added", count = count));
+        }
+        ("reminders", Some(sub_m)) => {
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            if let Some(numbers) = sub_m.values_of("remove") {
+                let task = &mut tasks[index];
+                let mut remove = Vec::new();
+                for number in numbers {
+                    match number.parse::<usize>() {
+                        Ok(n) if (1..=task.reminders.len()).contains(&n) => remove.push(n - 1),
+                        _ => {
+                            let message = t!("reminder-not-found", number = number);
+                            report_error(ErrorKind::NotFound, &message, None);
+                            return;
+                        }
+                    }
+                }
+                remove.sort_unstable();
+                remove.dedup();
+                for &index in remove.iter().rev() {
+                    task.reminders.remove(index);
+                }
+                touch(task);
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                println!("{}", t!("reminders-removed", count = remove.len()));
+                return;
+            }
+            let task = &tasks[index];
+            if task.reminders.is_empty() {
+                println!("{}", t!("no-reminders"));
+            }
+            for (number, reminder) in task.reminders.iter().enumerate() {
+                let time = reminder.time(task, &fmt).map(|time| fmt.datetime(time));
+                match time {
+                    Some(time) if !matches!(reminder.when, reminder::When::At(_)) => {
+                        println!("{}. {} ({})", number + 1, reminder.describe(&fmt), time)
+                    }
+                    _ => println!("{}. {}", number + 1, reminder.describe(&fmt)),
+                }
+            }
+        }
+        ("report", Some(sub_m)) => match sub_m.subcommand() {
+            ("accuracy", _) => print_accuracy_report(&tasks),
+            _ => println!("{}", t!("choose-report")),
+        },
+        ("project", Some(sub_m)) => match sub_m.subcommand() {
+            ("analyze", Some(analyze_m)) => {
+                let name = analyze_m.value_of("name").unwrap();
+                let Some(analysis) = critical::analyze(&tasks, name) else {
+                    println!("{}", t!("analyze-no-tasks", project = name));
+                    return;
+                };
+                let hours = |hours: f64| fmt.number(hours, 1);
+                println!(
+                    "{}",
+                    t!(
+                        "analyze-summary",
+                        project = name,
+                        remaining = hours(analysis.remaining),
+                        length = hours(analysis.length)
+                    )
+                );
+                println!("\n{}", t!("analyze-path"));
+                for task in &analysis.path {
+                    println!("  {} ({} h)", task.title, hours(critical::remaining(task)));
+                }
+                println!("\n{}", t!("analyze-slack"));
+                for (task, slack) in &analysis.slack {
+                    let note = if *slack > 0.0 {
+                        t!("analyze-can-slip", hours = hours(*slack))
+                    } else {
+                        t!("analyze-critical")
+                    };
+                    println!("  {}: {}", task.title, note);
+                }
+                if !analysis.unestimated.is_empty() {
+                    let titles: Vec<&str> = analysis
+                        .unestimated
+                        .iter()
+                        .map(|t| t.title.as_str())
+                        .collect();
+                    println!("\n{}", t!("analyze-unestimated", tasks = titles.join(", ")));
+                }
+            }
+            _ => println!("{}", t!("choose-project-command")),
+        },
+        ("goal", Some(sub_m)) => {
+            let mut goals = match goal::load() {
+                Ok(goals) => goals,
+                Err(err) => {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                    return;
+                }
+            };
+            match sub_m.subcommand() {
+                ("add", Some(add_m)) => {
+                    let name = add_m.value_of("name").unwrap();
+                    if goals.iter().any(|goal| goal.name == name) {
+                        let message = t!("goal-exists", name = name);
+                        report_error(ErrorKind::Conflict, &message, None);
+                        return;
+                    }
+                    goals.push(goal::Goal {
+                        name: name.to_string(),
+                        period: add_m.value_of("period").map(String::from),
+                        key_results: add_m
+                            .values_of("key-result")
+                            .map(|krs| krs.map(String::from).collect())
+                            .unwrap_or_default(),
+                    });
+                    if let Err(err) = goal::save(&goals) {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                        return;
+                    }
+                    println!("{}", t!("goal-added"));
+                }
+                ("progress", _) => {
+                    if goals.is_empty() {
+                        println!("{}", t!("no-goals"));
+                    }
+                    let percent = |tasks: &[&Task]| match goal::progress(tasks) {
+                        Some(progress) => {
+                            t!("goal-percent", percent = fmt.number(progress * 100.0, 0))
+                        }
+                        None => t!("goal-no-tasks"),
+                    };
+                    for goal in &goals {
+                        let linked: Vec<&Task> = tasks
+                            .iter()
+                            .filter(|task| task.goal.as_ref() == Some(&goal.name))
+                            .collect();
+                        let done = linked.iter().filter(|task| is_done(&task.status)).count();
+                        let name = match &goal.period {
+                            Some(period) => format!("{} ({})", goal.name, period),
+                            None => goal.name.clone(),
+                        };
+                        println!(
+                            "{}",
+                            t!(
+                                "goal-progress",
+                                goal = name,
+                                progress = percent(&linked),
+                                done = done,
+                                total = linked.len()
+                            )
+                        );
+                        for key_result in &goal.key_results {
+                            let tasks: Vec<&Task> = linked
+                                .iter()
+                                .copied()
+                                .filter(|task| task.key_result.as_ref() == Some(key_result))
+                                .collect();
+                            println!("  {}: {}", key_result, percent(&tasks));
+                        }
+                    }
+                }
+                _ => println!("{}", t!("choose-goal-command")),
+            }
+        }
+        ("habit", Some(sub_m)) => {
+            let mut habits = match habit::load() {
+                Ok(habits) => habits,
+                Err(err) => {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                    return;
+                }
+            };
+            let today = fmt.today();
+            match sub_m.subcommand() {
+                ("add", Some(add_m)) => {
+                    let name = add_m.value_of("name").unwrap();
+                    let Some(every) = habit::Frequency::parse(add_m.value_of("every").unwrap())
+                    else {
+                        report_error(ErrorKind::InvalidArgument, &t!("invalid-frequency"), None);
+                        return;
+                    };
+                    if habits.iter().any(|habit| habit.name == name) {
+                        let message = t!("habit-exists", name = name);
+                        report_error(ErrorKind::Conflict, &message, None);
+                        return;
+                    }
+                    habits.push(habit::Habit {
+                        name: name.to_string(),
+                        every,
+                        done: Default::default(),
+                    });
+                    if let Err(err) = habit::save(&habits) {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                        return;
+                    }
+                    println!("{}", t!("habit-added"));
+                }
+                ("done", Some(done_m)) => {
+                    let name = done_m.value_of("name").unwrap();
+                    let date = match done_m.value_of("date").map(parse_due).transpose() {
+                        Ok(date) => date.unwrap_or(today),
+                        Err(err) => {
+                            report_error(ErrorKind::InvalidArgument, &t!(err), None);
+                            return;
+                        }
+                    };
+                    let Some(habit) = habits.iter_mut().find(|habit| habit.name == name) else {
+                        report_error(ErrorKind::NotFound, &t!("habit-not-found"), Some(name));
+                        return;
+                    };
+                    habit.done.insert(date);
+                    let streak = habit.current_streak(today, fmt.week_start());
+                    if let Err(err) = habit::save(&habits) {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                        return;
+                    }
+                    println!("{}", t!("habit-kept", name = name, streak = streak));
+                }
+                ("streaks", _) => {
+                    if habits.is_empty() {
+                        println!("{}", t!("no-habits"));
+                    }
+                    for habit in &habits {
+                        let week_start = fmt.week_start();
+                        let every = match habit.every {
+                            habit::Frequency::Day => "day",
+                            habit::Frequency::Week => "week",
+                        };
+                        let mark = fmt.glyph("✔", "x");
+                        let kept = if habit.kept(today, week_start) {
+                            mark
+                        } else {
+                            " "
+                        };
+                        println!(
+                            "[{}] {}",
+                            kept,
+                            t!(
+                                "habit-streak",
+                                name = habit.name.as_str(),
+                                every = every,
+                                current = habit.current_streak(today, week_start),
+                                longest = habit.longest_streak(week_start)
+                            )
+                        );
+                    }
+                }
+                _ => println!("{}", t!("choose-habit-command")),
+            }
+        }
+        ("today", Some(sub_m)) => {
+            // Plans that cannot be read must not be saved over as if there were none
+            let mut plans = match load_plans() {
+                Ok(plans) => plans,
+                Err(err) => {
+                    report_error(ErrorKind::Io, &err.to_string(), None);
+                    return;
+                }
+            };
+            let today = fmt.today();
+            match sub_m.subcommand() {
+                ("add", Some(add_m)) => {
+                    let Some(index) = find_task(&tasks, add_m.value_of("title").unwrap()) else {
+                        return;
+                    };
+                    let title = tasks[index].title.as_str();
+                    let plan = plans.entry(today).or_default();
+                    if !plan.iter().any(|t| t == title) {
+                        plan.push(title.to_string());
+                    }
+                    if let Err(err) = save_plans(&plans) {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                        return;
+                    }
+                    println!("{}", t!("plan-task-added"));
+                }
+                ("clear", Some(clear_m)) => {
+                    let planned =
+                        planned_tasks(&tasks, plans.get(&today).map_or(&[], Vec::as_slice));
+                    if !planned.is_empty() {
+                        println!("{}", t!("today-heading"));
+                        for task in &planned {
+                            println!("  {}", task.title);
+                        }
+                        if !confirm(&t!("plan-clear-confirm"), clear_m.is_present("yes")) {
+                            println!("{}", t!("aborted"));
+                            return;
+                        }
+                    }
+                    plans.remove(&today);
+                    if let Err(err) = save_plans(&plans) {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                        return;
+                    }
+                    println!("{}", t!("plan-cleared"));
+                }
+                _ => {
+                    let planned =
+                        planned_tasks(&tasks, plans.get(&today).map_or(&[], Vec::as_slice));
+                    for (index, task) in planned.iter().enumerate() {
+                        print_task(index + 1, task, &fmt);
+                    }
+                    if let Err(err) = listing::save() {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                    }
+                }
+            }
+        }
+        ("delegate", Some(sub_m)) => {
+            let assignee = sub_m.value_of("to").unwrap();
+            let follow_up = match sub_m.value_of("follow-up").map(parse_due).transpose() {
+                Ok(date) => date.unwrap_or(fmt.today() + Duration::weeks(1)),
+                Err(err) => {
+                    report_error(ErrorKind::InvalidArgument, &t!(err), None);
+                    return;
+                }
+            };
+            let Some(index) = find_task(&tasks, sub_m.value_of("title").unwrap()) else {
+                return;
+            };
+            delegate_task(&mut tasks[index], assignee, follow_up);
+            if !save_or_report(&tasks) {
+                return;
+            }
+            println!(
+                "{}",
+                t!(
+                    "task-delegated",
+                    assignee = assignee,
+                    date = fmt.date(follow_up)
+                )
+            );
+        }
+        ("follow-ups", _) => {
+            let today = fmt.today();
+            for task in due_follow_ups(&tasks, today) {
+                let assignee = task.assignee.clone().unwrap_or_else(|| t!("nobody"));
+                println!(
+                    "{}",
+                    t!(
+                        "follow-up-entry",
+                        title = task.title.as_str(),
+                        assignee = assignee,
+                        date = fmt.date(task.follow_up.unwrap())
+                    )
+                );
+            }
+        }
+        ("waiting", _) => {
+            let now = Utc::now();
+            for (reason, group) in waiting_groups(&tasks) {
+                println!("{}", t!("waiting-heading", reason = reason));
+                for task in group {
+                    let age = match task.blocked_at {
+                        Some(blocked_at) => {
+                            let days = (now - blocked_at).num_days();
+                            let marker = match days {
+                                0..=6 => "",
+                                7..=29 => " !",
+                                _ => " !!",
+                            };
+                            format!("{}{}", t!("blocked-for", days = days), marker)
+                        }
+                        None => t!("blocked-since-unknown"),
+                    };
+                    println!("  {} ({})", task.title, age);
+                }
+            }
+        }
+        ("review", Some(sub_m)) => {
+            let mut reviews = match review::load() {
+                Ok(reviews) => reviews,
+                Err(err) => {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                    return;
+                }
+            };
+            if let Some(mark_m) = sub_m.subcommand_matches("mark") {
+                let mut marked = 0;
+                for reference in mark_m.values_of("title").into_iter().flatten() {
+                    let Some(index) = find_task(&tasks, reference) else {
+                        return;
+                    };
+                    let task = &mut tasks[index];
+                    task.last_reviewed = Some(Utc::now());
+                    touch(task);
+                    marked += 1;
+                }
+                for project in mark_m.values_of("project").into_iter().flatten() {
+                    if !tasks.iter().any(|task| task.project == project) {
+                        let message = t!("project-not-found", project = project);
+                        report_error(ErrorKind::NotFound, &message, None);
+                        return;
+                    }
+                    reviews.insert(project.to_string(), Utc::now());
+                    marked += 1;
+                }
+                if let Err(err) = review::save(&reviews) {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                    return;
+                }
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                println!("{}", t!("reviewed", count = marked));
+                return;
+            }
+            let today = fmt.today();
+            let projects = review::due_projects(&tasks, &config.review, &reviews, today, &fmt);
+            let due = review::due_tasks(&tasks, today, &fmt);
+            if projects.is_empty() && due.is_empty() {
+                println!("{}", t!("nothing-to-review"));
+                return;
+            }
+            if !projects.is_empty() {
+                println!("{}", t!("review-projects"));
+                for (project, last) in projects {
+                    let open = tasks
+                        .iter()
+                        .filter(|task| task.project == project && !is_done(&task.status))
+                        .count();
+                    let last = match last {
+                        Some(last) => t!("review-last", date = fmt.date(fmt.local_date(last))),
+                        None => t!("review-never"),
+                    };
+                    println!(
+                        "  {} ({}, {})",
+                        project,
+                        t!("open-count", count = open),
+                        last
+                    );
+                }
+            }
+            if !due.is_empty() {
+                println!("{}", t!("review-tasks"));
+                for (number, index) in due.into_iter().enumerate() {
+                    print_task(number + 1, &tasks[index], &fmt);
+                }
+                if let Err(err) = listing::save() {
+                    report_error(ErrorKind::Io, &err.to_string(), None);
+                }
+            }
+        }
+        ("board", Some(sub_m)) if sub_m.subcommand_name().is_some() => {
+            let (direction, move_m) = sub_m.subcommand();
+            let move_m = move_m.unwrap();
+            let Some(index) = find_task(&tasks, move_m.value_of("title").unwrap()) else {
+                return;
+            };
+            let up = direction == "move-up";
+            if !move_in_column(&mut tasks, index, up, sub_m.value_of("project")) {
+                let id = if up {
+                    "board-at-top"
+                } else {
+                    "board-at-bottom"
+                };
+                println!("{}", t!(id, title = tasks[index].title.as_str()));
+                return;
+            }
+            if !save_or_report(&tasks) {
+                return;
+            }
+            // The place as the board shows it, of the project if one is given
+            let task = &tasks[index];
+            let project = sub_m.value_of("project");
+            let place = tasks
+                .iter()
+                .filter(|other| other.status.eq_ignore_ascii_case(&task.status))
+                .filter(|other| project.is_none_or(|p| other.project == p))
+                .filter(|other| other.order <= task.order)
+                .count();
+            let message = t!(
+                "board-moved",
+                title = task.title.as_str(),
+                status = task.status.as_str(),
+                place = place
+            );
+            println!("{}", message);
+        }
+        ("board", Some(sub_m)) => {
+            let project = sub_m.value_of("project");
+            for (status, column) in board_columns(&tasks, project, &config.wip) {
+                let mut limit = config.wip.status_limit(&status);
+                if let Some(project) = project.filter(|_| is_in_progress(&status)) {
+                    limit = limit
+                        .into_iter()
+                        .chain(config.wip.project_limit(project))
+                        .min();
+                }
+                let heading = match limit {
+                    Some(limit) if column.len() > limit => fmt.alert(&t!(
+                        "board-column-over-limit",
+                        status = status.as_str(),
+                        count = column.len(),
+                        limit = limit
+                    )),
+                    Some(limit) => t!(
+                        "board-column-limit",
+                        status = status.as_str(),
+                        count = column.len(),
+                        limit = limit
+                    ),
+                    None => t!(
+                        "board-column",
+                        status = status.as_str(),
+                        count = column.len()
+                    ),
+                };
+                println!("{}", heading);
+                for task in column {
+                    println!("  {}", fmt.priority(task.priority, &task.title));
+                }
+                println!();
+            }
+        }
+        ("matrix", Some(sub_m)) => {
+            let Ok(top) = sub_m.value_of("top").unwrap().parse::<usize>() else {
+                report_error(ErrorKind::InvalidArgument, &t!("invalid-top"), None);
+                return;
+            };
+            let today = fmt.today();
+            let project = sub_m.value_of("project");
+            let quadrants = priority_matrix(&tasks, project, today, &config.matrix);
+            for (quadrant, group) in Quadrant::ALL.iter().zip(&quadrants) {
+                println!("{}", t!(quadrant.heading(), count = group.len()));
+                for task in group.iter().take(top) {
+                    let title = fmt.priority(task.priority, &task.title);
+                    match task.due {
+                        Some(due) => println!("  {} ({})", title, fmt.relative_due(due, today)),
+                        None => println!("  {}", title),
+                    }
+                }
+                if group.len() > top {
+                    println!("  {}", t!("and-more", count = group.len() - top));
+                }
+                println!();
+            }
+        }
+        ("stale", Some(sub_m)) => {
+            let after_days = match sub_m.value_of("days").map(str::parse::<i64>) {
+                None => config.stale.after_days,
+                Some(Ok(days)) if days >= 0 => days,
+                Some(_) => {
+                    report_error(ErrorKind::InvalidArgument, &t!("invalid-days"), None);
+                    return;
+                }
+            };
+            let now = Utc::now();
+            let groups = stale_groups(&tasks, now, after_days);
+            if groups.is_empty() {
+                println!("{}", t!("no-stale-tasks", days = after_days));
+            }
+            for (project, group) in groups {
+                println!("{}", t!("stale-heading", project = project));
+                for task in group {
+                    let days = last_activity(task).map_or(0, |last| (now - last).num_days());
+                    println!("  {} ({})", task.title, t!("untouched-for", days = days));
+                }
+            }
+        }
+        ("suggest", Some(sub_m)) => {
+            let today = fmt.today();
+            let candidates: Vec<&Task> = actionable_tasks(&tasks)
+                .into_iter()
+                .filter(|task| sub_m.value_of("project").is_none_or(|p| task.project == p))
+                .filter(|task| {
+                    sub_m.value_of("context").is_none_or(|context| {
+                        let context = context.trim_start_matches('@');
+                        task.contexts.iter().any(|c| c == context)
+                    })
+                })
+                .collect();
+            if candidates.is_empty() {
+                println!("{}", t!("nothing-to-suggest"));
+                return;
+            }
+
+            let mut rng = thread_rng();
+            let suggestion = if sub_m.is_present("random") {
+                candidates.choose(&mut rng).unwrap()
+            } else {
+                // Every candidate keeps a chance, even with priority 0 and no due date
+                let weights = candidates.iter().map(|task| urgency(task, today) + 1.0);
+                &candidates[WeightedIndex::new(weights).unwrap().sample(&mut rng)]
+            };
+            println!("{}", t!("suggestion-heading"));
+            print_task(1, suggestion, &fmt);
+        }
+        ("backup", Some(sub_m)) => match sub_m.subcommand() {
+            ("now", _) => {
+                let snapshot = load_plans().and_then(|plans| {
+                    let key = store_key(&config)?;
+                    backup::create(&config.backup, &tasks, &plans, Utc::now(), key.as_ref())
+                });
+                match snapshot {
+                    Ok(name) => println!("{}", t!("backup-taken", name = name)),
+                    Err(err) => report_error(ErrorKind::Io, &err.to_string(), None),
+                }
+            }
+            ("list", _) => match backup::list(&config.backup) {
+                Ok(snapshots) => {
+                    for taken_at in snapshots {
+                        println!(
+                            "{}  {}",
+                            taken_at.format("%Y%m%dT%H%M%SZ"),
+                            fmt.datetime(taken_at)
+                        );
+                    }
+                }
+                Err(err) => report_error(ErrorKind::Io, &err.to_string(), None),
+            },
+            ("restore", Some(restore_m)) => {
+                let name = restore_m.value_of("name").unwrap();
+                let snapshot = store_key(&config)
+                    .and_then(|key| backup::read(&config.backup, name, key.as_ref()));
+                let snapshot = match snapshot {
+                    Ok(snapshot) => snapshot,
+                    Err(err) => {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                        return;
+                    }
+                };
+                println!(
+                    "{}",
+                    t!(
+                        "backup-restore-summary",
+                        count = snapshot.tasks.len(),
+                        current = tasks.len()
+                    )
+                );
+                if !confirm(&t!("backup-restore-confirm"), restore_m.is_present("yes")) {
+                    println!("{}", t!("aborted"));
+                    return;
+                }
+                if !backup_before_change(&config) {
+                    return;
+                }
+                if !save_or_report(&snapshot.tasks) {
+                    return;
+                }
+                if let Err(err) = save_plans(&snapshot.plans) {
+                    report_error(ErrorKind::Io, &err.to_string(), None);
+                    return;
+                }
+                println!("{}", t!("backup-restored"));
+            }
+            _ => println!("{}", t!("choose-backup-command")),
+        },
+        ("export", Some(sub_m)) if sub_m.is_present("archive") => {
+            let archive = launch_dir.join(sub_m.value_of("archive").unwrap());
+            let skip: Vec<PathBuf> = config.encryption_key.iter().map(PathBuf::from).collect();
+            match workspace::export(Path::new("."), &archive, &skip) {
+                Ok(count) => println!("{}", t!("archive-exported", count = count)),
+                Err(err) => report_error(ErrorKind::Io, &err.to_string(), None),
+            }
+        }
+        ("export", Some(sub_m)) => {
+            let query = match Query::from_matches(sub_m, fmt.today()) {
+                Ok(query) => query,
+                Err(message) => {
+                    report_error(ErrorKind::InvalidArgument, &message, None);
+                    return;
+                }
+            };
+            let exported = match sub_m.value_of("format").unwrap() {
+                "dot" => graph::dot(&tasks, &query),
+                "mermaid" => graph::mermaid(&tasks, &query),
+                "opml" => opml::export(&tasks, &query),
+                "contexts" => places::contexts(&tasks, &query, &config.places),
+                "geojson" => places::geojson(&tasks, &query, &config.places),
+                _ => {
+                    let days = sub_m.value_of("days").unwrap().parse::<i64>().ok();
+                    let feed = days
+                        .filter(|days| *days > 0)
+                        .and_then(|days| feed::atom_feed(&tasks, &query, Utc::now(), days));
+                    match feed {
+                        Some(feed) => feed,
+                        None => {
+                            report_error(ErrorKind::InvalidArgument, &t!("invalid-days"), None);
+                            return;
+                        }
+                    }
+                }
+            };
+            match sub_m.value_of("output") {
+                Some(path) => {
+                    if let Err(err) = fs::write(launch_dir.join(path), exported) {
+                        report_error(ErrorKind::Io, &err.to_string(), None);
+                    }
+                }
+                None => print!("{}", exported),
+            }
+        }
+        ("import", Some(sub_m)) if sub_m.is_present("archive") => {
+            let archive = launch_dir.join(sub_m.value_of("archive").unwrap());
+            if !archive.is_file() {
+                let message = t!("archive-not-found", path = archive.display().to_string());
+                report_error(ErrorKind::NotFound, &message, None);
+                return;
+            }
+            if !tasks.is_empty() {
+                if !confirm(&t!("archive-import-confirm"), sub_m.is_present("yes")) {
+                    println!("{}", t!("aborted"));
+                    return;
+                }
+                if !backup_before_change(&config) {
+                    return;
+                }
+            }
+            match workspace::import(&archive, Path::new(".")) {
+                Ok(count) => println!("{}", t!("archive-imported", count = count)),
+                Err(err) => report_error(ErrorKind::Io, &err.to_string(), None),
+            }
+        }
+        ("import", Some(sub_m)) => {
+            let imported = fs::read_to_string(launch_dir.join(sub_m.value_of("file").unwrap()))
+                .map_err(Box::<dyn Error>::from)
+                .and_then(|source| opml::import(&source, &fmt));
+            let imported = match imported {
+                Ok(imported) => imported,
+                Err(err) => {
+                    report_error(ErrorKind::Failed, &err.to_string(), None);
+                    return;
+                }
+            };
+            let policy = OnDuplicate::parse(sub_m.value_of("on-duplicate").unwrap()).unwrap();
+            let (mut added, mut merged, mut skipped) = (0, 0, 0);
+            for mut task in imported {
+                if let Some((index, similarity)) = dedup::find(&tasks, &task.title) {
+                    let choice = match policy {
+                        OnDuplicate::Ask => ask_duplicate(&task, &tasks[index], similarity),
+                        policy => policy,
+                    };
+                    // Titles identify tasks, so one with the same title cannot be added again
+                    let exists = tasks.iter().any(|other| other.title == task.title);
+                    match choice {
+                        OnDuplicate::Merge => {
+                            dedup::merge(&mut tasks[index], task);
+                            touch(&mut tasks[index]);
+                            merged += 1;
+                            continue;
+                        }
+                        OnDuplicate::Create if !exists => {}
+                        _ => {
+                            skipped += 1;
+                            continue;
+                        }
+                    }
+                }
+                task.created_at = Some(Utc::now());
+                let status = task.status.clone();
+                set_status(&mut task, &status);
+                tasks.push(task);
+                added += 1;
+            }
+            // As with `update --depends-on`, new tasks waiting on open dependencies are blocked
+            let open: Vec<String> = tasks
+                .iter()
+                .filter(|task| !is_done(&task.status))
+                .map(|task| task.title.clone())
+                .collect();
+            for task in tasks.iter_mut().rev().take(added) {
+                let waiting = task.depends_on.iter().any(|dep| open.contains(dep));
+                if waiting && !is_done(&task.status) && !is_blocked(&task.status) {
+                    set_status(task, "Blocked");
+                }
+            }
+            if added + merged > 0 && !backup_before_change(&config) {
+                return;
+            }
+            if !save_or_report(&tasks) {
+                return;
+            }
+            println!(
+                "{}",
+                t!(
+                    "tasks-imported",
+                    count = added,
+                    merged = merged,
+                    skipped = skipped
+                )
+            );
+        }
+        ("auth", Some(sub_m)) => match sub_m.subcommand() {
+            ("login", Some(login_m)) => {
+                let name = login_m.value_of("service").unwrap();
+                let login = secrets::Login::parse(name).unwrap();
+                for (secret, question) in login.secrets() {
+                    let answer = secrets::prompt(&t!(question)).unwrap_or_default();
+                    if answer.is_empty() {
+                        println!("{}", t!("aborted"));
+                        return;
+                    }
+                    if let Err(err) = secrets::set(secret, &answer) {
+                        let message = t!("auth-failed", error = err.to_string());
+                        report_error(ErrorKind::Failed, &message, None);
+                        return;
+                    }
+                }
+                println!("{}", t!("auth-logged-in", service = name));
+                if secrets::CLEARED_ON_RESTART {
+                    println!("{}", t!("auth-cleared-on-restart", service = name));
+                }
+            }
+            ("logout", Some(logout_m)) => {
+                let name = logout_m.value_of("service").unwrap();
+                let login = secrets::Login::parse(name).unwrap();
+                let mut removed = false;
+                for (secret, _) in login.secrets() {
+                    match secrets::delete(secret) {
+                        Ok(found) => removed |= found,
+                        Err(err) => {
+                            let message = t!("auth-failed", error = err.to_string());
+                            report_error(ErrorKind::Failed, &message, None);
+                            return;
+                        }
+                    }
+                }
+                if removed {
+                    println!("{}", t!("auth-logged-out", service = name));
+                } else {
+                    println!("{}", t!("auth-not-logged-in", service = name));
+                }
+            }
+            _ => println!("{}", t!("choose-auth-command")),
+        },
+        ("sync", Some(sub_m)) => match sub_m.subcommand() {
+            ("conflicts", _) => {
+                let Ok(data) = fs::read(conflict::CONFLICT_FILE) else {
+                    println!("{}", t!("no-conflicts"));
+                    return;
+                };
+                if !io::stdin().is_terminal() {
+                    report_error(
+                        ErrorKind::InvalidArgument,
+                        &t!("conflicts-need-terminal"),
+                        None,
+                    );
+                    return;
+                }
+                let local =
+                    match store_key(&config).and_then(|key| json_store_tasks(data, key.as_ref())) {
+                        Ok(local) => local,
+                        Err(err) => {
+                            report_error(ErrorKind::Store, &err.to_string(), None);
+                            return;
+                        }
+                    };
+                let Some(merged) = conflict::merge(local, tasks, ask_side) else {
+                    println!("{}", t!("aborted"));
+                    return;
+                };
+                if save_or_report(&merged) {
+                    let _ = fs::remove_file(conflict::CONFLICT_FILE);
+                    println!("{}", t!("conflicts-resolved"));
+                }
+            }
+            ("flush", _) => {
+                if offline::pending().is_none() {
+                    println!("{}", t!("nothing-to-flush"));
+                    return;
+                }
+                let remote = match remote_store(&config) {
+                    Ok(Some(remote)) => remote,
+                    Ok(None) => {
+                        report_error(ErrorKind::Config, &t!("no-remote"), None);
+                        return;
+                    }
+                    Err(err) => {
+                        report_error(ErrorKind::Config, &err.to_string(), None);
+                        return;
+                    }
+                };
+                match offline::flush(|pending| remote.push(pending)) {
+                    Ok(Some(true)) => println!("{}", t!("flushed")),
+                    Ok(Some(false)) => {
+                        report_error(ErrorKind::Conflict, &t!("remote-changed"), None)
+                    }
+                    Ok(None) => println!("{}", t!("nothing-to-flush")),
+                    Err(err) => report_error(ErrorKind::Store, &err.to_string(), None),
+                }
+            }
+            _ => println!("{}", t!("choose-sync-command")),
+        },
+        ("key", Some(sub_m)) => match sub_m.subcommand() {
+            ("generate", _) => {
+                let path = config.encryption_key.as_deref().unwrap_or(DEFAULT_KEY_FILE);
+                match crypto::Key::generate().save(Path::new(path)) {
+                    Ok(()) => println!("{}", t!("key-generated", path = path)),
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                        report_error(ErrorKind::Conflict, &t!("key-exists", path = path), None)
+                    }
+                    Err(err) => report_error(ErrorKind::Io, &err.to_string(), None),
+                }
+            }
+            ("encrypt", _) => {
+                let key = match store_key(&config) {
+                    Ok(Some(key)) => key,
+                    Ok(None) => {
+                        report_error(ErrorKind::Config, &t!("no-encryption-key"), None);
+                        return;
+                    }
+                    Err(err) => {
+                        report_error(ErrorKind::Config, &err.to_string(), None);
+                        return;
+                    }
+                };
+                crypto::accept_plaintext();
+                let tasks = match load_tasks() {
+                    Ok(tasks) => tasks,
+                    Err(err) => {
+                        report_error(ErrorKind::Store, &err.to_string(), None);
+                        return;
+                    }
+                };
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                // The archive, the trash, the rejected records and the backups are kept like the store
+                let moved = repair::encrypt(&key)
+                    .and_then(|()| backup::encrypt(&config.backup, &key))
+                    .and_then(|()| retention::encrypt(&key));
+                match moved {
+                    Ok(moved) => {
+                        println!("{}", t!("store-encrypted-now", count = tasks.len() + moved))
+                    }
+                    Err(err) => report_error(ErrorKind::Io, &err.to_string(), None),
+                }
+            }
+            _ => println!("{}", t!("choose-key-command")),
+        },
+        ("convert", Some(sub_m)) => {
+            let (store, file) = match sub_m.value_of("store").unwrap() {
+                "binary" => (StoreKind::Binary, String::from(BINARY_STORE_FILE)),
+                "crdt" => (StoreKind::Crdt, String::from(crdt::STORE_FILE)),
+                "ndjson" => (StoreKind::Ndjson, String::from(NDJSON_STORE_FILE)),
+                _ => (StoreKind::Json, json_store_path(config.compression)),
+            };
+            // Load again, as a store that failed to load would otherwise be converted as empty
+            let result = load_tasks().and_then(|tasks| {
+                let key = store_key(&config)?;
+                save_tasks_to(store, config.compression, key.as_ref(), &tasks)?;
+                Ok(tasks.len())
+            });
+            match result {
+                Ok(count) => println!(
+                    "{}",
+                    t!(
+                        "store-converted",
+                        count = count,
+                        file = file,
+                        store = sub_m.value_of("store").unwrap()
+                    )
+                ),
+                Err(err) => report_error(ErrorKind::Store, &err.to_string(), None),
+            }
+        }
+        ("script", Some(sub_m)) => match sub_m.subcommand() {
+            ("run", Some(run_m)) => {
+                let result = fs::read_to_string(launch_dir.join(run_m.value_of("file").unwrap()))
+                    .map_err(Box::<dyn Error>::from)
+                    .and_then(|source| script::run(&source, &tasks, fmt.today()));
+                let mut result = match result {
+                    Ok(result) => result,
+                    Err(err) => {
+                        report_error(ErrorKind::Failed, &err.to_string(), None);
+                        return;
+                    }
+                };
+                for task in result.iter_mut() {
+                    let previous = tasks.iter().find(|t| t.title == task.title);
+                    if previous.is_none_or(|previous| previous.status != task.status) {
+                        let status = task.status.clone();
+                        set_status(task, &status);
+                    }
+                    if let Some(previous) = previous.filter(|previous| *previous != task) {
+                        task.revision = previous.revision + 1;
+                    }
+                }
+                unblock_ready_tasks(&mut result);
+                if result == tasks {
+                    return;
+                }
+
+                let is_in = |list: &[Task], task: &Task| list.iter().any(|t| t.title == task.title);
+                let added = result.iter().filter(|t| !is_in(&tasks, t)).count();
+                let removed = tasks.iter().filter(|t| !is_in(&result, t)).count();
+                let changed = result
+                    .iter()
+                    .filter(|t| tasks.iter().any(|old| old.title == t.title && old != *t))
+                    .count();
+                println!(
+                    "{}",
+                    t!(
+                        "script-changes",
+                        added = added,
+                        changed = changed,
+                        removed = removed
+                    )
+                );
+                if confirm(&t!("script-confirm"), run_m.is_present("yes")) {
+                    if !backup_before_change(&config) || !save_or_report(&result) {
+                        return;
+                    }
+                    println!("{}", t!("script-saved"));
+                } else {
+                    println!("{}", t!("aborted"));
+                }
+            }
+            _ => println!("{}", t!("choose-script-command")),
+        },
+        ("stats", Some(sub_m)) => match sub_m.subcommand() {
+            ("heatmap", _) => print!("{}", completion_heatmap(&tasks, &fmt, fmt.today())),
+            ("patterns", _) => {
+                let (weekdays, hours) = completion_patterns(&tasks, &fmt);
+                let weekdays: Vec<(String, usize)> =
+                    std::iter::successors(Some(fmt.week_start()), |day| Some(day.succ()))
+                        .take(7)
+                        .map(|day| {
+                            let label = t!("weekday-short", day = day.to_string().to_lowercase());
+                            (label, weekdays[day.num_days_from_monday() as usize])
+                        })
+                        .collect();
+                let hours: Vec<(String, usize)> = hours
+                    .iter()
+                    .enumerate()
+                    .map(|(hour, count)| (format!("{:02}", hour), *count))
+                    .collect();
+                println!("{}", t!("patterns-weekdays"));
+                print!("{}", bar_chart(&weekdays, 30, &fmt));
+                println!("\n{}", t!("patterns-hours"));
+                print!("{}", bar_chart(&hours, 30, &fmt));
+            }
+            _ => println!("{}", t!("choose-statistic")),
+        },
+        ("rebalance", Some(sub_m)) => {
+            let project = sub_m.value_of("project").unwrap();
+            let max_priority = match sub_m.value_of("max").unwrap().parse::<u8>() {
+                Ok(max) if max > 0 => max,
+                _ => {
+                    report_error(
+                        ErrorKind::InvalidArgument,
+                        &t!("invalid-max-priority"),
+                        None,
+                    );
+                    return;
+                }
+            };
+
+            let changes = rebalance_priorities(&mut tasks, project, max_priority);
+            if changes.is_empty() {
+                println!("{}", t!("priorities-balanced"));
+                return;
+            }
+            for change in &changes {
+                println!("{}", change);
+            }
+            if sub_m.is_present("dry-run") {
+                return;
+            }
+            if confirm(&t!("rebalance-confirm"), sub_m.is_present("yes")) {
+                if !backup_before_change(&config) {
+                    return;
+                }
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                println!("{}", t!("priorities-rebalanced"));
+            } else {
+                println!("{}", t!("aborted"));
+            }
+        }
+        ("rank", Some(sub_m)) => {
+            let project = sub_m.value_of("project").unwrap();
+            let max_priority = match sub_m.value_of("max").unwrap().parse::<u8>() {
+                Ok(max) if max > 0 => max,
+                _ => {
+                    report_error(
+                        ErrorKind::InvalidArgument,
+                        &t!("invalid-max-priority"),
+                        None,
+                    );
+                    return;
+                }
+            };
+            if !io::stdin().is_terminal() {
+                report_error(ErrorKind::InvalidArgument, &t!("rank-needs-terminal"), None);
+                return;
+            }
+            let open = ranked_open_tasks(&tasks, project);
+            if open.len() < 2 {
+                println!("{}", t!("rank-nothing", project = project));
+                return;
+            }
+            let Some(ranked) = rank_tasks(&tasks, &open, ask_preference) else {
+                println!("{}", t!("aborted"));
+                return;
+            };
+            let changes = assign_priorities(&mut tasks, &ranked, max_priority);
+            println!("{}", t!("rank-result"));
+            for (position, &index) in ranked.iter().enumerate() {
+                println!(
+                    "{:>3}. [{}] {}",
+                    position + 1,
+                    tasks[index].priority,
+                    tasks[index].title
+                );
+            }
+            if changes.is_empty() {
+                println!("{}", t!("priorities-balanced"));
+                return;
+            }
+            if confirm(&t!("rebalance-confirm"), sub_m.is_present("yes")) {
+                if !backup_before_change(&config) {
+                    return;
+                }
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                println!("{}", t!("priorities-rebalanced"));
+            } else {
+                println!("{}", t!("aborted"));
+            }
+        }
+        ("check", _) => {
+            let findings = check::check_tasks(&tasks, &config.check, Utc::now());
+            if findings.is_empty() {
+                println!("{}", t!("check-clean"));
+                return;
+            }
+            for finding in &findings {
+                let severity = match finding.severity {
+                    Severity::Error => t!("severity-error"),
+                    Severity::Warning => t!("severity-warning"),
+                    _ => t!("severity-info"),
+                };
+                println!(
+                    "{}[{}] {}: {}",
+                    severity,
+                    finding.rule.name(),
+                    finding.title,
+                    finding.message
+                );
+            }
+            if findings.iter().any(|f| f.severity == Severity::Error) {
+                timings.exit(1);
+            }
+        }
+        ("repair", Some(sub_m)) => {
+            let skipped = repair::take_skipped();
+            if skipped.is_empty() {
+                println!("{}", t!("store-valid"));
+                return;
+            }
+            if sub_m.is_present("check") {
+                timings.exit(1);
+            }
+            let rejected =
+                store_key(&config).and_then(|key| repair::reject(&skipped, key.as_ref()));
+            if let Err(err) = rejected {
+                report_error(ErrorKind::Io, &err.to_string(), None);
+                return;
+            }
+            if save_or_report(&tasks) {
+                println!(
+                    "{}",
+                    t!(
+                        "store-repaired",
+                        count = skipped.len(),
+                        file = repair::REJECTED_FILE
+                    )
+                );
+            }
+        }
+        ("digest", Some(sub_m)) => {
+            let today = fmt.today();
+            let body = digest::render(&digest::collect(&tasks, &fmt, today), &fmt, today);
+            if sub_m.is_present("print") {
+                print!("{}", body);
+                return;
+            }
+            let subject = t!("digest-subject", date = fmt.date(today));
+            match digest::send(&config.email, &subject, &body) {
+                Ok(()) => println!("{}", t!("digest-sent", count = config.email.to.len())),
+                Err(err) => {
+                    let message = t!("digest-failed", error = err.to_string());
+                    report_error(ErrorKind::Failed, &message, None);
+                }
+            }
+        }
+        ("tick", _) => {
+            let today = fmt.today();
+            let mut changes = apply_escalation_rules(&mut tasks, &config.escalation, today);
+            changes.extend(reminder::give(&mut tasks, Utc::now(), &fmt));
+            let key = match store_key(&config) {
+                Ok(key) => key,
+                Err(err) => {
+                    report_error(ErrorKind::Config, &err.to_string(), None);
+                    return;
                 }
+            };
+            let now = Utc::now();
+            let removed = match retention::apply(&mut tasks, &config.retention, now, key.as_ref()) {
+                Ok((moved, removed)) => {
+                    changes.extend(moved);
+                    removed
+                }
+                Err(err) => {
+                    report_error(ErrorKind::Store, &err.to_string(), None);
+                    return;
+                }
+            };
+            if changes.is_empty() {
+                println!("{}", t!("no-escalations"));
             } else {
-                println!("Please provide a priority with the --priority option");
+                if !save_or_report(&tasks) {
+                    return;
+                }
+                if let Err(err) = removed.save(key.as_ref()) {
+                    report_error(ErrorKind::Io, &err.to_string(), None);
+                    return;
+                }
+                for change in changes {
+                    println!("{}", change);
+                }
             }
         }
-        ("search", Some(sub_m)) => {
-            let query = sub_m.value_of("query").unwrap().to_lowercase();
-            let filtered_tasks: Vec<&Task> = tasks
-                .iter()
-                .filter(|task| {
-                    task.title.to_lowercase().contains(&query)
-                        || task.description.to_lowercase().contains(&query)
-                })
-                .collect();
-
-            for (index, task) in filtered_tasks.iter().enumerate() {
-                println!("Task {}: {:#?}", index + 1, task);
+        ("plugins", _) => {
+            for name in plugin::discover() {
+                println!("{}", name);
             }
         }
-
-        ("update", Some(sub_m)) => {
-            if let Err(err) = update_task(sub_m, &mut tasks) {
-                println!("Error: {}", err);
-            } else {
-                println!("Task updated successfully!");
+        (name, Some(sub_m)) => {
+            let args: Vec<&str> = sub_m
+                .values_of("")
+                .map(|args| args.collect())
+                .unwrap_or_default();
+            let (store, store_kind) = match config.store {
+                StoreKind::Json => (json_store_path(config.compression), "json"),
+                StoreKind::Crdt => (crdt::STORE_FILE.to_string(), "crdt"),
+                StoreKind::Binary => (BINARY_STORE_FILE.to_string(), "binary"),
+                StoreKind::Ndjson => (NDJSON_STORE_FILE.to_string(), "ndjson"),
+            };
+            let dir = env::current_dir().unwrap_or_default();
+            let flag = |name| String::from(if matches.is_present(name) { "1" } else { "0" });
+            let mut vars = vec![
+                ("TASK_MANAGER_STORE_KIND", store_kind.to_string()),
+                (
+                    "TASK_MANAGER_CONFIG",
+                    dir.join("config.json").display().to_string(),
+                ),
+                ("TASK_MANAGER_YES", flag("yes")),
+                ("TASK_MANAGER_JSON", flag("json")),
+                ("TASK_MANAGER_ASCII", flag("ascii")),
+                ("TASK_MANAGER_TIMINGS", flag("timings")),
+            ];
+            // A remote store is not kept in the data directory
+            if config.remote.is_none() {
+                vars.push(("TASK_MANAGER_STORE", dir.join(store).display().to_string()));
+            }
+            match plugin::run(name, &args, &vars, &launch_dir) {
+                Ok(Some(status)) => timings.exit(status.code().unwrap_or(1)),
+                Ok(None) => {
+                    let message = t!("unknown-command", name = name);
+                    report_error(ErrorKind::InvalidArgument, &message, None);
+                }
+                Err(err) => report_error(ErrorKind::Plugin, &err.to_string(), None),
             }
         }
-        _ => println!("Invalid command"),
+        _ => report_error(ErrorKind::InvalidArgument, &t!("invalid-command"), None),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     use std::fs;
 
     #[test]
@@ -331,6 +5267,7 @@ mod tests {
                 priority: 1,
                 status: String::from("Todo"),
                 project: String::from("Project"),
+                ..Default::default()
             },
             Task {
                 title: String::from("Task 2"),
@@ -338,6 +5275,7 @@ mod tests {
                 priority: 2,
                 status: String::from("In Progress"),
                 project: String::from("Project"),
+                ..Default::default()
             },
         ];
 
@@ -354,6 +5292,67 @@ mod tests {
         fs::remove_file("tasks.json").unwrap();
     }
 
+    #[test]
+    fn test_ndjson_round_trip() {
+        let tasks = vec![
+            Task {
+                title: String::from("Task 1"),
+                description: String::from("Line one\nline two"),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Task 2"),
+                tags: vec![String::from("work")],
+                ..Default::default()
+            },
+        ];
+        let mut serialized = Vec::new();
+        write_ndjson(&mut serialized, &tasks).unwrap();
+        assert_eq!(serialized.iter().filter(|&&b| b == b'\n').count(), 2);
+
+        let stream = read_ndjson(io::Cursor::new(serialized.clone()));
+        let loaded: Vec<Task> = stream.collect::<Result<_, _>>().unwrap();
+        assert_eq!(loaded, tasks);
+
+        // Lines that are not valid tasks are skipped, and the store is then not saved over
+        let broken = read_ndjson(io::Cursor::new([&b"{oops\n"[..], &serialized].concat()));
+        let loaded: Vec<Task> = broken.collect::<Result<_, _>>().unwrap();
+        assert_eq!(loaded, tasks);
+        assert!(save_tasks(&loaded).is_err());
+        assert_eq!(repair::take_skipped()[0].line, 1);
+    }
+
+    #[test]
+    fn test_unknown_fields_round_trip() {
+        let json = r#"{"title":"Task 1","description":"","priority":1,"status":"Todo","project":"Work","reminder":{"at":"09:00","repeat":true},"color":"blue"}"#;
+        let task: Task = serde_json::from_str(json).unwrap();
+        assert_eq!(task.extra["color"], "blue");
+        assert_eq!(task.extra["reminder"]["repeat"], true);
+        let saved: serde_json::Value = serde_json::to_value(&task).unwrap();
+        assert_eq!(
+            saved,
+            serde_json::from_str::<serde_json::Value>(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_binary_store_round_trip() {
+        let tasks = vec![Task {
+            title: String::from("Task 1"),
+            priority: 2,
+            due: NaiveDate::from_ymd_opt(2024, 7, 1),
+            tags: vec![String::from("work")],
+            estimate: Some(1.5),
+            extra: serde_json::Map::from_iter([(String::from("color"), "blue".into())]),
+            ..Default::default()
+        }];
+        let serialized = rmp_serde::to_vec_named(&tasks).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<Vec<Task>>(&serialized).unwrap(),
+            tasks
+        );
+    }
+
     #[test]
     fn test_update_task() {
         let mut tasks = vec![
@@ -363,6 +5362,7 @@ mod tests {
                 priority: 1,
                 status: String::from("Todo"),
                 project: String::from("Project"),
+                ..Default::default()
             },
             Task {
                 title: String::from("Task 2"),
@@ -370,6 +5370,7 @@ mod tests {
                 priority: 2,
                 status: String::from("In Progress"),
                 project: String::from("Project"),
+                ..Default::default()
             },
         ];
 
@@ -400,14 +5401,17 @@ mod tests {
 
         // Perform the update
         update_task(
-            &update_matches.subcommand_matches("update").unwrap(),
+            "Task 1",
+            update_matches.subcommand_matches("update").unwrap(),
             &mut tasks,
+            &Formatter::default(),
         )
         .unwrap();
 
         // Check if the task was updated successfully
         let updated_task = tasks.iter().find(|t| t.title == "Task 1").unwrap();
         assert_eq!(updated_task.description, "Updated Description");
+        assert_eq!(updated_task.revision, 1);
     }
 
     #[test]
@@ -418,6 +5422,7 @@ mod tests {
             priority: 1,
             status: String::from("Todo"),
             project: String::from("Project"),
+            ..Default::default()
         }];
 
         // Create ArgMatches for the add command
@@ -480,6 +5485,7 @@ mod tests {
                     priority,
                     status: status.to_string(),
                     project: project.to_string(),
+                    ..Default::default()
                 };
 
                 tasks.push(new_task);
@@ -505,6 +5511,7 @@ mod tests {
                 priority: 1,
                 status: String::from("Todo"),
                 project: String::from("Project"),
+                ..Default::default()
             },
             Task {
                 title: String::from("Task 2"),
@@ -512,6 +5519,7 @@ mod tests {
                 priority: 2,
                 status: String::from("In Progress"),
                 project: String::from("Project"),
+                ..Default::default()
             },
         ];
 
@@ -540,4 +5548,805 @@ mod tests {
         // Check if the task was removed successfully
         assert!(!tasks.iter().any(|t| t.title == "Task 1"));
     }
+
+    #[test]
+    fn test_apply_escalation_rules() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let mut tasks = vec![
+            Task {
+                title: String::from("Due soon"),
+                priority: 2,
+                status: String::from("Todo"),
+                due: NaiveDate::from_ymd_opt(2024, 7, 3),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Due later"),
+                priority: 2,
+                status: String::from("Todo"),
+                due: NaiveDate::from_ymd_opt(2024, 7, 10),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Already done"),
+                priority: 2,
+                status: String::from("Done"),
+                due: NaiveDate::from_ymd_opt(2024, 7, 2),
+                ..Default::default()
+            },
+        ];
+        let rules = vec![EscalationRule {
+            name: String::from("due-soon"),
+            within_days: 2,
+            raise_priority: 1,
+            add_tag: Some(String::from("urgent")),
+        }];
+
+        let changes = apply_escalation_rules(&mut tasks, &rules, today);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(tasks[0].priority, 3);
+        assert_eq!(tasks[0].tags, vec!["urgent"]);
+        assert_eq!(tasks[1].priority, 2);
+        assert_eq!(tasks[2].priority, 2);
+
+        // Running the rules again must not escalate the same task twice
+        assert!(apply_escalation_rules(&mut tasks, &rules, today).is_empty());
+        assert_eq!(tasks[0].priority, 3);
+    }
+
+    #[test]
+    fn test_unblock_ready_tasks() {
+        let mut tasks = vec![
+            Task {
+                title: String::from("Design"),
+                status: String::from("Done"),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Review"),
+                status: String::from("Todo"),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Build"),
+                status: String::from("Blocked"),
+                depends_on: vec![String::from("Design")],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Ship"),
+                status: String::from("Blocked"),
+                depends_on: vec![String::from("Build"), String::from("Review")],
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(unblock_ready_tasks(&mut tasks), vec!["Build"]);
+        assert_eq!(tasks[2].status, "Todo");
+        assert!(tasks[2].newly_unblocked);
+        assert_eq!(tasks[3].status, "Blocked");
+        assert!(depends_on_transitively(&tasks, "Ship", "Design"));
+        assert!(!depends_on_transitively(&tasks, "Design", "Ship"));
+
+        // Finishing the last dependency through `done` unblocks the task too
+        assert_eq!(mark_done(&mut tasks, 1), Vec::<String>::new());
+        assert_eq!(tasks[1].status, "Done");
+        assert!(tasks[1].completed_at.is_some());
+        assert_eq!(mark_done(&mut tasks, 2), vec!["Ship"]);
+        assert_eq!(tasks[3].status, "Todo");
+    }
+
+    #[test]
+    fn test_rebalance_priorities() {
+        let mut tasks: Vec<Task> = (1..=6)
+            .map(|i| Task {
+                title: format!("Task {}", i),
+                priority: 5,
+                status: String::from("Todo"),
+                project: String::from("Work"),
+                due: NaiveDate::from_ymd_opt(2024, 7, i),
+                ..Default::default()
+            })
+            .collect();
+        tasks[5].priority = 4;
+        tasks.push(Task {
+            title: String::from("Other project"),
+            priority: 5,
+            project: String::from("Home"),
+            ..Default::default()
+        });
+
+        rebalance_priorities(&mut tasks, "Work", 3);
+
+        let priorities: Vec<u8> = tasks.iter().map(|t| t.priority).collect();
+        assert_eq!(priorities, vec![3, 3, 2, 2, 1, 1, 5]);
+        assert!(rebalance_priorities(&mut tasks, "Work", 3).is_empty());
+    }
+
+    #[test]
+    fn test_rank_tasks() {
+        let mut tasks: Vec<Task> = ["Taxes", "Dentist", "Groceries", "Tires"]
+            .iter()
+            .map(|title| Task {
+                title: title.to_string(),
+                priority: 3,
+                project: String::from("Home"),
+                ..Default::default()
+            })
+            .collect();
+        let order = ["Tires", "Taxes", "Groceries", "Dentist"];
+        let place = |task: &Task| order.iter().position(|title| *title == task.title);
+        let mut asked = 0;
+        let ranked = rank_tasks(&tasks, &[0, 1, 2, 3], |a, b| {
+            asked += 1;
+            Some(place(a) < place(b))
+        })
+        .unwrap();
+        assert_eq!(ranked, [3, 0, 2, 1]);
+        assert!(asked < 6);
+
+        assign_priorities(&mut tasks, &ranked, 4);
+        let priorities: Vec<u8> = tasks.iter().map(|t| t.priority).collect();
+        assert_eq!(priorities, [3, 1, 2, 4]);
+        assert_eq!(rank_tasks(&tasks, &[0, 1], |_, _| None), None);
+    }
+
+    #[test]
+    fn test_estimate_errors_by_project() {
+        let entry = |hours| TimeEntry {
+            date: NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+            hours,
+        };
+        let tasks = vec![
+            Task {
+                title: String::from("Overran"),
+                status: String::from("Done"),
+                project: String::from("Work"),
+                estimate: Some(2.0),
+                time_entries: vec![entry(2.0), entry(1.0)],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Still open"),
+                status: String::from("In Progress"),
+                project: String::from("Work"),
+                estimate: Some(2.0),
+                time_entries: vec![entry(5.0)],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Quick"),
+                status: String::from("Done"),
+                project: String::from("Home"),
+                estimate: Some(4.0),
+                time_entries: vec![entry(3.0)],
+                ..Default::default()
+            },
+        ];
+
+        let errors = estimate_errors_by_project(&tasks);
+        assert_eq!(
+            errors,
+            vec![
+                (String::from("Home"), vec![-0.25]),
+                (String::from("Work"), vec![0.5])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_completion_heatmap() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 3).unwrap(); // a Wednesday
+        let completed = |day: NaiveDate| day.and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let tasks = vec![
+            Task {
+                status: String::from("Done"),
+                completed_at: Some(completed(today)),
+                ..Default::default()
+            },
+            Task {
+                status: String::from("Done"),
+                completed_at: Some(completed(today)),
+                ..Default::default()
+            },
+            Task {
+                status: String::from("Done"),
+                completed_at: Some(completed(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap())),
+                ..Default::default()
+            },
+        ];
+
+        let fmt = Formatter::new(&DisplayConfig {
+            locale: Some(String::from("en-GB")),
+            timezone: Some(String::from("UTC")),
+            week_start: None,
+            ascii: Some(false),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let heatmap = completion_heatmap(&tasks, &fmt, today);
+        let rows: Vec<&str> = heatmap.lines().collect();
+        assert!(rows[1].starts_with("Mon "));
+        assert_eq!(rows[1].chars().count(), 4 + 53);
+        assert!(rows[3].ends_with('▒'));
+        // Days after today are left blank
+        assert_eq!(rows[4].chars().count(), 4 + 52);
+        assert!(heatmap.contains("2 tasks completed in the last year"));
+    }
+
+    #[test]
+    fn test_planned_tasks() {
+        let tasks = vec![
+            Task {
+                title: String::from("Task 1"),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Task 2"),
+                ..Default::default()
+            },
+        ];
+        let plan = vec![
+            String::from("Task 2"),
+            String::from("Removed task"),
+            String::from("Task 1"),
+        ];
+
+        let planned: Vec<&str> = planned_tasks(&tasks, &plan)
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect();
+        assert_eq!(planned, vec!["Task 2", "Task 1"]);
+    }
+
+    #[test]
+    fn test_urgency_and_actionable_tasks() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let tasks = vec![
+            Task {
+                title: String::from("Overdue"),
+                priority: 1,
+                status: String::from("Todo"),
+                due: NaiveDate::from_ymd_opt(2024, 6, 30),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Due in a week"),
+                priority: 1,
+                status: String::from("Todo"),
+                due: NaiveDate::from_ymd_opt(2024, 7, 8),
+                depends_on: vec![String::from("Overdue")],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Done"),
+                priority: 5,
+                status: String::from("Done"),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(urgency(&tasks[0], today), 11.0);
+        assert_eq!(urgency(&tasks[1], today), 4.5);
+        assert_eq!(urgency(&tasks[2], today), 5.0);
+
+        let actionable: Vec<&str> = actionable_tasks(&tasks)
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect();
+        assert_eq!(actionable, vec!["Overdue"]);
+    }
+
+    #[test]
+    fn test_waiting_groups() {
+        let blocked = |title: &str, waiting_on: Option<&str>, days_ago| Task {
+            title: String::from(title),
+            status: String::from("Blocked"),
+            waiting_on: waiting_on.map(String::from),
+            blocked_at: Some(Utc::now() - Duration::days(days_ago)),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            blocked("Newer", Some("Alice"), 1),
+            blocked("Older", Some("Alice"), 10),
+            blocked("Contract", Some("Legal review"), 3),
+            blocked("No reason", None, 2),
+        ];
+        tasks.push(Task {
+            title: String::from("Not blocked"),
+            status: String::from("Todo"),
+            waiting_on: Some(String::from("Alice")),
+            ..Default::default()
+        });
+
+        let groups: Vec<(String, Vec<&str>)> = waiting_groups(&tasks)
+            .into_iter()
+            .map(|(reason, group)| (reason, group.iter().map(|t| t.title.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (String::from("(no reason given)"), vec!["No reason"]),
+                (String::from("Alice"), vec!["Older", "Newer"]),
+                (String::from("Legal review"), vec!["Contract"]),
+            ]
+        );
+
+        // Leaving the blocked status forgets the reason
+        set_status(&mut tasks[0], "In Progress");
+        assert_eq!(tasks[0].waiting_on, None);
+        assert_eq!(tasks[0].blocked_at, None);
+    }
+
+    #[test]
+    fn test_cascade_due_dates() {
+        let day = |day| NaiveDate::from_ymd_opt(2024, 7, day).unwrap();
+        let task = |title: &str, status: &str, due: Option<u32>, depends_on: &[&str]| Task {
+            title: String::from(title),
+            status: String::from(status),
+            due: due.map(day),
+            depends_on: depends_on.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("Design", "Todo", Some(1), &[]),
+            task("Build", "Todo", Some(5), &["Design"]),
+            task("Test", "Todo", Some(8), &["Build"]),
+            task("Docs", "Done", Some(6), &["Design"]),
+            task("Launch", "Todo", None, &["Test"]),
+            task("Unrelated", "Todo", Some(9), &[]),
+        ];
+        assert_eq!(
+            cascade_due_dates(&tasks, "Design", Duration::days(3)),
+            [(1, day(8)), (2, day(11))]
+        );
+        assert!(cascade_due_dates(&tasks, "Test", Duration::days(3)).is_empty());
+
+        // Times due keep their time of day when the clocks change in between
+        let fmt = Formatter::new(&DisplayConfig {
+            timezone: Some(String::from("Europe/Berlin")),
+            ..Default::default()
+        })
+        .unwrap();
+        let at = |day, hour| Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap();
+        assert_eq!(shift_due_at(at(30, 8), Duration::days(1), &fmt), at(31, 7));
+        assert_eq!(shift_due_at(at(31, 7), Duration::days(-1), &fmt), at(30, 8));
+        // 02:00 on March 31 does not exist there
+        assert_eq!(shift_due_at(at(30, 1), Duration::days(1), &fmt), at(31, 1));
+    }
+
+    #[test]
+    fn test_board_columns() {
+        let task = |title: &str, project: &str, status: &str| Task {
+            title: String::from(title),
+            project: String::from(project),
+            status: String::from(status),
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("Shipped", "Website", "done"),
+            task("Header", "Website", "In Progress"),
+            task("Review", "Website", "In Review"),
+            task("Footer", "Website", "todo"),
+            task("Backups", "Ops", "Todo"),
+        ];
+        let wip: WipConfig = serde_json::from_str(r#"{ "statuses": { "Blocked": 2 } }"#).unwrap();
+        let columns = board_columns(&tasks, Some("Website"), &wip);
+        let summary: Vec<(&str, usize)> = columns
+            .iter()
+            .map(|(status, column)| (status.as_str(), column.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Todo", 1),
+                ("In Progress", 1),
+                ("Blocked", 0),
+                ("In Review", 1),
+                ("done", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_move_in_column() {
+        let task = |title: &str, status: &str| Task {
+            title: String::from(title),
+            status: String::from(status),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("First", "Todo"),
+            task("Started", "In Progress"),
+            task("Second", "Todo"),
+            task("Third", "todo"),
+        ];
+        let column = |tasks: &[Task]| -> Vec<String> {
+            let columns = board_columns(tasks, None, &WipConfig::default());
+            columns[0].1.iter().map(|task| task.title.clone()).collect()
+        };
+        assert!(move_in_column(&mut tasks, 3, true, None));
+        assert_eq!(column(&tasks), ["First", "Third", "Second"]);
+        // Every task that changed places is touched, and only those
+        let revisions: Vec<u64> = tasks.iter().map(|task| task.revision).collect();
+        assert_eq!(revisions, [1, 0, 1, 1]);
+        assert!(move_in_column(&mut tasks, 0, false, None));
+        assert_eq!(column(&tasks), ["Third", "First", "Second"]);
+        let revisions: Vec<u64> = tasks.iter().map(|task| task.revision).collect();
+        assert_eq!(revisions, [2, 0, 1, 2]);
+        assert!(!move_in_column(&mut tasks, 3, true, None));
+        assert!(!move_in_column(&mut tasks, 1, false, None));
+
+        // On the board of a project, tasks of other projects are passed over
+        tasks[0].project = String::from("Home");
+        tasks[2].project = String::from("Home");
+        assert!(!move_in_column(&mut tasks, 2, false, Some("Home")));
+        assert!(move_in_column(&mut tasks, 2, true, Some("Home")));
+        assert_eq!(column(&tasks), ["Third", "Second", "First"]);
+        assert!(!move_in_column(&mut tasks, 2, true, Some("Home")));
+
+        // Changing status leaves the place behind
+        set_status(&mut tasks[3], "In Progress");
+        assert_eq!(tasks[3].order, None);
+        assert_eq!(column(&tasks), ["Second", "First"]);
+    }
+
+    #[test]
+    fn test_priority_matrix() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let task = |title: &str, priority: u8, due_in: Option<i64>| Task {
+            title: String::from(title),
+            priority,
+            status: String::from("Todo"),
+            due: due_in.map(|days| today + Duration::days(days)),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Outage", 5, Some(0)),
+            task("Overdue report", 3, Some(-2)),
+            task("Roadmap", 4, Some(30)),
+            task("Form", 1, Some(1)),
+            task("Tidy desk", 1, None),
+            task("Shipped", 5, Some(0)),
+        ];
+        tasks[5].status = String::from("Done");
+
+        let quadrants = priority_matrix(&tasks, None, today, &MatrixConfig::default());
+        let titles: Vec<Vec<&str>> = quadrants
+            .iter()
+            .map(|group| group.iter().map(|task| task.title.as_str()).collect())
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                vec!["Overdue report", "Outage"],
+                vec!["Roadmap"],
+                vec!["Form"],
+                vec!["Tidy desk"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stale_groups() {
+        let now = Utc::now();
+        let task = |title: &str, project: &str, status: &str, days_ago: i64| Task {
+            title: String::from(title),
+            project: String::from(project),
+            status: String::from(status),
+            created_at: Some(now - Duration::days(days_ago)),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Fresh", "Website", "Todo", 3),
+            task("Old", "Website", "Todo", 20),
+            task("Older", "Website", "Todo", 40),
+            task("Finished", "Website", "Done", 40),
+            task("Logged", "Ops", "In Progress", 40),
+            task("Forgotten", "Ops", "Todo", 30),
+            Task {
+                title: String::from("Legacy"),
+                status: String::from("Todo"),
+                ..Default::default()
+            },
+        ];
+        tasks[4].time_entries.push(TimeEntry {
+            date: (now - Duration::days(1)).date_naive(),
+            hours: 1.0,
+        });
+
+        let groups = stale_groups(&tasks, now, 14);
+        let titles: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(project, group)| (*project, group.iter().map(|t| t.title.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("Ops", vec!["Forgotten"]),
+                ("Website", vec!["Older", "Old"])
+            ]
+        );
+        assert!(!is_stale(&tasks[1], now, 30));
+    }
+
+    #[test]
+    fn test_completion_patterns() {
+        let fmt = Formatter::new(&DisplayConfig {
+            timezone: Some(String::from("Europe/Berlin")),
+            ascii: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let completed = |at: &str| Task {
+            completed_at: Some(at.parse().unwrap()),
+            ..Default::default()
+        };
+        // Late on a Sunday in UTC is early on Monday in Berlin
+        let tasks = [
+            completed("2024-07-07T22:30:00Z"),
+            completed("2024-07-08T07:10:00Z"),
+            completed("2024-07-10T13:00:00Z"),
+            Task::default(),
+        ];
+        let (weekdays, hours) = completion_patterns(&tasks, &fmt);
+        assert_eq!(weekdays, [2, 0, 1, 0, 0, 0, 0]);
+        assert_eq!((hours[0], hours[9], hours[15]), (1, 1, 1));
+
+        let rows = [(String::from("Mon"), 4), (String::from("Tue"), 1)];
+        assert_eq!(bar_chart(&rows, 8, &fmt), "Mon ######## 4\nTue ## 1\n");
+    }
+
+    #[test]
+    fn test_task_url() {
+        let mut task = Task {
+            description: String::from("Review (https://github.com/org/repo/pull/42), then merge."),
+            ..Default::default()
+        };
+        assert_eq!(task_url(&task), Some("https://github.com/org/repo/pull/42"));
+        task.url = Some(String::from("https://example.com/spec"));
+        assert_eq!(task_url(&task), Some("https://example.com/spec"));
+        task.url = None;
+        task.description = String::from("See https:// or www.example.com");
+        assert_eq!(task_url(&task), None);
+        assert!(is_url("mailto:team@example.com"));
+    }
+
+    #[test]
+    fn test_upsert_task() {
+        let imported = |title: &str, status: &str| Task {
+            title: String::from(title),
+            status: String::from(status),
+            external_id: Some(String::from("JIRA-123")),
+            ..Default::default()
+        };
+        let mut tasks = vec![Task {
+            title: String::from("Release"),
+            depends_on: vec![String::from("Fix login")],
+            ..Default::default()
+        }];
+        assert!(!upsert_task(&mut tasks, imported("Fix login", "Todo")));
+        tasks[1].tags = vec![String::from("auth")];
+        tasks[1].time_entries.push(TimeEntry {
+            date: NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(),
+            hours: 2.0,
+        });
+
+        assert!(upsert_task(&mut tasks, imported("Fix SSO login", "Done")));
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1].title, "Fix SSO login");
+        assert!(tasks[1].completed_at.is_some());
+        assert_eq!(tasks[1].tags, vec!["auth"]);
+        assert_eq!(tasks[1].time_entries.len(), 1);
+        assert_eq!(tasks[0].depends_on, vec!["Fix SSO login"]);
+
+        let mut other = imported("Other", "Todo");
+        other.external_id = Some(String::from("JIRA-124"));
+        assert!(!upsert_task(&mut tasks, other));
+        assert_eq!(tasks.len(), 3);
+    }
+
+    #[test]
+    fn test_delegate_and_follow_ups() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 10).unwrap();
+        let mut tasks = vec![
+            Task {
+                title: String::from("Later"),
+                status: String::from("Todo"),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Now"),
+                status: String::from("Todo"),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Finished"),
+                status: String::from("Done"),
+                follow_up: NaiveDate::from_ymd_opt(2024, 7, 1),
+                ..Default::default()
+            },
+        ];
+        delegate_task(
+            &mut tasks[0],
+            "Bob",
+            NaiveDate::from_ymd_opt(2024, 7, 11).unwrap(),
+        );
+        delegate_task(&mut tasks[1], "Alice", today);
+
+        assert_eq!(tasks[1].status, "Waiting");
+        assert_eq!(tasks[1].assignee.as_deref(), Some("Alice"));
+        assert!(actionable_tasks(&tasks).is_empty());
+
+        let due: Vec<&str> = due_follow_ups(&tasks, today)
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect();
+        assert_eq!(due, vec!["Now"]);
+    }
+
+    #[test]
+    fn test_only_json_store_encrypted() {
+        let config = Config {
+            store: StoreKind::Binary,
+            encryption_key: Some(String::from("tasks.key")),
+            ..Default::default()
+        };
+        assert!(store_key(&config).is_err());
+
+        // Converting to a store that cannot be encrypted writes nothing
+        let key = crypto::Key::generate();
+        for store in [StoreKind::Binary, StoreKind::Ndjson, StoreKind::Crdt] {
+            assert!(save_tasks_to(store, Compression::None, Some(&key), &[]).is_err());
+        }
+        assert!(!Path::new(BINARY_STORE_FILE).exists());
+        assert!(!Path::new(NDJSON_STORE_FILE).exists());
+    }
+
+    #[test]
+    fn test_confirm() {
+        assert!(confirm("Remove 3 tasks?", true));
+        assert!(is_yes("y\n"));
+        assert!(is_yes(" YES "));
+        assert!(!is_yes("n"));
+        assert!(!is_yes("\n"));
+        assert!(!is_yes("yes please"));
+    }
+
+    #[test]
+    fn test_triage_task() {
+        let fmt = Formatter::default();
+        let inbox = || Task {
+            title: String::from("Call plumber"),
+            project: String::from(INBOX_PROJECT),
+            status: String::from("Todo"),
+            priority: 1,
+            ..Default::default()
+        };
+
+        // An answer that cannot be read is asked again, and an empty one keeps the value
+        let mut task = inbox();
+        let mut input = &b"Home\nhigh\n3\n2024-07-01\n@phone, errands\n"[..];
+        assert!(triage_task(&mut task, &fmt, &mut input).is_ok());
+        assert_eq!(task.project, "Home");
+        assert_eq!(task.priority, 3);
+        assert_eq!(task.due, NaiveDate::from_ymd_opt(2024, 7, 1));
+        assert_eq!(task.contexts, ["phone", "errands"]);
+        let mut input = &b"\n\n\n\n"[..];
+        assert!(triage_task(&mut task, &fmt, &mut input).is_ok());
+        assert_eq!(task.project, "Home");
+        assert_eq!(task.contexts, ["phone", "errands"]);
+
+        // Nothing is changed when the task is skipped or triage stops before the last question
+        let mut task = inbox();
+        let mut input = &b"Home\n2\ns\n"[..];
+        assert!(matches!(
+            triage_task(&mut task, &fmt, &mut input),
+            Err(Stop::Skip)
+        ));
+        let mut input = &b"Home\nQ\n"[..];
+        assert!(matches!(
+            triage_task(&mut task, &fmt, &mut input),
+            Err(Stop::Quit)
+        ));
+        let mut input = &b"Home\n"[..];
+        assert!(matches!(
+            triage_task(&mut task, &fmt, &mut input),
+            Err(Stop::Quit)
+        ));
+        assert_eq!(task.project, INBOX_PROJECT);
+        assert_eq!(task.priority, 1);
+        assert!(task.updated_at.is_none());
+    }
+
+    #[test]
+    fn test_render_task_details() {
+        let fmt = Formatter::new(&DisplayConfig {
+            timezone: Some(String::from("UTC")),
+            ascii: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2024, 7, day, hour, 0, 0).unwrap();
+        let task = |title: &str, status: &str| Task {
+            title: title.to_string(),
+            project: String::from("Home"),
+            status: status.to_string(),
+            priority: 2,
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Fix roof", "In Progress"),
+            task("Buy shingles", "Done"),
+            task("Paint fence", "Todo"),
+        ];
+        tasks[0].depends_on = vec![String::from("Buy shingles"), String::from("Hire roofer")];
+        tasks[0].created_at = Some(at(1, 9));
+        tasks[0].started_at = Some(at(2, 10));
+        tasks[0].updated_at = Some(at(2, 10));
+        tasks[0].revision = 3;
+        tasks[0].time_entries = vec![TimeEntry {
+            date: NaiveDate::from_ymd_opt(2024, 7, 2).unwrap(),
+            hours: 1.5,
+        }];
+        tasks[2].depends_on = vec![String::from("Fix roof")];
+
+        let text = render_task_details(&tasks[0], &tasks, &fmt);
+        assert!(text.starts_with("Fix roof\n    Slug: fix-roof (revision 3)\n"));
+        assert!(text.contains("\n    Status: [~] In Progress\n"));
+        assert!(text.contains("\nTime entries\n    07/02/2024  1.5 h\n"));
+        // The history is in order, with the last change at the end
+        assert!(text.contains(
+            "\nHistory\n    07/01/2024 09:00 AM  Added\n    07/02/2024 10:00 AM  Started\n    \
+             07/02/2024 10:00 AM  Last changed\n"
+        ));
+        assert!(text.ends_with(
+            "\nDependencies\n    [x] Done Buy shingles\n    [?] Hire roofer\n    `- Fix roof\n       \
+             `- [ ] Todo Paint fence\n"
+        ));
+
+        // A task on its own has no time entries, history or dependencies to show
+        let text = render_task_details(&tasks[1], &[], &fmt);
+        assert_eq!(text.lines().count(), 6);
+    }
+
+    #[test]
+    fn test_timings() {
+        let mut timings = Timings::new();
+        timings.lap("timing-startup");
+        assert!(timings.report().is_empty());
+
+        timings.enabled = true;
+        timings.lap("timing-load");
+        let report = timings.report();
+        assert_eq!(report.len(), 4);
+        assert_eq!(report[0], "Timings:");
+        assert!(report[2].starts_with("  Loading tasks: "));
+        assert!(report[3].ends_with(" ms"));
+        // The report is given once, whether the command exits or the timings are dropped
+        assert!(timings.report().is_empty());
+    }
+
+    #[test]
+    fn test_error_json() {
+        assert_eq!(
+            error_json(ErrorKind::NotFound, "Task not found", Some("Taxes")),
+            serde_json::json!({ "error": "NotFound", "message": "Task not found", "title": "Taxes" })
+        );
+        assert_eq!(
+            error_json(ErrorKind::Config, "Invalid config", None),
+            serde_json::json!({ "error": "Config", "message": "Invalid config" })
+        );
+        assert_eq!(ErrorKind::of("goal-not-found"), ErrorKind::NotFound);
+        assert_eq!(ErrorKind::of("dependency-cycle"), ErrorKind::Conflict);
+        assert_eq!(ErrorKind::of("save-failed"), ErrorKind::Store);
+        assert_eq!(ErrorKind::of("goals-unreadable"), ErrorKind::Store);
+        assert_eq!(ErrorKind::of("invalid-due"), ErrorKind::InvalidArgument);
+        assert_eq!(ErrorKind::of("something-else"), ErrorKind::Failed);
+
+        // Any error reported makes the command exit with status 1
+        report_error(ErrorKind::Failed, "Something went wrong", None);
+        assert!(FAILED.load(Ordering::Relaxed));
+    }
 }
@@ -0,0 +1,169 @@
+//! Working offline with a remote store.
+//!
+//! Every time the remote store (see the `remote` module) is read or written, a copy of it is
+//! kept in `remote.cache`, with its version in `remote.version`. When the server cannot be
+//! reached, commands read that copy instead of failing, and changes are saved to
+//! `remote.pending` rather than pushed. Later commands keep working on the pending store until
+//! `sync flush` sends it to the server. The flush only goes ahead if the remote store is still
+//! the version the offline changes were made to; otherwise the pending store is handed to
+//! `sync conflicts` to be merged field by field (see the `conflict` module).
+
+use crate::{conflict, remote};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const CACHE_FILE: &str = "remote.cache";
+const VERSION_FILE: &str = "remote.version";
+const PENDING_FILE: &str = "remote.pending";
+
+/// Set when the remote store could not be read, so changes must not be pushed either.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// The data directory the files are kept in, which commands run in.
+fn here() -> &'static Path {
+    Path::new("")
+}
+
+/// Keeps a copy of the remote store as last read or written, with its version.
+pub fn remember(contents: &[u8], version: Option<String>) -> Result<(), Box<dyn Error>> {
+    remember_in(here(), contents, version)
+}
+
+fn remember_in(dir: &Path, contents: &[u8], version: Option<String>) -> Result<(), Box<dyn Error>> {
+    fs::write(dir.join(CACHE_FILE), contents)?;
+    fs::write(dir.join(VERSION_FILE), version.unwrap_or_default())?;
+    Ok(())
+}
+
+/// The copy of the remote store to work on while it cannot be reached, if there is one. From
+/// then on, changes are queued.
+pub fn go_offline() -> Option<Vec<u8>> {
+    go_offline_in(here())
+}
+
+fn go_offline_in(dir: &Path) -> Option<Vec<u8>> {
+    let cached = fs::read(dir.join(CACHE_FILE)).ok()?;
+    OFFLINE.store(true, Ordering::Relaxed);
+    Some(cached)
+}
+
+/// Tells whether changes are to be queued rather than pushed: the remote store could not be
+/// read, or changes are queued already.
+pub fn is_queueing() -> bool {
+    is_queueing_in(here())
+}
+
+fn is_queueing_in(dir: &Path) -> bool {
+    OFFLINE.load(Ordering::Relaxed) || dir.join(PENDING_FILE).exists()
+}
+
+/// Queues the store with the changes made offline, replacing what was queued before.
+pub fn queue(contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    queue_in(here(), contents)
+}
+
+fn queue_in(dir: &Path, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    fs::write(dir.join(PENDING_FILE), contents)?;
+    Ok(())
+}
+
+/// The store with the changes made offline, if any are queued.
+pub fn pending() -> Option<Vec<u8>> {
+    pending_in(here())
+}
+
+fn pending_in(dir: &Path) -> Option<Vec<u8>> {
+    fs::read(dir.join(PENDING_FILE)).ok()
+}
+
+/// The version of the remote store the queued changes were made to.
+fn base_version_in(dir: &Path) -> Option<String> {
+    fs::read_to_string(dir.join(VERSION_FILE)).ok()
+}
+
+/// Sends the queued changes with `push`, but only if the remote store is still the version they
+/// were made to; otherwise they are handed to `sync conflicts`. Either way they are no longer
+/// queued. Returns whether they were sent, or `None` if nothing was queued.
+pub fn flush(
+    push: impl FnOnce(&[u8]) -> Result<(), Box<dyn Error>>,
+) -> Result<Option<bool>, Box<dyn Error>> {
+    flush_in(here(), push)
+}
+
+fn flush_in(
+    dir: &Path,
+    push: impl FnOnce(&[u8]) -> Result<(), Box<dyn Error>>,
+) -> Result<Option<bool>, Box<dyn Error>> {
+    let Some(pending) = pending_in(dir) else {
+        return Ok(None);
+    };
+    remote::assume_version(base_version_in(dir));
+    let sent = match push(&pending) {
+        Ok(()) => {
+            remember_in(dir, &pending, remote::version())?;
+            true
+        }
+        Err(err) if err.is::<remote::Changed>() => {
+            fs::write(dir.join(conflict::CONFLICT_FILE), &pending)?;
+            false
+        }
+        Err(err) => return Err(err),
+    };
+    // The queued changes were sent or handed to `sync conflicts`
+    fs::remove_file(dir.join(PENDING_FILE))?;
+    Ok(Some(sent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn test_offline_changes() {
+        // A fake server at a version, refusing changes made to another
+        let server = |version: &'static str| {
+            move |contents: &[u8]| -> Result<(), Box<dyn Error>> {
+                if remote::version().as_deref() != Some(version) {
+                    return Err(remote::Changed.into());
+                }
+                remote::assume_version(Some(format!("{} + {}", version, contents.len())));
+                Ok(())
+            }
+        };
+        let dir = env::temp_dir().join(format!("offline-test-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        remember_in(&dir, b"[]", Some(String::from("v1"))).unwrap();
+        assert_eq!(go_offline_in(&dir).unwrap(), b"[]");
+        assert!(is_queueing_in(&dir));
+        // Only the process that went offline stays so; a later one queues because of the pending file
+        OFFLINE.store(false, Ordering::Relaxed);
+        queue_in(&dir, b"[{\"title\":\"Offline\"}]").unwrap();
+        assert!(is_queueing_in(&dir));
+        assert_eq!(base_version_in(&dir).as_deref(), Some("v1"));
+
+        // Someone saved the store in the meantime
+        assert_eq!(flush_in(&dir, server("v2")).unwrap(), Some(false));
+        assert_eq!(
+            fs::read(dir.join(conflict::CONFLICT_FILE)).unwrap(),
+            b"[{\"title\":\"Offline\"}]"
+        );
+        assert!(pending_in(&dir).is_none());
+        assert!(!is_queueing_in(&dir));
+        assert_eq!(flush_in(&dir, server("v1")).unwrap(), None);
+
+        queue_in(&dir, b"[{\"title\":\"Later\"}]").unwrap();
+        assert_eq!(flush_in(&dir, server("v1")).unwrap(), Some(true));
+        assert_eq!(
+            fs::read(dir.join(CACHE_FILE)).unwrap(),
+            b"[{\"title\":\"Later\"}]"
+        );
+        assert_eq!(base_version_in(&dir).as_deref(), Some("v1 + 19"));
+        assert!(pending_in(&dir).is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+//! OPML outlines.
+//!
+//! `export --format opml` writes the tasks as an outline for outliners like Workflowy or
+//! OmniOutliner: one top-level node per project, with its tasks as children. Descriptions go in
+//! the `_note` attribute and finished tasks are marked `_complete`, as those tools expect, while
+//! the priority, status, due date and tags get attributes of their own, so an exported outline
+//! imports again unchanged. The tasks a task depends on are nested below it where they can be,
+//! that is once and within its project; any others are listed in its `depends` attribute, one
+//! title per line.
+//!
+//! `import --format opml <file>` reads an outline back. Outliners nest nodes freely, so nodes
+//! below a task become tasks of the same project which that task depends on.
+
+use crate::feed::escape;
+use crate::format::Formatter;
+use crate::query::Query;
+use crate::{is_done, Task};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::error::Error;
+
+/// Renders the tasks chosen by a query as an OPML outline.
+pub fn export(tasks: &[Task], query: &Query) -> String {
+    let mut projects: Vec<&str> = Vec::new();
+    for task in tasks.iter().filter(|task| query.matches(task)) {
+        if !projects.contains(&task.project.as_str()) {
+            projects.push(&task.project);
+        }
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    out.push_str("<opml version=\"2.0\">\n");
+    out.push_str("  <head>\n    <title>Tasks</title>\n  </head>\n");
+    out.push_str("  <body>\n");
+    for project in projects {
+        out.push_str(&format!("    <outline text=\"{}\">\n", escape(project)));
+        let tasks: Vec<&Task> = tasks
+            .iter()
+            .filter(|task| task.project == project && query.matches(task))
+            .collect();
+        let parents = parents(&tasks);
+        for index in (0..tasks.len()).filter(|&index| parents[index].is_none()) {
+            write_task(&mut out, &tasks, &parents, index, 3);
+        }
+        out.push_str("    </outline>\n");
+    }
+    out.push_str("  </body>\n</opml>\n");
+    out
+}
+
+/// Chooses which task of a project each task is nested below: the first that depends on it,
+/// unless that would nest a task below itself.
+fn parents(tasks: &[&Task]) -> Vec<Option<usize>> {
+    let mut parents: Vec<Option<usize>> = vec![None; tasks.len()];
+    for (index, task) in tasks.iter().enumerate() {
+        for dep in &task.depends_on {
+            let Some(child) = tasks.iter().position(|task| task.title == *dep) else {
+                continue;
+            };
+            if parents[child].is_some() {
+                continue;
+            }
+            let mut ancestor = Some(index);
+            while let Some(current) = ancestor.filter(|&current| current != child) {
+                ancestor = parents[current];
+            }
+            if ancestor.is_none() {
+                parents[child] = Some(index);
+            }
+        }
+    }
+    parents
+}
+
+/// Writes the node of a task, with the tasks nested below it.
+fn write_task(
+    out: &mut String,
+    tasks: &[&Task],
+    parents: &[Option<usize>],
+    index: usize,
+    depth: usize,
+) {
+    let task = tasks[index];
+    let children: Vec<usize> = (0..tasks.len())
+        .filter(|&child| parents[child] == Some(index))
+        .collect();
+    let mut attributes = vec![("text", task.title.clone())];
+    if !task.description.is_empty() {
+        attributes.push(("_note", task.description.clone()));
+    }
+    if is_done(&task.status) {
+        attributes.push(("_complete", String::from("true")));
+    }
+    attributes.push(("priority", task.priority.to_string()));
+    attributes.push(("status", task.status.clone()));
+    if let Some(due_at) = task.due_at {
+        attributes.push(("due", due_at.to_rfc3339()));
+    } else if let Some(due) = task.due {
+        attributes.push(("due", due.format("%Y-%m-%d").to_string()));
+    }
+    if !task.tags.is_empty() {
+        attributes.push(("tags", task.tags.join(",")));
+    }
+    let nested: Vec<&str> = children
+        .iter()
+        .map(|&child| tasks[child].title.as_str())
+        .collect();
+    let depends: Vec<&str> = task
+        .depends_on
+        .iter()
+        .map(String::as_str)
+        .filter(|dep| !nested.contains(dep))
+        .collect();
+    if !depends.is_empty() {
+        attributes.push(("depends", depends.join("\n")));
+    }
+    let attributes: Vec<String> = attributes
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_attribute(value)))
+        .collect();
+    let indent = "  ".repeat(depth);
+    if children.is_empty() {
+        out.push_str(&format!("{}<outline {}/>\n", indent, attributes.join(" ")));
+        return;
+    }
+    out.push_str(&format!("{}<outline {}>\n", indent, attributes.join(" ")));
+    for child in children {
+        write_task(out, tasks, parents, child, depth + 1);
+    }
+    out.push_str(&format!("{}</outline>\n", indent));
+}
+
+/// Escapes text for an attribute, keeping line breaks, which XML turns into spaces otherwise.
+fn escape_attribute(text: &str) -> String {
+    escape(text)
+        .replace('\r', "&#13;")
+        .replace('\n', "&#10;")
+        .replace('\t', "&#9;")
+}
+
+/// Reads the tasks from an OPML outline, taking top-level nodes as projects.
+pub fn import(source: &str, fmt: &Formatter) -> Result<Vec<Task>, Box<dyn Error>> {
+    let document = roxmltree::Document::parse(source)?;
+    let body = document
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name("body"))
+        .ok_or("the outline has no body")?;
+
+    let mut tasks = Vec::new();
+    for project in outlines(body) {
+        let name = project.attribute("text").unwrap_or_default();
+        for node in outlines(project) {
+            import_task(node, name, fmt, &mut tasks)?;
+        }
+    }
+    Ok(tasks)
+}
+
+/// Adds the task of an outline node and, before it, the tasks nested below it.
+fn import_task(
+    node: roxmltree::Node,
+    project: &str,
+    fmt: &Formatter,
+    tasks: &mut Vec<Task>,
+) -> Result<(), Box<dyn Error>> {
+    let title = node.attribute("text").unwrap_or_default().trim();
+    if title.is_empty() {
+        return Ok(());
+    }
+    let mut task = Task {
+        title: title.to_string(),
+        description: node.attribute("_note").unwrap_or_default().to_string(),
+        priority: node.attribute("priority").map_or(Ok(1), str::parse)?,
+        status: String::from("Todo"),
+        project: project.to_string(),
+        due: node
+            .attribute("due")
+            .filter(|due| due.len() == 10)
+            .map(|due| NaiveDate::parse_from_str(due, "%Y-%m-%d"))
+            .transpose()?,
+        tags: node
+            .attribute("tags")
+            .map(|tags| tags.split(',').map(|tag| tag.trim().to_string()).collect())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    // Tasks due at a time carry the instant, and the date it falls on here
+    if let Some(due) = node.attribute("due").filter(|due| due.len() > 10) {
+        let due_at = DateTime::parse_from_rfc3339(due)?.with_timezone(&Utc);
+        task.due = Some(fmt.local_date(due_at));
+        task.due_at = Some(due_at);
+    }
+    if let Some(status) = node.attribute("status") {
+        task.status = status.to_string();
+    } else if node.attribute("_complete") == Some("true") {
+        task.status = String::from("Done");
+    }
+
+    for child in outlines(node) {
+        if let Some(title) = child
+            .attribute("text")
+            .filter(|title| !title.trim().is_empty())
+        {
+            task.depends_on.push(title.trim().to_string());
+        }
+        import_task(child, project, fmt, tasks)?;
+    }
+    if let Some(depends) = node.attribute("depends") {
+        let depends = depends.lines().map(str::trim).filter(|dep| !dep.is_empty());
+        task.depends_on.extend(depends.map(str::to_string));
+    }
+    tasks.push(task);
+    Ok(())
+}
+
+/// The `outline` elements directly below a node.
+fn outlines<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    node.children()
+        .filter(|child| child.has_tag_name("outline"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::DisplayConfig;
+
+    fn formatter(timezone: &str) -> Formatter {
+        Formatter::new(&DisplayConfig {
+            timezone: Some(timezone.to_string()),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tasks = vec![
+            Task {
+                title: String::from("Design & plan"),
+                description: String::from("Sketch \"v2\"\n\n- header\n- footer"),
+                priority: 2,
+                status: String::from("Done"),
+                project: String::from("Website"),
+                due: NaiveDate::from_ymd_opt(2024, 7, 1),
+                tags: vec![String::from("ux"), String::from("web")],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Build"),
+                priority: 1,
+                status: String::from("Blocked"),
+                project: String::from("Website"),
+                depends_on: vec![String::from("Design & plan"), String::from("Backups")],
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Backups"),
+                priority: 1,
+                status: String::from("Todo"),
+                project: String::from("Ops"),
+                ..Default::default()
+            },
+        ];
+        let outline = export(&tasks, &Query::default());
+        assert!(outline.contains("<outline text=\"Website\">"));
+        assert!(outline.contains("_note=\"Sketch &quot;v2&quot;&#10;&#10;- header&#10;- footer\""));
+        // Dependencies in the project are nested, others named
+        assert!(outline.contains("<outline text=\"Build\" priority=\"1\" status=\"Blocked\" depends=\"Backups\">\n        <outline text=\"Design &amp; plan\""));
+        assert_eq!(import(&outline, &formatter("UTC")).unwrap(), tasks);
+        let ops = Query {
+            project: Some(String::from("Ops")),
+            ..Default::default()
+        };
+        assert!(!export(&tasks, &ops).contains("Website"));
+    }
+
+    #[test]
+    fn test_import_nested_outline() {
+        let outline = r#"<opml version="2.0"><head/><body>
+            <outline text="Move">
+              <outline text="Pack">
+                <outline text="Buy boxes" _complete="true" due="2024-07-01T23:30:00Z"/>
+                <outline text=""/>
+              </outline>
+            </outline>
+        </body></opml>"#;
+        let tasks = import(outline, &formatter("Europe/Berlin")).unwrap();
+        let titles: Vec<&str> = tasks.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles, vec!["Buy boxes", "Pack"]);
+        assert_eq!(tasks[0].status, "Done");
+        // A task due at a time is due on the day that time falls on here
+        assert_eq!(tasks[0].due, NaiveDate::from_ymd_opt(2024, 7, 2));
+        assert_eq!(tasks[1].depends_on, vec!["Buy boxes"]);
+        assert!(tasks.iter().all(|task| task.project == "Move"));
+        assert!(import("<opml/>", &formatter("UTC")).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Places of contexts, exported for phones.
+//!
+//! The `places` section of `config.json` says where the contexts of tasks are, such as the
+//! supermarket for `@errands`. `export --format contexts` writes the tasks by context, with the
+//! places of each, and `export --format geojson` writes the places as GeoJSON points with the
+//! tasks of their context, so that an iOS Shortcut or a Tasker profile can bring up the errands
+//! when the phone is near the store.
+//!
+//! Both formats are part of the interface and keep this schema; fields are only ever added, and
+//! `version` goes up should any change meaning. A task is an object with `title`, `slug`,
+//! `project`, `priority`, `status` and, if it has one, `due` (`YYYY-MM-DD`, or an RFC 3339 time
+//! for tasks due at a time). A place is an object with `name`, `latitude`, `longitude` and
+//! `radius` in metres.
+//!
+//! - `contexts`: `{ "version": 1, "contexts": { "errands": { "places": [place], "tasks":
+//!   [task] } } }`, with a key for every context of the tasks exported and every context with a
+//!   place, without the `@`.
+//! - `geojson`: a `FeatureCollection` with a `Point` feature for every place, whose properties
+//!   are `context`, `name`, `radius` and `tasks`, plus `version` on the collection.
+
+use crate::query::Query;
+use crate::Task;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// The version of the exported schema.
+const VERSION: u32 = 1;
+
+/// `Place`: Where a context is, such as `{ "name": "Supermarket", "latitude": 52.52,
+/// "longitude": 13.405, "radius": 150 }` under `errands` in the `places` section of `config.json`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Place {
+    #[serde(default)]
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// How near to be, in metres.
+    #[serde(default = "default_radius")]
+    pub radius: f64,
+}
+
+fn default_radius() -> f64 {
+    100.0
+}
+
+/// A task as it is exported.
+fn task_json(task: &Task) -> Value {
+    let mut object = json!({
+        "title": task.title,
+        "slug": task.slug,
+        "project": task.project,
+        "priority": task.priority,
+        "status": task.status,
+    });
+    let due = match (task.due_at, task.due) {
+        (Some(due_at), _) => Some(due_at.to_rfc3339()),
+        (None, Some(due)) => Some(due.format("%Y-%m-%d").to_string()),
+        (None, None) => None,
+    };
+    if let Some(due) = due {
+        object["due"] = Value::String(due);
+    }
+    object
+}
+
+/// The tasks chosen by a query, by context.
+fn tasks_by_context<'a>(tasks: &'a [Task], query: &Query) -> BTreeMap<&'a str, Vec<&'a Task>> {
+    let mut contexts: BTreeMap<&str, Vec<&Task>> = BTreeMap::new();
+    for task in tasks.iter().filter(|task| query.matches(task)) {
+        for context in &task.contexts {
+            contexts.entry(context).or_default().push(task);
+        }
+    }
+    contexts
+}
+
+/// Renders the tasks chosen by a query by context, with the places of each context.
+pub fn contexts(tasks: &[Task], query: &Query, places: &BTreeMap<String, Vec<Place>>) -> String {
+    let mut by_context = tasks_by_context(tasks, query);
+    for context in places.keys() {
+        by_context.entry(context).or_default();
+    }
+    let contexts: Map<String, Value> = by_context
+        .into_iter()
+        .map(|(context, tasks)| {
+            let places = places.get(context).map_or(&[][..], Vec::as_slice);
+            let tasks: Vec<Value> = tasks.into_iter().map(task_json).collect();
+            (
+                context.to_string(),
+                json!({ "places": places, "tasks": tasks }),
+            )
+        })
+        .collect();
+    let out = json!({ "version": VERSION, "contexts": contexts });
+    serde_json::to_string_pretty(&out).unwrap() + "\n"
+}
+
+/// Renders the places as GeoJSON points, each with the tasks of its context chosen by a query.
+pub fn geojson(tasks: &[Task], query: &Query, places: &BTreeMap<String, Vec<Place>>) -> String {
+    let by_context = tasks_by_context(tasks, query);
+    let mut features = Vec::new();
+    for (context, places) in places {
+        let tasks: Vec<Value> = by_context
+            .get(context.as_str())
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|task| task_json(task))
+            .collect();
+        for place in places {
+            features.push(json!({
+                "type": "Feature",
+                // GeoJSON puts the longitude first
+                "geometry": { "type": "Point", "coordinates": [place.longitude, place.latitude] },
+                "properties": {
+                    "context": context,
+                    "name": place.name,
+                    "radius": place.radius,
+                    "tasks": tasks,
+                },
+            }));
+        }
+    }
+    let out = json!({ "type": "FeatureCollection", "version": VERSION, "features": features });
+    serde_json::to_string_pretty(&out).unwrap() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_places() {
+        let task = |title: &str, contexts: &[&str]| Task {
+            title: title.to_string(),
+            project: String::from("Home"),
+            status: String::from("Todo"),
+            contexts: contexts.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("Buy milk", &["errands"]),
+            task("Call plumber", &["phone"]),
+            task("Read book", &[]),
+        ];
+        let places: BTreeMap<String, Vec<Place>> = serde_json::from_str(
+            r#"{ "errands": [{ "name": "Supermarket", "latitude": 52.52, "longitude": 13.405 }],
+                 "office": [{ "latitude": 48.1, "longitude": 11.6, "radius": 50 }] }"#,
+        )
+        .unwrap();
+        let query = Query::default();
+
+        let out: Value = serde_json::from_str(&contexts(&tasks, &query, &places)).unwrap();
+        assert_eq!(out["version"], 1);
+        let contexts = out["contexts"].as_object().unwrap();
+        assert_eq!(
+            contexts.keys().collect::<Vec<_>>(),
+            ["errands", "office", "phone"]
+        );
+        assert_eq!(contexts["errands"]["tasks"][0]["title"], "Buy milk");
+        assert_eq!(contexts["errands"]["places"][0]["radius"], 100.0);
+        assert_eq!(contexts["office"]["tasks"].as_array().unwrap().len(), 0);
+        assert_eq!(contexts["phone"]["places"].as_array().unwrap().len(), 0);
+
+        let out: Value = serde_json::from_str(&geojson(&tasks, &query, &places)).unwrap();
+        assert_eq!(out["type"], "FeatureCollection");
+        let features = out["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(
+            features[0]["geometry"]["coordinates"],
+            json!([13.405, 52.52])
+        );
+        assert_eq!(features[0]["properties"]["context"], "errands");
+        assert_eq!(features[0]["properties"]["tasks"][0]["title"], "Buy milk");
+    }
+}
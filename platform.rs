@@ -0,0 +1,173 @@
+//! Platform differences.
+//!
+//! The tasks, `config.json` and the files next to them are kept in the data directory:
+//!
+//! 1. the directory named by `TASK_MANAGER_DIR`, if it is set;
+//! 2. else the current directory, if it already holds a store or `config.json`;
+//! 3. else the platform's data directory: `%APPDATA%\task-manager` on Windows,
+//!    `~/Library/Application Support/task-manager` on macOS and `$XDG_DATA_HOME/task-manager`
+//!    (by default `~/.local/share/task-manager`) elsewhere.
+//!
+//! Paths given on the command line stay relative to the directory the command was run in.
+//!
+//! On Windows, colors also need escape codes to be turned on in the console first.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The variable naming the data directory.
+pub const DIR_VAR: &str = "TASK_MANAGER_DIR";
+
+/// The files that make a directory a data directory.
+const DATA_FILES: [&str; 7] = [
+    "tasks.json",
+    "tasks.json.gz",
+    "tasks.json.zst",
+    "tasks.msgpack",
+    "tasks.ndjson",
+    "tasks.automerge",
+    "config.json",
+];
+
+/// `Os`: The platforms whose conventions for data directories differ.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Os {
+    Windows,
+    Mac,
+    Unix,
+}
+
+impl Os {
+    /// The platform the program was built for.
+    pub fn current() -> Os {
+        if cfg!(windows) {
+            Os::Windows
+        } else if cfg!(target_os = "macos") {
+            Os::Mac
+        } else {
+            Os::Unix
+        }
+    }
+
+    /// The platform's data directory for the task manager, from the environment.
+    fn data_dir(self, var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+        let absolute = |name: &str| var(name).map(PathBuf::from).filter(|p| p.has_root());
+        let base = match self {
+            Os::Windows => PathBuf::from(var("APPDATA").filter(|dir| !dir.is_empty())?),
+            Os::Mac => absolute("HOME")?
+                .join("Library")
+                .join("Application Support"),
+            Os::Unix => absolute("XDG_DATA_HOME")
+                .or_else(|| Some(absolute("HOME")?.join(".local").join("share")))?,
+        };
+        Some(base.join("task-manager"))
+    }
+}
+
+/// Chooses the data directory, or `None` to stay in the current directory.
+fn choose_data_dir(
+    os: Os,
+    var: impl Fn(&str) -> Option<OsString>,
+    cwd_has_data: bool,
+) -> Option<PathBuf> {
+    if let Some(dir) = var(DIR_VAR).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if cwd_has_data {
+        return None;
+    }
+    os.data_dir(var)
+}
+
+/// Finds the data directory, creating it if need be, and makes it the current directory.
+pub fn enter_data_dir() -> io::Result<()> {
+    let cwd_has_data = DATA_FILES.iter().any(|file| Path::new(file).exists());
+    let Some(dir) = choose_data_dir(Os::current(), |name| env::var_os(name), cwd_has_data) else {
+        return Ok(());
+    };
+    fs::create_dir_all(&dir)?;
+    env::set_current_dir(&dir)
+}
+
+/// Turns on escape codes in the Windows console, telling whether colors can be used.
+#[cfg(windows)]
+pub fn enable_ansi() -> bool {
+    use std::ffi::c_void;
+    use std::os::windows::io::AsRawHandle;
+
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleMode(console: *mut c_void, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: *mut c_void, mode: u32) -> i32;
+    }
+
+    let console = io::stdout().as_raw_handle();
+    let mut mode = 0;
+    // SAFETY: The handle is the process's standard output, and the mode is a plain integer.
+    unsafe {
+        GetConsoleMode(console, &mut mode) != 0
+            && (mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                || SetConsoleMode(console, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0)
+    }
+}
+
+/// Turns on escape codes in the Windows console; other terminals always understand them.
+#[cfg(not(windows))]
+pub fn enable_ansi() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_data_dir() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| OsString::from(value))
+            }
+        };
+        let windows = env(&[("APPDATA", r"C:\Users\ann\AppData\Roaming")]);
+        let home = env(&[("HOME", "/home/ann")]);
+        let xdg = env(&[("HOME", "/home/ann"), ("XDG_DATA_HOME", "/data")]);
+        let chosen = env(&[("HOME", "/home/ann"), (DIR_VAR, "/srv/tasks")]);
+
+        assert_eq!(
+            choose_data_dir(Os::Windows, windows, false),
+            Some(Path::new(r"C:\Users\ann\AppData\Roaming").join("task-manager"))
+        );
+        assert_eq!(
+            choose_data_dir(Os::Mac, home, false),
+            Some(PathBuf::from(
+                "/home/ann/Library/Application Support/task-manager"
+            ))
+        );
+        assert_eq!(
+            choose_data_dir(Os::Unix, home, false),
+            Some(PathBuf::from("/home/ann/.local/share/task-manager"))
+        );
+        assert_eq!(
+            choose_data_dir(Os::Unix, xdg, false),
+            Some(PathBuf::from("/data/task-manager"))
+        );
+        // An existing store in the current directory is kept, unless another is asked for
+        assert_eq!(choose_data_dir(Os::Unix, home, true), None);
+        assert_eq!(
+            choose_data_dir(Os::Unix, chosen, true),
+            Some(PathBuf::from("/srv/tasks"))
+        );
+        // Without a usable home, the current directory is used
+        assert_eq!(choose_data_dir(Os::Windows, home, false), None);
+        assert_eq!(
+            choose_data_dir(Os::Unix, env(&[("HOME", "relative")]), false),
+            None
+        );
+    }
+}
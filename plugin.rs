@@ -0,0 +1,78 @@
+//! External subcommands.
+//!
+//! Like git, any executable called `task-manager-<name>` on the `PATH` can be run as
+//! `task-manager <name>`, so the tool can be extended without changing it. The plugin receives
+//! the remaining arguments as they were given and learns about the store and global flags through
+//! environment variables:
+//!
+//! - `TASK_MANAGER_STORE`: The absolute path of the task store, e.g. `tasks.json.gz` when the JSON
+//!   store is compressed (see the `compress` module). It is not set for a remote store, which is
+//!   not kept locally, and an encrypted store can only be read with its key (see the `crypto`
+//!   module).
+//! - `TASK_MANAGER_STORE_KIND`: `json`, `crdt`, `binary` or `ndjson`.
+//! - `TASK_MANAGER_CONFIG`: The absolute path of `config.json`, which may not exist.
+//! - `TASK_MANAGER_YES`, `TASK_MANAGER_JSON`, `TASK_MANAGER_ASCII` and `TASK_MANAGER_TIMINGS`:
+//!   `1` when `--yes`, `--json`, `--ascii` or `--timings` was given, `0` otherwise.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// The prefix of plugin executables.
+pub const PREFIX: &str = "task-manager-";
+
+/// Runs the plugin for a subcommand in a directory, returning `Ok(None)` when there is no such
+/// plugin.
+pub fn run(
+    name: &str,
+    args: &[&str],
+    vars: &[(&str, String)],
+    dir: &Path,
+) -> Result<Option<ExitStatus>, io::Error> {
+    let status = Command::new(format!("{}{}", PREFIX, name))
+        .args(args)
+        .current_dir(dir)
+        .envs(vars.iter().map(|(key, value)| (*key, value)))
+        .status();
+    match status {
+        Ok(status) => Ok(Some(status)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Lists the names of the plugins found on the `PATH`.
+pub fn discover() -> Vec<String> {
+    let mut names = BTreeSet::new();
+    let Some(path) = env::var_os("PATH") else {
+        return vec![];
+    };
+    for dir in env::split_paths(&path) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if let Some(name) = file_name.strip_prefix(PREFIX) {
+                if is_executable(&entry.path()) {
+                    names.insert(name.trim_end_matches(".exe").to_string());
+                }
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,184 @@
+//! Task queries.
+//!
+//! `list` and every `export` format pick tasks with the same flags: `--project`, `--status`
+//! (`open` for any status but Done), `--tag` and `--context` (repeatable; a task needs all of
+//! them), `--query` to search titles and descriptions, and `--due-from` and `--due-to` for a
+//! range of due dates. For example `export --format opml --project Work --status open --due-to
+//! 2024-07-31` exports the open Work tasks due by the end of July. `--where` adds conditions on
+//! computed fields, such as `--where "urgency>=6"` (see the `computed` module).
+
+use crate::computed::Condition;
+use crate::i18n::t;
+use crate::{is_done, parse_due, Task};
+use chrono::NaiveDate;
+use clap::{Arg, ArgMatches};
+
+/// `Query`: The tasks chosen with the query flags; all tasks when none is given.
+#[derive(Debug, Default)]
+pub struct Query {
+    pub project: Option<String>,
+    pub status: Option<String>,
+    pub tags: Vec<String>,
+    pub contexts: Vec<String>,
+    pub text: Option<String>,
+    pub due_from: Option<NaiveDate>,
+    pub due_to: Option<NaiveDate>,
+    pub conditions: Vec<Condition>,
+    /// The day computed fields such as `age` are counted to.
+    pub today: NaiveDate,
+}
+
+/// The query flags, to add to a subcommand.
+pub fn args<'a, 'b>() -> [Arg<'a, 'b>; 8] {
+    let repeated = |name: &'a str, help: &'b str| {
+        Arg::with_name(name)
+            .long(name)
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help(help)
+    };
+    let single =
+        |name: &'a str, help: &'b str| Arg::with_name(name).long(name).takes_value(true).help(help);
+    [
+        single("project", "Only tasks of this project"),
+        single(
+            "status",
+            "Only tasks with this status, or `open` for those not done",
+        ),
+        repeated("tag", "Only tasks with this tag"),
+        repeated("context", "Only tasks that can be done in this context"),
+        single(
+            "query",
+            "Only tasks whose title or description contains this text",
+        ),
+        single(
+            "due-from",
+            "Only tasks due on or after this date (YYYY-MM-DD)",
+        ),
+        single(
+            "due-to",
+            "Only tasks due on or before this date (YYYY-MM-DD)",
+        ),
+        repeated(
+            "where",
+            "Only tasks meeting this condition on a computed field, e.g. urgency>=6",
+        ),
+    ]
+}
+
+impl Query {
+    /// Reads the query flags of a subcommand, counting computed fields to a day.
+    pub fn from_matches(matches: &ArgMatches, today: NaiveDate) -> Result<Query, String> {
+        let values = |name: &str| -> Vec<String> {
+            matches
+                .values_of(name)
+                .map(|values| values.map(|value| value.trim_start_matches('@').to_string()))
+                .map(Iterator::collect)
+                .unwrap_or_default()
+        };
+        let date = |name: &str| {
+            matches
+                .value_of(name)
+                .map(|date| parse_due(date).map_err(|err| t!(err)))
+                .transpose()
+        };
+        Ok(Query {
+            project: matches.value_of("project").map(String::from),
+            status: matches.value_of("status").map(String::from),
+            tags: values("tag"),
+            contexts: values("context"),
+            text: matches.value_of("query").map(str::to_lowercase),
+            due_from: date("due-from")?,
+            due_to: date("due-to")?,
+            conditions: matches
+                .values_of("where")
+                .into_iter()
+                .flatten()
+                .map(Condition::parse)
+                .collect::<Result<_, _>>()?,
+            today,
+        })
+    }
+
+    /// Tells whether a task is chosen by the query.
+    pub fn matches(&self, task: &Task) -> bool {
+        let status = match self.status.as_deref() {
+            None => true,
+            Some(status) if status.eq_ignore_ascii_case("open") => !is_done(&task.status),
+            Some(status) => task.status.eq_ignore_ascii_case(status),
+        };
+        let text = self.text.as_ref().is_none_or(|text| {
+            task.title.to_lowercase().contains(text)
+                || task.description.to_lowercase().contains(text)
+        });
+        let due = match (self.due_from, self.due_to) {
+            (None, None) => true,
+            (from, to) => task.due.is_some_and(|due| {
+                from.is_none_or(|from| due >= from) && to.is_none_or(|to| due <= to)
+            }),
+        };
+        status
+            && text
+            && due
+            && self.project.as_ref().is_none_or(|p| task.project == *p)
+            && self.tags.iter().all(|tag| task.tags.contains(tag))
+            && self.contexts.iter().all(|c| task.contexts.contains(c))
+            && self.conditions.iter().all(|c| c.holds(task, self.today))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let task = Task {
+            title: String::from("Release notes"),
+            description: String::from("Summarize the changes"),
+            status: String::from("In Progress"),
+            project: String::from("Work"),
+            due: NaiveDate::from_ymd_opt(2024, 7, 12),
+            tags: vec![String::from("docs"), String::from("release")],
+            ..Default::default()
+        };
+        let date = |d: u32| NaiveDate::from_ymd_opt(2024, 7, d);
+        assert!(Query::default().matches(&task));
+        let open_work_in_july = Query {
+            project: Some(String::from("Work")),
+            status: Some(String::from("open")),
+            due_from: date(1),
+            due_to: date(31),
+            ..Default::default()
+        };
+        assert!(open_work_in_july.matches(&task));
+        let query = |query: Query| query.matches(&task);
+        assert!(query(Query {
+            text: Some(String::from("changes")),
+            tags: vec![String::from("release")],
+            ..Default::default()
+        }));
+        assert!(!query(Query {
+            status: Some(String::from("done")),
+            ..Default::default()
+        }));
+        assert!(!query(Query {
+            tags: vec![String::from("docs"), String::from("urgent")],
+            ..Default::default()
+        }));
+        assert!(!query(Query {
+            due_to: date(11),
+            ..Default::default()
+        }));
+        assert!(!query(Query {
+            contexts: vec![String::from("work")],
+            ..Default::default()
+        }));
+        assert!(query(Query {
+            conditions: vec![Condition::parse("days-until-due<=2").unwrap()],
+            today: NaiveDate::from_ymd_opt(2024, 7, 10).unwrap(),
+            ..Default::default()
+        }));
+    }
+}
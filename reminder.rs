@@ -0,0 +1,194 @@
+//! Reminders.
+//!
+//! A task can have any number of reminders, set with `remind`: some time before it is due, as in
+//! `--at "1d before"` or `--at "2h before"`, or at a time of their own, as in `--at "2024-07-01
+//! 09:00"`. Reminders before a task due some time on a day count from the start of that day.
+//! `tick`, which is meant to be run regularly, gives the reminders whose time has come and
+//! notes which time each was given for, so a reminder is given once, and again when the due
+//! date is moved. `reminders` lists the reminders of a task and removes them.
+
+use crate::format::Formatter;
+use crate::i18n::t;
+use crate::{is_done, Task};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// `Reminder`: When to remind of a task.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Reminder {
+    #[serde(flatten)]
+    pub when: When,
+    /// The time the reminder was last given for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent: Option<DateTime<Utc>>,
+}
+
+/// `When`: A time before the task is due, in minutes, or a time of its own.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum When {
+    Before(i64),
+    At(DateTime<Utc>),
+}
+
+/// The units a time before the due date can be given in, in minutes.
+const UNITS: [(&str, i64); 4] = [("w", 7 * 24 * 60), ("d", 24 * 60), ("h", 60), ("m", 1)];
+
+impl Reminder {
+    /// Reads a reminder as given to `--at`: `2h before`, `1 day before` or a local time such as
+    /// `2024-07-01 09:00`.
+    pub fn parse(text: &str, fmt: &Formatter) -> Result<Reminder, &'static str> {
+        let text = text.trim().to_lowercase();
+        let when = match text.strip_suffix("before") {
+            Some(amount) => When::Before(parse_amount(amount.trim()).ok_or("invalid-reminder")?),
+            None => {
+                let local = NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M")
+                    .map_err(|_| "invalid-reminder")?;
+                When::At(fmt.utc(local).ok_or("nonexistent-due-time")?)
+            }
+        };
+        Ok(Reminder { when, sent: None })
+    }
+
+    /// The time to give the reminder, if the task has a due date or it does not depend on one.
+    pub fn time(&self, task: &Task, fmt: &Formatter) -> Option<DateTime<Utc>> {
+        match self.when {
+            When::At(at) => Some(at),
+            When::Before(minutes) => {
+                let due = match task.due_at {
+                    Some(due_at) => due_at,
+                    None => fmt.utc(task.due?.and_hms_opt(0, 0, 0)?)?,
+                };
+                due.checked_sub_signed(Duration::try_minutes(minutes)?)
+            }
+        }
+    }
+
+    /// Describes the reminder as it can be given to `--at`.
+    pub fn describe(&self, fmt: &Formatter) -> String {
+        match self.when {
+            When::At(at) => fmt.datetime(at),
+            When::Before(minutes) => {
+                let (unit, size) = UNITS
+                    .into_iter()
+                    .find(|(_, size)| minutes % size == 0)
+                    .unwrap();
+                t!(
+                    "reminder-before",
+                    amount = format!("{}{}", minutes / size, unit)
+                )
+            }
+        }
+    }
+}
+
+/// Reads an amount of time such as `2h`, `30 min` or `1 day`, in minutes. Amounts too large to
+/// count back from a date are refused.
+fn parse_amount(amount: &str) -> Option<i64> {
+    let split = amount.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = amount.split_at(split);
+    let number: i64 = number.parse().ok()?;
+    let unit = match unit.trim() {
+        "w" | "week" | "weeks" => "w",
+        "d" | "day" | "days" => "d",
+        "h" | "hour" | "hours" => "h",
+        "m" | "min" | "mins" | "minute" | "minutes" => "m",
+        _ => return None,
+    };
+    let (_, size) = UNITS.into_iter().find(|(name, _)| *name == unit)?;
+    let minutes = number.checked_mul(size)?;
+    Duration::try_minutes(minutes)?;
+    Some(minutes)
+}
+
+/// Gives the reminders of open tasks whose time has come and that were not given for that time
+/// yet, and notes them as given. Returns a description of each.
+pub fn give(tasks: &mut [Task], now: DateTime<Utc>, fmt: &Formatter) -> Vec<String> {
+    let mut given = Vec::new();
+    for task in tasks.iter_mut().filter(|task| !is_done(&task.status)) {
+        for index in 0..task.reminders.len() {
+            let Some(time) = task.reminders[index].time(task, fmt) else {
+                continue;
+            };
+            if time > now || task.reminders[index].sent == Some(time) {
+                continue;
+            }
+            task.reminders[index].sent = Some(time);
+            given.push(match (task.due_at, task.due) {
+                (Some(due_at), _) => t!(
+                    "reminder-due",
+                    title = task.title.as_str(),
+                    due = fmt.relative_due_at(due_at, now)
+                ),
+                (None, Some(due)) => t!(
+                    "reminder-due",
+                    title = task.title.as_str(),
+                    due = fmt.relative_due(due, fmt.local_date(now))
+                ),
+                (None, None) => t!("reminder", title = task.title.as_str()),
+            });
+        }
+    }
+    given
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::DisplayConfig;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[test]
+    fn test_reminders() {
+        let fmt = Formatter::new(&DisplayConfig {
+            timezone: Some(String::from("UTC")),
+            ..Default::default()
+        })
+        .unwrap();
+        let parse = |text: &str| Reminder::parse(text, &fmt).map(|reminder| reminder.when);
+        assert_eq!(parse("1d before"), Ok(When::Before(24 * 60)));
+        assert_eq!(parse("2 hours before"), Ok(When::Before(120)));
+        assert_eq!(parse("90m before"), Ok(When::Before(90)));
+        let at = Utc.with_ymd_and_hms(2024, 7, 1, 9, 0, 0).unwrap();
+        assert_eq!(parse("2024-07-01 09:00"), Ok(When::At(at)));
+        assert_eq!(parse("soon"), Err("invalid-reminder"));
+        assert_eq!(parse("2 fortnights before"), Err("invalid-reminder"));
+        assert_eq!(parse("99999999999999999w before"), Err("invalid-reminder"));
+        assert_eq!(parse("9999999999999d before"), Err("invalid-reminder"));
+        assert_eq!(
+            parse("99999999999999999999m before"),
+            Err("invalid-reminder")
+        );
+
+        let mut tasks = vec![Task {
+            title: String::from("Submit report"),
+            status: String::from("Todo"),
+            due: NaiveDate::from_ymd_opt(2024, 7, 3),
+            reminders: vec![
+                Reminder::parse("1d before", &fmt).unwrap(),
+                Reminder::parse("2h before", &fmt).unwrap(),
+            ],
+            ..Default::default()
+        }];
+        let now = Utc.with_ymd_and_hms(2024, 7, 2, 12, 0, 0).unwrap();
+        assert_eq!(give(&mut tasks, now, &fmt).len(), 1);
+        // A reminder is given once for the same due date
+        assert!(give(&mut tasks, now, &fmt).is_empty());
+        let later = Utc.with_ymd_and_hms(2024, 7, 2, 23, 0, 0).unwrap();
+        assert_eq!(give(&mut tasks, later, &fmt).len(), 1);
+        // And again once the due date is moved
+        tasks[0].due = NaiveDate::from_ymd_opt(2024, 7, 2);
+        assert_eq!(give(&mut tasks, later, &fmt).len(), 2);
+        assert_eq!(tasks[0].reminders[1].describe(&fmt), "2h before");
+
+        // Reminders too far before the due date, e.g. edited into the store, are never given
+        tasks[0].reminders = vec![Reminder {
+            when: When::Before(i64::MAX),
+            sent: None,
+        }];
+        assert_eq!(tasks[0].reminders[0].time(&tasks[0], &fmt), None);
+        tasks[0].reminders[0].when = When::Before(Duration::days(100_000_000).num_minutes());
+        assert_eq!(tasks[0].reminders[0].time(&tasks[0], &fmt), None);
+        assert!(give(&mut tasks, later, &fmt).is_empty());
+    }
+}
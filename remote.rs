@@ -0,0 +1,257 @@
+//! Remote stores.
+//!
+//! With `"remote": "ssh://host/path/tasks.json"` in `config.json`, the JSON store is kept on a
+//! server and read and written with `ssh`, so a store living on a machine you shell into can be
+//! used from anywhere. With `"remote": "s3://bucket/path/tasks.json"` it is kept in object
+//! storage instead (see the `s3` module). `ssh://user@host:2222/~/tasks.json` logs in as another user, on another
+//! port, and keeps the store relative to the home directory. Everything else, `config.json`
+//! included, stays local; `ssh` has to log in without asking for a password, e.g. with a key.
+//!
+//! Writes only go ahead when the store is still what was read: when someone else saved in between,
+//! the change is refused rather than overwriting theirs, and can be merged with `sync conflicts`
+//! (see the `conflict` module). Over SSH, writes also hold a lock on the
+//! server, a `<store>.lock` directory. When the server cannot be reached, the store is worked on
+//! offline until `sync flush` (see the `offline` module).
+
+use crate::i18n::t;
+use crate::s3::{self, S3Config, Written};
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// The exit status of the write script when the store is locked.
+const LOCKED: i32 = 75;
+/// The exit status of the write script when the store changed since it was read.
+const CHANGED: i32 = 76;
+/// The exit status of `ssh` itself when it could not connect.
+const SSH_FAILED: i32 = 255;
+
+/// The version of the store as first read, then as last written: its checksum as printed by
+/// `cksum` on an SSH server, or its ETag on S3. Empty while there is no store yet.
+static VERSION: Mutex<Option<String>> = Mutex::new(None);
+
+/// `Changed`: The error of a write refused because the store changed since it was read.
+#[derive(Debug)]
+pub struct Changed;
+
+impl fmt::Display for Changed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&t!("remote-changed"))
+    }
+}
+
+impl Error for Changed {}
+
+/// `Unreachable`: The error of a read or write that did not get through to the server, e.g.
+/// without a network, so the store can be worked on offline (see the `offline` module).
+#[derive(Debug)]
+pub struct Unreachable(pub String);
+
+impl fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&t!("remote-unreachable", error = self.0.as_str()))
+    }
+}
+
+impl Error for Unreachable {}
+
+/// The version of the store as last read or written, if any.
+pub fn version() -> Option<String> {
+    VERSION.lock().unwrap().clone()
+}
+
+/// Takes the store to be at a version, as read earlier, so the next write only goes ahead if it
+/// still is.
+pub fn assume_version(version: Option<String>) {
+    *VERSION.lock().unwrap() = version;
+}
+
+/// `Remote`: Where a remote store is kept.
+#[derive(Debug, PartialEq)]
+pub enum Remote {
+    Ssh(Ssh),
+    S3(s3::Object),
+}
+
+impl Remote {
+    /// Parses a location such as `ssh://host/path/tasks.json` or `s3://bucket/tasks.json`.
+    pub fn parse(url: &str, s3: &S3Config) -> Result<Remote, String> {
+        if url.starts_with("s3://") {
+            return Ok(Remote::S3(s3::Object::parse(url, s3)?));
+        }
+        Ssh::parse(url)
+            .map(Remote::Ssh)
+            .ok_or_else(|| t!("invalid-remote", url = url))
+    }
+
+    /// Reads the store, which is empty when there is none yet.
+    pub fn fetch(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let (contents, version) = match self {
+            Remote::Ssh(ssh) => ssh.fetch()?,
+            Remote::S3(object) => object.get()?,
+        };
+        VERSION.lock().unwrap().get_or_insert(version);
+        Ok(contents)
+    }
+
+    /// Writes the store, unless it changed since it was read.
+    pub fn push(&self, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+        let expected = VERSION.lock().unwrap().clone();
+        let version = match self {
+            Remote::Ssh(ssh) => ssh.push(contents, expected.as_deref())?,
+            Remote::S3(object) => {
+                let etag = expected.as_deref().filter(|etag| !etag.is_empty());
+                match object.put(contents, etag)? {
+                    Written::Done(etag) => etag,
+                    Written::Changed => return Err(Changed.into()),
+                }
+            }
+        };
+        *VERSION.lock().unwrap() = Some(version);
+        Ok(())
+    }
+}
+
+/// `Ssh`: A store on a server reached over SSH.
+#[derive(Debug, PartialEq)]
+pub struct Ssh {
+    /// The host to log in to, possibly with a user, as in `ann@example.com`.
+    host: String,
+    port: Option<u16>,
+    /// The path of the store on the server, relative to the home directory unless absolute.
+    path: String,
+}
+
+impl Ssh {
+    /// Parses a location such as `ssh://user@host:port/path`, a path starting with `/~/` being
+    /// relative to the home directory.
+    fn parse(url: &str) -> Option<Ssh> {
+        let (authority, path) = url.strip_prefix("ssh://")?.split_once('/')?;
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port.parse().ok()?)),
+            None => (authority, None),
+        };
+        let path = match path.strip_prefix("~/") {
+            Some(relative) => relative.to_string(),
+            None => format!("/{}", path),
+        };
+        if host.is_empty() || path.ends_with('/') || path.is_empty() {
+            return None;
+        }
+        Some(Ssh {
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+
+    /// Runs a shell script on the server, feeding it some input.
+    fn ssh(&self, script: &str, input: &[u8]) -> Result<(i32, Vec<u8>), Box<dyn Error>> {
+        let mut command = Command::new("ssh");
+        if let Some(port) = self.port {
+            command.arg("-p").arg(port.to_string());
+        }
+        let mut child = command
+            .arg(&self.host)
+            .arg(script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| t!("remote-ssh-failed", error = err.to_string()))?;
+        child.stdin.take().unwrap().write_all(input)?;
+        let output = child.wait_with_output()?;
+        Ok((output.status.code().unwrap_or(-1), output.stdout))
+    }
+
+    /// Reads the store and its checksum, both empty when there is no store yet.
+    fn fetch(&self) -> Result<(Vec<u8>, String), Box<dyn Error>> {
+        let path = quote(&self.path);
+        let script = format!("if [ -e {path} ]; then cksum < {path}; cat {path}; else echo; fi");
+        let (status, output) = self.ssh(&script, &[])?;
+        if status == SSH_FAILED {
+            return Err(Unreachable(self.host.clone()).into());
+        }
+        if status != 0 {
+            return Err(t!(
+                "remote-read-failed",
+                host = self.host.as_str(),
+                status = status
+            )
+            .into());
+        }
+        let split = output
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap_or(output.len());
+        let checksum = String::from_utf8_lossy(&output[..split]).into_owned();
+        Ok((
+            output.get(split + 1..).unwrap_or_default().to_vec(),
+            checksum,
+        ))
+    }
+
+    /// Writes the store under a lock, unless its checksum is no longer the one expected,
+    /// returning the new checksum.
+    fn push(&self, contents: &[u8], expected: Option<&str>) -> Result<String, Box<dyn Error>> {
+        let path = quote(&self.path);
+        let lock = quote(&format!("{}.lock", self.path));
+        let check = match expected {
+            Some(expected) => format!(
+                "current=$(if [ -e {path} ]; then cksum < {path}; fi); \
+                 [ \"$current\" = {expected} ] || exit {CHANGED};",
+                expected = quote(expected),
+            ),
+            None => String::new(),
+        };
+        let script = format!(
+            "mkdir {lock} 2>/dev/null || exit {LOCKED}; trap {remove} EXIT; {check} \
+             cat > {path}.tmp && mv {path}.tmp {path} && cksum < {path}",
+            remove = quote(&format!("rmdir {}", lock)),
+        );
+        let (status, output) = self.ssh(&script, contents)?;
+        let host = self.host.as_str();
+        match status {
+            0 => Ok(String::from_utf8_lossy(&output).trim_end().to_string()),
+            LOCKED => Err(t!("remote-locked", host = host).into()),
+            CHANGED => Err(Changed.into()),
+            SSH_FAILED => Err(Unreachable(self.host.clone()).into()),
+            status => Err(t!("remote-write-failed", host = host, status = status).into()),
+        }
+    }
+}
+
+/// Quotes a word for the shell.
+fn quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Ssh::parse("ssh://example.com/srv/tasks/tasks.json"),
+            Some(Ssh {
+                host: String::from("example.com"),
+                port: None,
+                path: String::from("/srv/tasks/tasks.json"),
+            })
+        );
+        assert_eq!(
+            Ssh::parse("ssh://ann@example.com:2222/~/tasks.json"),
+            Some(Ssh {
+                host: String::from("ann@example.com"),
+                port: Some(2222),
+                path: String::from("tasks.json"),
+            })
+        );
+        assert_eq!(Ssh::parse("https://example.com/tasks.json"), None);
+        assert_eq!(Ssh::parse("ssh://example.com"), None);
+        assert_eq!(Ssh::parse("ssh://example.com:ssh/tasks.json"), None);
+        assert_eq!(quote("it's"), r"'it'\''s'");
+    }
+}
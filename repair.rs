@@ -0,0 +1,171 @@
+//! Malformed records in the store.
+//!
+//! A task that cannot be read, e.g. after editing `tasks.json` by hand, no longer makes the whole
+//! store unreadable: the JSON and NDJSON stores skip it, reporting its record and line, and read
+//! the other tasks. Since saving would then lose it, the store is not written until `repair` has
+//! moved the skipped records to `tasks.rejected.json`, where they can be fixed and added back.
+//! Like the store, the file is encrypted when the store has a key (see the `crypto` module).
+//! `repair --check` only reports them, failing when there are any, for validating a store in CI.
+
+use crate::{crypto, Task};
+use serde_json::value::RawValue;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fs;
+
+/// The file `repair` moves the records it drops from the store to.
+pub const REJECTED_FILE: &str = "tasks.rejected.json";
+
+/// `Malformed`: A record of the store that is not a valid task.
+#[derive(Debug, PartialEq)]
+pub struct Malformed {
+    /// The number of the record, counting from 1.
+    pub record: usize,
+    pub line: usize,
+    pub error: String,
+    /// The record as it was found in the store.
+    pub source: String,
+}
+
+thread_local! {
+    /// The records skipped while loading the store.
+    static SKIPPED: RefCell<Vec<Malformed>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Reads the tasks of a JSON array, setting aside the records that are not valid tasks.
+pub fn parse_array(contents: &str) -> Result<(Vec<Task>, Vec<Malformed>), serde_json::Error> {
+    let records: Vec<&RawValue> = serde_json::from_str(contents)?;
+    let mut tasks = Vec::new();
+    let mut malformed = Vec::new();
+    for (index, record) in records.into_iter().enumerate() {
+        match serde_json::from_str(record.get()) {
+            Ok(task) => tasks.push(task),
+            Err(err) => {
+                // The record borrows from the contents, so its offset tells its line
+                let offset = record.get().as_ptr() as usize - contents.as_ptr() as usize;
+                let start = contents[..offset].matches('\n').count() + 1;
+                malformed.push(Malformed {
+                    record: index + 1,
+                    line: start + err.line().saturating_sub(1),
+                    error: describe(&err),
+                    source: record.get().to_string(),
+                });
+            }
+        }
+    }
+    Ok((tasks, malformed))
+}
+
+/// Reads the task on a line of an NDJSON store.
+pub fn parse_line(line_number: usize, line: &str) -> Result<Task, Malformed> {
+    serde_json::from_str(line).map_err(|err| Malformed {
+        record: line_number,
+        line: line_number,
+        error: describe(&err),
+        source: line.to_string(),
+    })
+}
+
+/// Describes what is wrong with a record, without the position in it, which is given as the
+/// line in the store instead.
+fn describe(err: &serde_json::Error) -> String {
+    let message = err.to_string();
+    match message.rsplit_once(" at line ") {
+        Some((description, _)) if err.line() > 0 => description.to_string(),
+        _ => message,
+    }
+}
+
+/// Remembers a record skipped while loading, telling whether it was not skipped before, as
+/// when the store is read twice.
+pub fn skip(malformed: Malformed) -> bool {
+    SKIPPED.with(|skipped| {
+        let mut skipped = skipped.borrow_mut();
+        let new = !skipped.contains(&malformed);
+        if new {
+            skipped.push(malformed);
+        }
+        new
+    })
+}
+
+/// Tells whether records were skipped while loading, so the store must not be saved over.
+pub fn has_skipped() -> bool {
+    SKIPPED.with(|skipped| !skipped.borrow().is_empty())
+}
+
+/// Takes the records skipped while loading, allowing the store to be saved again.
+pub fn take_skipped() -> Vec<Malformed> {
+    SKIPPED.with(|skipped| skipped.take())
+}
+
+/// Adds records to the rejected ones, keeping them as they were found where they are still
+/// JSON at all, encrypted with the key of the store if it has one.
+pub fn reject(malformed: &[Malformed], key: Option<&crypto::Key>) -> Result<(), Box<dyn Error>> {
+    let mut rejected = load_rejected(key)?;
+    for record in malformed {
+        let source = serde_json::from_str(&record.source);
+        rejected.push(source.unwrap_or_else(|_| Value::from(record.source.clone())));
+    }
+    save_rejected(&rejected, key)
+}
+
+/// Saves the rejected records again, encrypted with the key of the store, for `key encrypt`.
+pub fn encrypt(key: &crypto::Key) -> Result<(), Box<dyn Error>> {
+    if fs::metadata(REJECTED_FILE).is_ok() {
+        save_rejected(&load_rejected(Some(key))?, Some(key))?;
+    }
+    Ok(())
+}
+
+/// Loads the records rejected so far.
+fn load_rejected(key: Option<&crypto::Key>) -> Result<Vec<Value>, Box<dyn Error>> {
+    let data = fs::read(REJECTED_FILE).unwrap_or_default();
+    let contents = String::from_utf8(crypto::decrypt(data, key)?)?;
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
+
+fn save_rejected(rejected: &[Value], key: Option<&crypto::Key>) -> Result<(), Box<dyn Error>> {
+    let contents = serde_json::to_string_pretty(rejected)?;
+    let data = match key {
+        Some(key) => crypto::encrypt(contents.as_bytes(), key),
+        None => contents.into_bytes(),
+    };
+    fs::write(REJECTED_FILE, data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_array() {
+        let contents = r#"[
+  {"title": "Fine", "description": "", "priority": 1, "status": "Todo", "project": "Work"},
+  {"title": "Broken", "priority": "high"},
+  {"title": "Also fine", "description": "", "priority": 2, "status": "Done", "project": "Work"}
+]"#;
+        let (tasks, malformed) = parse_array(contents).unwrap();
+        let titles: Vec<&str> = tasks.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles, ["Fine", "Also fine"]);
+        assert_eq!(malformed.len(), 1);
+        assert_eq!((malformed[0].record, malformed[0].line), (2, 3));
+        assert_eq!(
+            malformed[0].error,
+            r#"invalid type: string "high", expected u8"#
+        );
+        assert_eq!(
+            malformed[0].source,
+            r#"{"title": "Broken", "priority": "high"}"#
+        );
+
+        // A store that is not a JSON array at all still cannot be read
+        assert!(parse_array("[{").is_err());
+        assert_eq!(parse_line(7, "{oops").unwrap_err().line, 7);
+    }
+}
@@ -0,0 +1,205 @@
+//! Retention of finished tasks.
+//!
+//! The `retention` section of `config.json` clears old tasks out of the store when `tick` runs:
+//! Done tasks are moved to `archive.json` some days after they were completed, and Cancelled
+//! tasks to `trash.json` some days after they were last touched. Both files keep the tasks as the
+//! JSON store does, encrypted with its key if it has one, so nothing is lost, and `tick` reports
+//! every task it moved. They are only written once the store is saved without the tasks, so a
+//! store that cannot be saved does not leave them in both places. Projects can be left alone by
+//! listing them in `exempt_projects`.
+
+use crate::compress::Compression;
+use crate::i18n::t;
+use crate::{crypto, is_done, json_store_bytes, last_activity, Task};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+
+const ARCHIVE_FILE: &str = "archive.json";
+const TRASH_FILE: &str = "trash.json";
+
+/// `RetentionConfig`: The `retention` section of `config.json`.
+///
+/// For example `{ "archive_done_after_days": 30, "trash_cancelled_after_days": 90,
+/// "exempt_projects": ["Personal"] }`. Without a number of days, tasks are kept in the store.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    pub archive_done_after_days: Option<i64>,
+    pub trash_cancelled_after_days: Option<i64>,
+    /// Projects whose tasks are never moved.
+    pub exempt_projects: Vec<String>,
+}
+
+/// `Moved`: Where a task was moved out of the store to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Moved {
+    Archived,
+    Trashed,
+}
+
+/// Returns true when a status marks the task as given up.
+fn is_cancelled(status: &str) -> bool {
+    status.eq_ignore_ascii_case("cancelled") || status.eq_ignore_ascii_case("canceled")
+}
+
+/// Finds the tasks due to leave the store, with where they go and how many days ago they were
+/// finished.
+pub fn due(
+    tasks: &[Task],
+    config: &RetentionConfig,
+    now: DateTime<Utc>,
+) -> Vec<(usize, Moved, i64)> {
+    let mut moved = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        if config.exempt_projects.contains(&task.project) {
+            continue;
+        }
+        let (after_days, finished, to) = if is_done(&task.status) {
+            let finished = task.completed_at.or_else(|| last_activity(task));
+            (config.archive_done_after_days, finished, Moved::Archived)
+        } else if is_cancelled(&task.status) {
+            (
+                config.trash_cancelled_after_days,
+                last_activity(task),
+                Moved::Trashed,
+            )
+        } else {
+            continue;
+        };
+        let (Some(after_days), Some(finished)) = (after_days, finished) else {
+            continue;
+        };
+        let age = (now - finished).num_days();
+        if age >= after_days {
+            moved.push((index, to, age));
+        }
+    }
+    moved
+}
+
+/// `Removed`: The archive and the trash with the tasks moved out of the store added, to be saved
+/// once the store is.
+#[derive(Debug, Default)]
+pub struct Removed {
+    archived: Option<Vec<Task>>,
+    trashed: Option<Vec<Task>>,
+}
+
+impl Removed {
+    /// Saves the archive and the trash where tasks were moved to, encrypted with the key of the
+    /// store if it has one.
+    pub fn save(&self, key: Option<&crypto::Key>) -> Result<(), Box<dyn Error>> {
+        for (file, kept) in [(ARCHIVE_FILE, &self.archived), (TRASH_FILE, &self.trashed)] {
+            if let Some(kept) = kept {
+                fs::write(file, json_store_bytes(kept, Compression::None, key)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Moves the tasks due to leave the store into the archive or the trash, returning a line
+/// describing each move. The tasks still have to be saved, and then what was removed.
+pub fn apply(
+    tasks: &mut Vec<Task>,
+    config: &RetentionConfig,
+    now: DateTime<Utc>,
+    key: Option<&crypto::Key>,
+) -> Result<(Vec<String>, Removed), Box<dyn Error>> {
+    let due = due(tasks, config, now);
+    if due.is_empty() {
+        return Ok((vec![], Removed::default()));
+    }
+    let (mut archived, mut trashed) = (load(ARCHIVE_FILE, key)?, load(TRASH_FILE, key)?);
+    let mut report = Vec::new();
+    for (index, task) in std::mem::take(tasks).into_iter().enumerate() {
+        let Some((_, to, age)) = due.iter().find(|(moved, _, _)| *moved == index) else {
+            tasks.push(task);
+            continue;
+        };
+        let (id, kept) = match to {
+            Moved::Archived => ("retention-archived", &mut archived),
+            Moved::Trashed => ("retention-trashed", &mut trashed),
+        };
+        report.push(t!(id, title = task.title.as_str(), days = *age));
+        kept.push(task);
+    }
+    let moved = |to| due.iter().any(|(_, moved, _)| *moved == to);
+    let removed = Removed {
+        archived: moved(Moved::Archived).then_some(archived),
+        trashed: moved(Moved::Trashed).then_some(trashed),
+    };
+    Ok((report, removed))
+}
+
+/// Saves the archive and the trash again, encrypted with the key of the store, for `key encrypt`.
+/// Returns how many tasks they keep.
+pub fn encrypt(key: &crypto::Key) -> Result<usize, Box<dyn Error>> {
+    let mut count = 0;
+    for file in [ARCHIVE_FILE, TRASH_FILE] {
+        if fs::metadata(file).is_ok() {
+            let kept = load(file, Some(key))?;
+            fs::write(file, json_store_bytes(&kept, Compression::None, Some(key))?)?;
+            count += kept.len();
+        }
+    }
+    Ok(count)
+}
+
+/// Loads the tasks moved to the archive or the trash so far.
+fn load(file: &str, key: Option<&crypto::Key>) -> Result<Vec<Task>, Box<dyn Error>> {
+    let data = fs::read(file).unwrap_or_default();
+    let contents = String::from_utf8(crypto::decrypt(data, key)?)?;
+    if contents.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_due() {
+        let now = Utc::now();
+        let task = |status: &str, project: &str, days_ago: i64| Task {
+            title: format!("{} {}", status, days_ago),
+            status: status.to_string(),
+            project: project.to_string(),
+            updated_at: Some(now - Duration::days(days_ago)),
+            completed_at: is_done(status).then(|| now - Duration::days(days_ago)),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Done", "Work", 40),
+            task("Done", "Work", 10),
+            task("Cancelled", "Work", 100),
+            task("Cancelled", "Work", 40),
+            task("Todo", "Work", 400),
+            task("Done", "Personal", 400),
+        ];
+        let config = RetentionConfig {
+            archive_done_after_days: Some(30),
+            trash_cancelled_after_days: Some(90),
+            exempt_projects: vec![String::from("Personal")],
+        };
+        assert_eq!(
+            due(&tasks, &config, now),
+            [(0, Moved::Archived, 40), (2, Moved::Trashed, 100)]
+        );
+
+        // Without a number of days, nothing is moved
+        assert!(due(&tasks, &RetentionConfig::default(), now).is_empty());
+
+        // Nothing is written until the store is saved
+        let (report, removed) = apply(&mut tasks, &config, now, None).unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(tasks.len(), 4);
+        assert_eq!(removed.archived.unwrap()[0].title, "Done 40");
+        assert_eq!(removed.trashed.unwrap()[0].title, "Cancelled 100");
+    }
+}
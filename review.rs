@@ -0,0 +1,173 @@
+//! Regular reviews.
+//!
+//! Tasks and projects can be looked at again at regular intervals, GTD style, so that nothing
+//! sits untouched until it is forgotten. `update --review-every 2w` sets how often a task is
+//! reviewed, and the `review` section of `config.json` how often each project is. `review` lists
+//! the projects and tasks due for review, and `review mark` records that they were reviewed: in
+//! `last_reviewed` for tasks and in `reviews.json` for projects. A task not reviewed yet is due
+//! its interval after it was added.
+
+use crate::format::Formatter;
+use crate::{is_done, Task};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+
+const REVIEWS_FILE: &str = "reviews.json";
+
+/// The longest interval between reviews, in days: ten years.
+const MAX_INTERVAL: u32 = 3650;
+
+/// `ReviewConfig`: The `review` section of `config.json`.
+///
+/// For example `{ "projects": { "Work": 7, "Home": 14 } }` reviews the Work project every week
+/// and the Home project every other week.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReviewConfig {
+    /// Days between reviews, by project.
+    pub projects: BTreeMap<String, u32>,
+}
+
+/// `Reviews`: When each project was last reviewed.
+pub type Reviews = BTreeMap<String, DateTime<Utc>>;
+
+/// Reads an interval as given to `--review-every`, such as `10`, `10d` or `2w`, in days, of at
+/// most ten years.
+pub fn parse_interval(text: &str) -> Option<u32> {
+    let text = text.trim().to_lowercase();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let days = match unit.trim() {
+        "" | "d" | "day" | "days" => 1,
+        "w" | "week" | "weeks" => 7,
+        _ => return None,
+    };
+    number
+        .parse::<u32>()
+        .ok()
+        .filter(|number| *number > 0)
+        .and_then(|number| number.checked_mul(days))
+        .filter(|days| *days <= MAX_INTERVAL)
+}
+
+/// Whether something last reviewed at a time, or never, is due for review again.
+fn is_due(last: Option<DateTime<Utc>>, every: u32, today: NaiveDate, fmt: &Formatter) -> bool {
+    last.is_none_or(|last| {
+        // An interval past the last date there can be, e.g. edited into the store, never ends
+        let next = fmt
+            .local_date(last)
+            .checked_add_days(Days::new(every.into()));
+        next.is_some_and(|next| next <= today)
+    })
+}
+
+/// The open tasks due for review.
+pub fn due_tasks(tasks: &[Task], today: NaiveDate, fmt: &Formatter) -> Vec<usize> {
+    (0..tasks.len())
+        .filter(|&i| {
+            let task = &tasks[i];
+            let Some(every) = task.review_every else {
+                return false;
+            };
+            let last = task.last_reviewed.or(task.created_at);
+            !is_done(&task.status) && is_due(last, every, today, fmt)
+        })
+        .collect()
+}
+
+/// The projects with open tasks that are due for review, with when they were last reviewed.
+pub fn due_projects<'a>(
+    tasks: &[Task],
+    config: &'a ReviewConfig,
+    reviews: &Reviews,
+    today: NaiveDate,
+    fmt: &Formatter,
+) -> Vec<(&'a str, Option<DateTime<Utc>>)> {
+    config
+        .projects
+        .iter()
+        .filter(|(project, _)| {
+            tasks
+                .iter()
+                .any(|task| task.project == **project && !is_done(&task.status))
+        })
+        .map(|(project, every)| (project.as_str(), reviews.get(project).copied(), *every))
+        .filter(|(_, last, every)| is_due(*last, *every, today, fmt))
+        .map(|(project, last, _)| (project, last))
+        .collect()
+}
+
+/// Loads when the projects were last reviewed.
+pub fn load() -> Result<Reviews, Box<dyn Error>> {
+    let contents = fs::read_to_string(REVIEWS_FILE).unwrap_or_default();
+    if contents.trim().is_empty() {
+        return Ok(Reviews::new());
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Saves when the projects were last reviewed.
+pub fn save(reviews: &Reviews) -> Result<(), Box<dyn Error>> {
+    fs::write(REVIEWS_FILE, serde_json::to_string_pretty(reviews)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_due_for_review() {
+        assert_eq!(parse_interval("10"), Some(10));
+        assert_eq!(parse_interval("3d"), Some(3));
+        assert_eq!(parse_interval("2w"), Some(14));
+        assert_eq!(parse_interval("1 week"), Some(7));
+        assert_eq!(parse_interval("0"), None);
+        assert_eq!(parse_interval("monthly"), None);
+        assert_eq!(parse_interval("520w"), Some(3640));
+        assert_eq!(parse_interval("4000000000d"), None);
+        assert_eq!(parse_interval("1000000000w"), None);
+
+        let fmt = Formatter::default();
+        let at = |day: u32| Utc.with_ymd_and_hms(2024, 7, day, 12, 0, 0).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let task = |title: &str, project: &str, status: &str| Task {
+            title: title.to_string(),
+            project: project.to_string(),
+            status: status.to_string(),
+            review_every: Some(7),
+            created_at: Some(at(1)),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Plan offsite", "Work", "Todo"),
+            task("Renew passport", "Home", "Todo"),
+            task("File taxes", "Home", "Done"),
+            task("Fix roof", "Home", "Todo"),
+        ];
+        tasks[1].last_reviewed = Some(at(10));
+        tasks[3].review_every = None;
+        assert_eq!(due_tasks(&tasks, today, &fmt), [0]);
+        tasks[0].review_every = Some(u32::MAX);
+        assert!(due_tasks(&tasks, today, &fmt).is_empty());
+        tasks[0].review_every = Some(7);
+
+        let config = ReviewConfig {
+            projects: BTreeMap::from([
+                (String::from("Work"), 7),
+                (String::from("Home"), 7),
+                (String::from("Garden"), 7),
+            ]),
+        };
+        let reviews = Reviews::from([(String::from("Work"), at(8))]);
+        // Work was reviewed a week ago; Garden has no open tasks to review
+        let due = due_projects(&tasks, &config, &reviews, today, &fmt);
+        assert_eq!(due, [("Home", None), ("Work", Some(at(8)))]);
+    }
+}
@@ -0,0 +1,97 @@
+//! User scripts.
+//!
+//! `script run <file>` runs a [Rhai](https://rhai.rs) script against the task list, for custom
+//! reports and bulk edits the built-in commands don't cover. The script sees the tasks as an
+//! array of maps called `tasks`, with the same fields as `tasks.json`, and may change, add or
+//! remove entries; `today()` returns the current date as `YYYY-MM-DD`. Scripts cannot reach
+//! files, the network or other programs, and are stopped after a fixed number of operations.
+//!
+//! ```text
+//! for i in 0..tasks.len() {
+//!     if tasks[i].project == "Website" && tasks[i].status == "Todo" {
+//!         tasks[i].priority += 1;
+//!     }
+//! }
+//! print(`${tasks.len()} tasks`);
+//! ```
+
+use crate::Task;
+use chrono::NaiveDate;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::serde::{from_dynamic, to_dynamic};
+use rhai::{Dynamic, Engine, Scope};
+use std::error::Error;
+
+/// The number of operations after which a script is stopped.
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+/// Runs a script over the tasks, returning the task list as the script left it.
+pub fn run(source: &str, tasks: &[Task], today: NaiveDate) -> Result<Vec<Task>, Box<dyn Error>> {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    // Rhai loads `import`ed modules from files by default
+    engine.set_module_resolver(DummyModuleResolver::new());
+    let today = today.format("%Y-%m-%d").to_string();
+    engine.register_fn("today", move || today.clone());
+
+    let mut scope = Scope::new();
+    scope.push("tasks", to_dynamic(tasks)?);
+    engine.run_with_scope(&mut scope, source)?;
+
+    let tasks = scope
+        .get_value::<Dynamic>("tasks")
+        .ok_or("the script removed the `tasks` variable")?;
+    Ok(from_dynamic(&tasks)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let tasks = vec![
+            Task {
+                title: String::from("Task 1"),
+                priority: 1,
+                status: String::from("Todo"),
+                project: String::from("Website"),
+                ..Default::default()
+            },
+            Task {
+                title: String::from("Task 2"),
+                priority: 1,
+                status: String::from("Done"),
+                project: String::from("Website"),
+                ..Default::default()
+            },
+        ];
+        let today = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        let script = r#"
+            for i in 0..tasks.len() {
+                if tasks[i].status == "Todo" {
+                    tasks[i].priority += 2;
+                    tasks[i].due = today();
+                }
+            }
+            tasks.retain(|task| task.status != "Done");
+        "#;
+
+        let result = run(script, &tasks, today).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].priority, 3);
+        assert_eq!(result[0].due, Some(today));
+
+        assert!(run("loop {}", &tasks, today).is_err());
+        assert!(run("tasks = 5;", &tasks, today).is_err());
+        // Scripts cannot load other scripts either
+        let module = std::env::temp_dir().join(format!("script-test-{}", std::process::id()));
+        std::fs::write(module.with_extension("rhai"), "export const answer = 42;").unwrap();
+        let import = format!(
+            "import {:?} as m; if m::answer != 42 {{ throw 1; }}",
+            module
+        );
+        assert!(run(&import, &tasks, today).is_err());
+        std::fs::remove_file(module.with_extension("rhai")).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+//! Secrets in the OS keyring.
+//!
+//! Credentials, such as the keys to the S3 bucket keeping a store (see the `s3` module) or the
+//! password of the mail server sending digests (see the `digest` module), can be kept in the
+//! keyring of the operating system instead of in plain text in `config.json`: `auth login s3` or
+//! `auth login smtp` asks for them and stores them, `auth logout` removes them again. The
+//! keyring is the Keychain on macOS, the Credential Manager on Windows and the kernel keyring on
+//! Linux. Using it needs the default `keyring` feature.
+//!
+//! The kernel keyring lives in memory only, so on Linux the secrets are gone after a restart and
+//! `auth login` has to be run again; `auth login` says so. The Secret Service of the desktop
+//! would keep them, but needs D-Bus, which headless machines and containers often lack.
+
+#[cfg(not(feature = "keyring"))]
+use crate::i18n::t;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+/// The service secrets are filed under in the keyring.
+#[cfg(feature = "keyring")]
+const SERVICE: &str = "task-manager";
+
+/// Whether the keyring forgets the secrets when the machine restarts, as the kernel keyring does.
+pub const CLEARED_ON_RESTART: bool = cfg!(target_os = "linux");
+
+/// `Login`: A service `auth login` can store credentials for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Login {
+    S3,
+    Smtp,
+}
+
+impl Login {
+    pub const NAMES: [&'static str; 2] = ["s3", "smtp"];
+
+    pub fn parse(name: &str) -> Option<Login> {
+        match name {
+            "s3" => Some(Login::S3),
+            "smtp" => Some(Login::Smtp),
+            _ => None,
+        }
+    }
+
+    /// The secrets of the service, as their names in the keyring and the prompts asking for them.
+    pub fn secrets(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Login::S3 => &[
+                ("s3-access-key", "auth-s3-access-key"),
+                ("s3-secret-key", "auth-s3-secret-key"),
+            ],
+            Login::Smtp => &[("smtp-password", "auth-smtp-password")],
+        }
+    }
+}
+
+/// Reads a secret from the keyring, if it is there.
+#[cfg(feature = "keyring")]
+pub fn get(name: &str) -> Option<String> {
+    keyring::Entry::new(SERVICE, name).ok()?.get_password().ok()
+}
+
+/// Stores a secret in the keyring.
+#[cfg(feature = "keyring")]
+pub fn set(name: &str, secret: &str) -> Result<(), Box<dyn Error>> {
+    Ok(keyring::Entry::new(SERVICE, name)?.set_password(secret)?)
+}
+
+/// Removes a secret from the keyring, telling whether it was there.
+#[cfg(feature = "keyring")]
+pub fn delete(name: &str) -> Result<bool, Box<dyn Error>> {
+    match keyring::Entry::new(SERVICE, name)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Stands in for the keyring when built without the `keyring` feature.
+#[cfg(not(feature = "keyring"))]
+pub fn get(_name: &str) -> Option<String> {
+    None
+}
+
+/// Stands in for the keyring when built without the `keyring` feature.
+#[cfg(not(feature = "keyring"))]
+pub fn set(_name: &str, _secret: &str) -> Result<(), Box<dyn Error>> {
+    Err(t!("keyring-unsupported").into())
+}
+
+/// Stands in for the keyring when built without the `keyring` feature.
+#[cfg(not(feature = "keyring"))]
+pub fn delete(_name: &str) -> Result<bool, Box<dyn Error>> {
+    Ok(false)
+}
+
+/// Asks for a secret on the terminal, without showing what is typed where the terminal allows.
+pub fn prompt(question: &str) -> io::Result<String> {
+    print!("{} ", question);
+    io::stdout().flush()?;
+    let _echo = EchoOff::new();
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    println!();
+    Ok(answer.trim().to_string())
+}
+
+/// Turns off the echo of the terminal until dropped.
+struct EchoOff {
+    #[cfg(unix)]
+    saved: Option<libc::termios>,
+}
+
+impl EchoOff {
+    #[cfg(unix)]
+    fn new() -> EchoOff {
+        // SAFETY: tcgetattr only writes a `termios` to the pointer it is given, and tcsetattr
+        // only reads one.
+        unsafe {
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                return EchoOff { saved: None };
+            }
+            let saved = termios;
+            termios.c_lflag &= !libc::ECHO;
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios);
+            EchoOff { saved: Some(saved) }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> EchoOff {
+        EchoOff {}
+    }
+}
+
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(saved) = &self.saved {
+            // SAFETY: The settings were read by tcgetattr.
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::t;
+
+    #[test]
+    fn test_logins() {
+        for name in Login::NAMES {
+            let login = Login::parse(name).unwrap();
+            for (secret, question) in login.secrets() {
+                assert!(secret.starts_with(name));
+                assert_ne!(t!(question), *question, "no prompt for {}", secret);
+            }
+        }
+        assert_eq!(Login::parse("ftp"), None);
+        let names: Vec<&str> = Login::NAMES
+            .iter()
+            .flat_map(|name| Login::parse(name).unwrap().secrets())
+            .map(|(secret, _)| *secret)
+            .collect();
+        assert_eq!(names, ["s3-access-key", "s3-secret-key", "smtp-password"]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Slugs and references to tasks.
+//!
+//! Every task gets a slug made from its title, such as `fix-login-bug` for "Fix: login bug!".
+//! It is kept with the task, so it stays the same when the title changes; when two titles make
+//! the same slug, the later task gets `-2`, `-3` and so on.
+//!
+//! Commands naming a task, like `open`, `update`, `done` or `remove`, accept its exact title or
+//! slug, the start of either, or fragments of the title's words such as `log bug` for "Fix login
+//! bug", as long as only one task matches. A reference with a typo, like `fix lgoin bug`, is
+//! matched by how alike the titles are (see the `dedup` module), but only offered to choose from.
+
+use crate::{dedup, Task};
+
+/// How alike a title must be to a reference, from 0 to 1, to offer it for a mistyped reference.
+const ALIKE: f64 = 0.7;
+
+/// `Found`: What a reference to a task matches.
+#[derive(Debug, PartialEq)]
+pub enum Found {
+    One(usize),
+    None,
+    /// The tasks it could mean, to choose from.
+    Candidates(Vec<usize>),
+}
+
+/// Makes a slug from a title: its letters and digits in lower case, with a dash between words.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for word in title.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.extend(word.chars().flat_map(char::to_lowercase));
+    }
+    if slug.is_empty() {
+        slug.push_str("task");
+    }
+    slug
+}
+
+/// Gives the tasks without a slug one, unique among the tasks.
+pub fn assign(tasks: &mut [Task]) {
+    for index in 0..tasks.len() {
+        if tasks[index].slug.is_some() {
+            continue;
+        }
+        let base = slugify(&tasks[index].title);
+        let taken = |slug: &str| tasks.iter().any(|task| task.slug.as_deref() == Some(slug));
+        let slug = (1..)
+            .map(|n| match n {
+                1 => base.clone(),
+                n => format!("{}-{}", base, n),
+            })
+            .find(|slug| !taken(slug))
+            .unwrap();
+        tasks[index].slug = Some(slug);
+    }
+}
+
+/// Finds the task a reference means: the one with that title or slug, else the only one whose
+/// title or slug starts with it or whose words start with its words, ignoring case. Failing
+/// that, the tasks with titles much like it are candidates.
+pub fn find(tasks: &[Task], reference: &str) -> Found {
+    let exact = tasks
+        .iter()
+        .position(|task| task.title == reference || task.slug.as_deref() == Some(reference));
+    if let Some(index) = exact {
+        return Found::One(index);
+    }
+    let prefix = reference.to_lowercase();
+    let fragments: Vec<&str> = prefix.split(|c: char| !c.is_alphanumeric()).collect();
+    let fragments: Vec<&str> = fragments.into_iter().filter(|f| !f.is_empty()).collect();
+    if fragments.is_empty() {
+        return Found::None;
+    }
+    let starts = |task: &Task| {
+        task.title.to_lowercase().starts_with(&prefix)
+            || task
+                .slug
+                .as_ref()
+                .is_some_and(|slug| slug.starts_with(&prefix))
+    };
+    let has_fragments = |task: &Task| {
+        let title = slugify(&task.title);
+        let words: Vec<&str> = title.split('-').collect();
+        fragments
+            .iter()
+            .all(|fragment| words.iter().any(|word| word.starts_with(fragment)))
+    };
+    for matches in [&starts as &dyn Fn(&Task) -> bool, &has_fragments] {
+        let matching: Vec<usize> = (0..tasks.len()).filter(|&i| matches(&tasks[i])).collect();
+        match matching[..] {
+            [] => continue,
+            [index] => return Found::One(index),
+            _ => return Found::Candidates(matching),
+        }
+    }
+    let mut alike: Vec<(usize, f64)> = tasks
+        .iter()
+        .map(|task| dedup::similarity(&task.title, reference))
+        .enumerate()
+        .filter(|(_, similarity)| *similarity >= ALIKE)
+        .collect();
+    if alike.is_empty() {
+        return Found::None;
+    }
+    alike.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    Found::Candidates(alike.into_iter().map(|(index, _)| index).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugs() {
+        assert_eq!(slugify("Fix: login bug!"), "fix-login-bug");
+        assert_eq!(slugify("Über die Brücke"), "über-die-brücke");
+        assert_eq!(slugify("?!"), "task");
+
+        let task = |title: &str| Task {
+            title: title.to_string(),
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            task("Fix login bug"),
+            task("Fix: login bug"),
+            task("Write docs"),
+        ];
+        tasks[2].slug = Some(String::from("docs"));
+        assign(&mut tasks);
+        let slugs: Vec<&str> = tasks.iter().map(|t| t.slug.as_deref().unwrap()).collect();
+        assert_eq!(slugs, ["fix-login-bug", "fix-login-bug-2", "docs"]);
+
+        assert_eq!(find(&tasks, "Fix: login bug"), Found::One(1));
+        assert_eq!(find(&tasks, "fix-login-bug-2"), Found::One(1));
+        assert_eq!(find(&tasks, "docs"), Found::One(2));
+        assert_eq!(find(&tasks, "write"), Found::One(2));
+        assert_eq!(find(&tasks, "fix"), Found::Candidates(vec![0, 1]));
+        assert_eq!(find(&tasks, "fix-login-bug-"), Found::One(1));
+        assert_eq!(find(&tasks, "wri do"), Found::One(2));
+        assert_eq!(find(&tasks, "log bug"), Found::Candidates(vec![0, 1]));
+        assert_eq!(find(&tasks, "wirte docs"), Found::Candidates(vec![2]));
+        assert_eq!(find(&tasks, "Deploy"), Found::None);
+        assert_eq!(find(&tasks, "?"), Found::None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Tables.
+//!
+//! `list --table` shows the tasks as a table that fits the terminal. The columns come from the
+//! theme (see the `theme` module), and when they do not fit, the least important ones are
+//! dropped: the description first, then tags, project, priority, due date, status and the
+//! number. What still does not fit is made narrower, wrapping the description and shortening
+//! titles. `--columns id,title,due` shows exactly the given columns instead, which are only
+//! made narrower. They can include computed fields, as in `--columns title,urgency,age` (see the
+//! `computed` module).
+
+use crate::computed::Computed;
+use crate::format::Formatter;
+use crate::i18n::t;
+use crate::{slug, Task};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::{self, IsTerminal};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The narrowest a title or description is made to fit the terminal.
+const MIN_FLEXIBLE_WIDTH: usize = 12;
+/// The space between columns.
+const GAP: &str = "  ";
+
+/// `Column`: A column of the task table.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Id,
+    Title,
+    Status,
+    Priority,
+    Project,
+    Due,
+    Tags,
+    Description,
+    /// The revision of the task, to pass to `update --if-revision`. Only shown when asked for.
+    Revision,
+    /// The slug of the task, to refer to it by. Only shown when asked for.
+    Slug,
+    /// Computed fields (see the `computed` module), only shown when asked for.
+    Urgency,
+    Age,
+    #[serde(rename = "days-until-due")]
+    DaysUntilDue,
+    Blocked,
+}
+
+impl Column {
+    pub const ALL: [Column; 8] = [
+        Column::Id,
+        Column::Title,
+        Column::Status,
+        Column::Priority,
+        Column::Project,
+        Column::Due,
+        Column::Tags,
+        Column::Description,
+    ];
+
+    /// The columns only shown when asked for, after those in `ALL`.
+    const ON_REQUEST: [Column; 6] = [
+        Column::Revision,
+        Column::Slug,
+        Column::Urgency,
+        Column::Age,
+        Column::DaysUntilDue,
+        Column::Blocked,
+    ];
+
+    /// Parses a column name as given to `--columns`.
+    pub fn parse(name: &str) -> Option<Column> {
+        serde_json::from_value(serde_json::Value::from(name.trim().to_lowercase())).ok()
+    }
+
+    /// How important the column is; the least important ones are dropped first.
+    fn rank(self) -> usize {
+        match self {
+            Column::Revision
+            | Column::Slug
+            | Column::Urgency
+            | Column::Age
+            | Column::DaysUntilDue
+            | Column::Blocked => 0,
+            Column::Description => 1,
+            Column::Tags => 2,
+            Column::Project => 3,
+            Column::Priority => 4,
+            Column::Due => 5,
+            Column::Status => 6,
+            Column::Id => 7,
+            Column::Title => 8,
+        }
+    }
+
+    fn header(self) -> String {
+        match self {
+            Column::Id => t!("column-id"),
+            Column::Title => t!("column-title"),
+            Column::Status => t!("column-status"),
+            Column::Priority => t!("column-priority"),
+            Column::Project => t!("column-project"),
+            Column::Due => t!("column-due"),
+            Column::Tags => t!("column-tags"),
+            Column::Description => t!("column-description"),
+            Column::Revision => t!("column-revision"),
+            Column::Slug => t!("column-slug"),
+            Column::Urgency => t!("column-urgency"),
+            Column::Age => t!("column-age"),
+            Column::DaysUntilDue => t!("column-days-until-due"),
+            Column::Blocked => t!("column-blocked"),
+        }
+    }
+
+    fn cell(self, index: usize, task: &Task, fmt: &Formatter) -> String {
+        let computed = |field: Computed, decimals: usize| {
+            let value = field.value(task, fmt.today());
+            value.map_or_else(String::new, |value| fmt.number(value, decimals))
+        };
+        match self {
+            Column::Id => (index + 1).to_string(),
+            Column::Title => task.title.clone(),
+            Column::Status => fmt.status_label(&task.status),
+            Column::Priority => fmt.priority_label(task.priority),
+            Column::Project => task.project.clone(),
+            Column::Due => match (task.due_at, task.due) {
+                (Some(due_at), _) => fmt.datetime(due_at),
+                (None, Some(due)) => fmt.date(due),
+                (None, None) => String::new(),
+            },
+            Column::Tags => task.tags.join(", "),
+            Column::Description => task.description.clone(),
+            Column::Revision => task.revision.to_string(),
+            Column::Slug => task
+                .slug
+                .clone()
+                .unwrap_or_else(|| slug::slugify(&task.title)),
+            Column::Urgency => computed(Computed::Urgency, 1),
+            Column::Age => computed(Computed::Age, 0),
+            Column::DaysUntilDue => computed(Computed::DaysUntilDue, 0),
+            Column::Blocked => match Computed::Blocked.value(task, fmt.today()) {
+                Some(blocked) if blocked > 0.0 => fmt.glyph("●", "x").to_string(),
+                _ => String::new(),
+            },
+        }
+    }
+}
+
+/// The width of the terminal, from `COLUMNS` or the terminal itself, or `None` when output does
+/// not go to a terminal.
+pub fn terminal_width() -> Option<usize> {
+    if let Some(width) = env::var("COLUMNS").ok().and_then(|w| w.parse().ok()) {
+        return Some(width);
+    }
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    #[cfg(unix)]
+    {
+        // SAFETY: TIOCGWINSZ only writes a `winsize` to the pointer it is given.
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0
+            && size.ws_col > 0
+        {
+            return Some(usize::from(size.ws_col));
+        }
+    }
+    Some(80)
+}
+
+/// Renders tasks as a table at most `width` columns wide. Unless `fixed`, columns that do not fit
+/// are dropped.
+pub fn render(
+    tasks: &[&Task],
+    columns: &[Column],
+    width: Option<usize>,
+    fixed: bool,
+    fmt: &Formatter,
+) -> String {
+    let mut columns = columns.to_vec();
+    let cells: Vec<Vec<String>> = tasks
+        .iter()
+        .enumerate()
+        .map(|(index, task)| {
+            Column::ALL
+                .iter()
+                .chain(&Column::ON_REQUEST)
+                .map(|column| column.cell(index, task, fmt))
+                .collect()
+        })
+        .collect();
+    let cell = |row: usize, column: Column| &cells[row][column as usize];
+    let natural = |column: Column| {
+        (0..tasks.len())
+            .map(|row| cell(row, column).width())
+            .chain([column.header().width()])
+            .max()
+            .unwrap_or(0)
+    };
+    let total = |widths: &[usize]| widths.iter().sum::<usize>() + GAP.len() * (widths.len() - 1);
+
+    let mut widths: Vec<usize> = columns.iter().map(|column| natural(*column)).collect();
+    if let Some(width) = width {
+        while !fixed && columns.len() > 1 && total(&widths) > width {
+            let (drop, _) = columns
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, column)| column.rank())
+                .unwrap();
+            columns.remove(drop);
+            widths.remove(drop);
+        }
+        // Then make the description, and then the title, narrower
+        for flexible in [Column::Description, Column::Title] {
+            if let Some(i) = columns.iter().position(|column| *column == flexible) {
+                let excess = total(&widths).saturating_sub(width);
+                widths[i] = widths[i]
+                    .saturating_sub(excess)
+                    .max(MIN_FLEXIBLE_WIDTH.min(widths[i]));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let headers: Vec<Vec<String>> = columns
+        .iter()
+        .zip(&widths)
+        .map(|(column, width)| vec![truncate(&column.header(), *width, fmt)])
+        .collect();
+    push_row(&mut out, &headers, &widths, |_, text| text.to_string());
+    for (row, task) in tasks.iter().enumerate() {
+        let lines: Vec<Vec<String>> = columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, width)| match column {
+                Column::Description => wrap(cell(row, *column), *width),
+                _ => vec![truncate(cell(row, *column), *width, fmt)],
+            })
+            .collect();
+        push_row(&mut out, &lines, &widths, |i, text| match columns[i] {
+            Column::Title => fmt.priority(task.priority, text),
+            Column::Status => fmt.paint_status(&task.status, text),
+            _ => text.to_string(),
+        });
+    }
+    out
+}
+
+/// Adds the lines of a row, padding every cell but the last to its column's width.
+fn push_row(
+    out: &mut String,
+    cells: &[Vec<String>],
+    widths: &[usize],
+    paint: impl Fn(usize, &str) -> String,
+) {
+    let height = cells.iter().map(Vec::len).max().unwrap_or(1).max(1);
+    for line in 0..height {
+        let mut text = String::new();
+        for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+            let part = cell.get(line).map_or("", String::as_str);
+            if i + 1 == cells.len() {
+                text.push_str(&paint(i, part));
+            } else {
+                let padding = " ".repeat(width.saturating_sub(part.width()));
+                text.push_str(&paint(i, part));
+                text.push_str(&padding);
+                text.push_str(GAP);
+            }
+        }
+        out.push_str(text.trim_end());
+        out.push('\n');
+    }
+}
+
+/// Shortens text to a width, marking the cut with an ellipsis.
+fn truncate(text: &str, width: usize, fmt: &Formatter) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let ellipsis = fmt.glyph("…", "...");
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + ellipsis.width() > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push_str(ellipsis);
+    out
+}
+
+/// Wraps text at spaces into lines of a width, breaking words longer than a line.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.width() + 1 + word.width() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        for c in word.chars() {
+            if line.width() + c.width().unwrap_or(0) > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let task = Task {
+            title: String::from("Write the release notes"),
+            description: String::from("Collect the changes since the last release"),
+            priority: 3,
+            status: String::from("Todo"),
+            project: String::from("Website"),
+            tags: vec![String::from("docs")],
+            ..Default::default()
+        };
+        let fmt = Formatter::default();
+
+        let wide = render(&[&task], &Column::ALL, None, false, &fmt);
+        let lines: Vec<&str> = wide.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("#  Title"));
+        assert!(lines[1].ends_with("Collect the changes since the last release"));
+
+        // Too narrow: the least important columns are dropped
+        let narrow = render(&[&task], &Column::ALL, Some(45), false, &fmt);
+        assert!(narrow.lines().all(|line| line.width() <= 45));
+        assert!(narrow.contains("Write the release notes") && narrow.contains("Todo"));
+        for dropped in ["Collect", "docs", "Website", "Priority"] {
+            assert!(!narrow.contains(dropped));
+        }
+
+        // Chosen columns are kept, but titles are shortened and descriptions wrap
+        let titles = render(&[&task], &[Column::Id, Column::Title], Some(12), true, &fmt);
+        assert_eq!(titles.lines().nth(1), Some("1  Write the r…"));
+        let columns = [Column::Id, Column::Description];
+        let fixed = render(&[&task], &columns, Some(20), true, &fmt);
+        assert_eq!(
+            fixed.lines().collect::<Vec<_>>(),
+            vec![
+                "#  Description",
+                "1  Collect the",
+                "   changes since the",
+                "   last release"
+            ]
+        );
+
+        assert_eq!(Column::parse(" Due"), Some(Column::Due));
+        assert_eq!(Column::parse("owner"), None);
+        assert_eq!(Column::parse("days-until-due"), Some(Column::DaysUntilDue));
+        assert_eq!(truncate("Release notes", 8, &fmt), "Release…");
+    }
+}
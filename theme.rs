@@ -0,0 +1,238 @@
+//! Color themes.
+//!
+//! How priorities and statuses stand out in task listings, and which columns `list --table`
+//! shows, is set by `theme` in the `display` section of `config.json`: the name of a built-in
+//! theme (`default`, `high-contrast`, `colorblind` or `plain`), or a theme based on one of them
+//! with some styles changed:
+//!
+//! ```text
+//! { "display": { "theme": {
+//!     "base": "colorblind",
+//!     "priorities": ["", "", "cyan", "bold yellow", "bold #D55E00"],
+//!     "statuses": { "Waiting": { "color": "magenta", "glyph": "…", "ascii": "..." } },
+//!     "columns": ["id", "title", "status", "due"]
+//! } } }
+//! ```
+//!
+//! A style is a list of words: `bold`, `dim`, `italic`, `underline`, a color name such as `red`
+//! or `bright-blue`, or a `#rrggbb` color. Colors are only used when writing to a terminal, and
+//! never when `NO_COLOR` is set.
+
+use crate::i18n::t;
+use crate::table::Column;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// `ThemeConfig`: The theme chosen in the display settings.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ThemeConfig {
+    Named(String),
+    Custom(CustomTheme),
+}
+
+/// `CustomTheme`: A built-in theme with some of its styles replaced.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CustomTheme {
+    pub base: Option<String>,
+    pub priorities: Option<Vec<String>>,
+    pub statuses: BTreeMap<String, StatusStyle>,
+    pub columns: Option<Vec<Column>>,
+}
+
+/// `StatusStyle`: How a status is shown.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StatusStyle {
+    pub color: String,
+    pub glyph: String,
+    /// Shown instead of a non-ASCII glyph when the terminal lacks Unicode.
+    pub ascii: String,
+}
+
+/// `Theme`: The styles of priorities and statuses, and the layout of tables.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Styles of priority 1 and up; higher priorities use the last one.
+    priorities: Vec<String>,
+    /// Styles by lowercase status.
+    statuses: BTreeMap<String, StatusStyle>,
+    /// The columns of `list --table`, most important first.
+    columns: Vec<Column>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::builtin("default").unwrap()
+    }
+}
+
+impl Theme {
+    /// Builds the theme chosen in the display settings, checking every style in it.
+    pub fn new(config: Option<&ThemeConfig>) -> Result<Theme, String> {
+        let theme = match config {
+            None => Theme::default(),
+            Some(ThemeConfig::Named(name)) => Theme::named(name)?,
+            Some(ThemeConfig::Custom(custom)) => {
+                let mut theme = Theme::named(custom.base.as_deref().unwrap_or("default"))?;
+                if let Some(priorities) = &custom.priorities {
+                    theme.priorities = priorities.clone();
+                }
+                for (status, style) in &custom.statuses {
+                    theme.statuses.insert(status.to_lowercase(), style.clone());
+                }
+                if let Some(columns) = &custom.columns {
+                    theme.columns = columns.clone();
+                }
+                theme
+            }
+        };
+        let styles = theme.statuses.values().map(|style| &style.color);
+        for style in theme.priorities.iter().chain(styles) {
+            sgr(style).ok_or_else(|| t!("unknown-style", style = style.as_str()))?;
+        }
+        Ok(theme)
+    }
+
+    fn named(name: &str) -> Result<Theme, String> {
+        Theme::builtin(name).ok_or_else(|| t!("unknown-theme", name = name))
+    }
+
+    fn builtin(name: &str) -> Option<Theme> {
+        let (priorities, todo, in_progress, blocked, done) = match name {
+            "default" => (
+                ["", "", "yellow", "bold yellow", "bold red"],
+                "",
+                "yellow",
+                "red",
+                "green",
+            ),
+            "high-contrast" => (
+                [
+                    "bright-white",
+                    "bright-white",
+                    "bold bright-yellow",
+                    "bold bright-yellow",
+                    "bold bright-red",
+                ],
+                "bold bright-white",
+                "bold bright-yellow",
+                "bold bright-red",
+                "bold bright-green",
+            ),
+            // The Okabe-Ito palette, which stays apart under the common kinds of color blindness
+            "colorblind" => (
+                ["", "", "#56B4E9", "#E69F00", "bold #D55E00"],
+                "",
+                "#E69F00",
+                "#D55E00",
+                "#0072B2",
+            ),
+            "plain" => (["", "", "", "", ""], "", "", "", ""),
+            _ => return None,
+        };
+        let status = |color: &str, glyph: &str, ascii: &str| StatusStyle {
+            color: color.to_string(),
+            glyph: glyph.to_string(),
+            ascii: ascii.to_string(),
+        };
+        let statuses = BTreeMap::from([
+            (String::from("todo"), status(todo, "○", "[ ]")),
+            (String::from("in progress"), status(in_progress, "◐", "[~]")),
+            (String::from("blocked"), status(blocked, "⊘", "[/]")),
+            (String::from("done"), status(done, "✔", "[x]")),
+        ]);
+        Some(Theme {
+            priorities: priorities.map(String::from).to_vec(),
+            statuses,
+            columns: Column::ALL.to_vec(),
+        })
+    }
+
+    /// The style of a priority.
+    pub fn priority(&self, priority: u8) -> &str {
+        let index = usize::from(priority.max(1) - 1).min(self.priorities.len().saturating_sub(1));
+        self.priorities.get(index).map_or("", String::as_str)
+    }
+
+    /// The columns of a table.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// The style of a status, if the theme knows it.
+    pub fn status(&self, status: &str) -> Option<&StatusStyle> {
+        self.statuses.get(&status.to_lowercase())
+    }
+}
+
+/// Wraps text in the escape codes of a style.
+pub fn paint(text: &str, style: &str) -> String {
+    match sgr(style) {
+        Some(codes) if !codes.is_empty() => format!("\x1b[{}m{}\x1b[0m", codes, text),
+        _ => text.to_string(),
+    }
+}
+
+/// Translates a style to the parameters of an ANSI escape code, or `None` if it is invalid.
+fn sgr(style: &str) -> Option<String> {
+    let mut codes = Vec::new();
+    for word in style.split_whitespace() {
+        let code = match word {
+            "bold" => String::from("1"),
+            "dim" => String::from("2"),
+            "italic" => String::from("3"),
+            "underline" => String::from("4"),
+            "grey" | "gray" => String::from("90"),
+            _ if word.starts_with('#') && word.len() == 7 => {
+                let channel = |i: usize| u8::from_str_radix(word.get(i..i + 2)?, 16).ok();
+                format!("38;2;{};{};{}", channel(1)?, channel(3)?, channel(5)?)
+            }
+            _ => match word.strip_prefix("bright-") {
+                Some(name) => (90 + COLORS.iter().position(|color| *color == name)?).to_string(),
+                None => (30 + COLORS.iter().position(|color| *color == word)?).to_string(),
+            },
+        };
+        codes.push(code);
+    }
+    Some(codes.join(";"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_themes() {
+        assert_eq!(sgr("bold red").unwrap(), "1;31");
+        assert_eq!(sgr("bright-blue #E69F00").unwrap(), "94;38;2;230;159;0");
+        assert_eq!(sgr("").unwrap(), "");
+        assert!(sgr("#E69F0").is_none());
+        assert!(sgr("purple").is_none());
+        assert_eq!(paint("Task", "red"), "\x1b[31mTask\x1b[0m");
+        assert_eq!(paint("Task", ""), "Task");
+
+        let theme = Theme::new(Some(&ThemeConfig::Named(String::from("colorblind")))).unwrap();
+        assert_eq!(theme.priority(5), "bold #D55E00");
+        assert_eq!(theme.priority(9), "bold #D55E00");
+        assert_eq!(theme.priority(0), "");
+        assert_eq!(theme.status("In Progress").unwrap().glyph, "◐");
+
+        let custom: ThemeConfig = serde_json::from_str(
+            r#"{ "base": "plain", "statuses": { "Waiting": { "color": "magenta", "glyph": "…" } } }"#,
+        )
+        .unwrap();
+        let theme = Theme::new(Some(&custom)).unwrap();
+        assert_eq!(theme.status("waiting").unwrap().color, "magenta");
+        assert_eq!(theme.status("Done").unwrap().color, "");
+
+        assert!(Theme::new(Some(&ThemeConfig::Named(String::from("neon")))).is_err());
+        let invalid: ThemeConfig = serde_json::from_str(r#"{ "priorities": ["pink"] }"#).unwrap();
+        assert!(Theme::new(Some(&invalid)).is_err());
+    }
+}
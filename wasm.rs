@@ -0,0 +1,249 @@
+//! Sandboxed WebAssembly plugins.
+//!
+//! Modules (`.wasm`, or `.wat` text) in the directory set as `wasm_plugins` in `config.json` can
+//! hook into the task manager. Unlike external subcommands (see the `plugin` module) they run
+//! inside wasmtime without any imports, so they cannot touch files or the network, and every
+//! call is limited in fuel and memory.
+//!
+//! Tasks are passed as JSON through the module's linear memory. A module exports `memory` and
+//! `alloc(len: i32) -> i32`, which returns room for the host to write `len` bytes, plus any of
+//! these hooks, each receiving a pointer and length of a JSON task:
+//!
+//! - `on_add(ptr: i32, len: i32) -> i64`: Returns the task to add instead, as JSON, or `0` to
+//!   leave it unchanged.
+//! - `on_list_render(ptr: i32, len: i32) -> i64`: Returns a line of text to show below the task
+//!   in `list`, or `0` for none.
+//! - `filter_<name>(ptr: i32, len: i32) -> i32`: Returns `1` to keep the task in
+//!   `list --filter <name>`, `0` to hide it.
+//!
+//! Strings are returned as `(ptr << 32) | len`.
+
+#[cfg(not(feature = "wasm-plugins"))]
+use crate::i18n::t;
+use crate::Task;
+use std::error::Error;
+#[cfg(feature = "wasm-plugins")]
+use std::fs;
+use std::path::Path;
+
+/// `PluginHost`: The WASM plugins loaded from the plugins directory.
+#[cfg(feature = "wasm-plugins")]
+pub struct PluginHost {
+    engine: wasmtime::Engine,
+    modules: Vec<(String, wasmtime::Module)>,
+}
+
+#[cfg(feature = "wasm-plugins")]
+impl PluginHost {
+    /// The fuel a single hook call may use, roughly the number of instructions it runs.
+    const FUEL: u64 = 10_000_000;
+    /// The most memory a plugin may grow to, in bytes.
+    const MAX_MEMORY: usize = 16 << 20;
+
+    /// Compiles every module in the directory, in file name order.
+    pub fn load(dir: &Path) -> Result<PluginHost, Box<dyn Error>> {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        let engine = wasmtime::Engine::new(&config)?;
+
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == "wasm" || ext == "wat")
+            })
+            .collect();
+        paths.sort();
+
+        let mut modules = Vec::new();
+        for path in paths {
+            let module = wasmtime::Module::from_file(&engine, &path)
+                .map_err(|err| format!("{}: {}", path.display(), err))?;
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            modules.push((name.into_owned(), module));
+        }
+        Ok(PluginHost { engine, modules })
+    }
+
+    /// Lets every plugin with an `on_add` hook change a task before it is added.
+    pub fn on_add(&self, task: &mut Task) -> Result<(), Box<dyn Error>> {
+        for (_, module) in &self.modules {
+            let json = serde_json::to_vec(task)?;
+            if let Some(changed) = self.call_returning(module, "on_add", &json)? {
+                *task = serde_json::from_slice(&changed)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects the extra lines plugins want to show below a task in `list`.
+    pub fn on_list_render(&self, task: &Task) -> Result<Vec<String>, Box<dyn Error>> {
+        let json = serde_json::to_vec(task)?;
+        let mut lines = Vec::new();
+        for (_, module) in &self.modules {
+            if let Some(line) = self.call_returning(module, "on_list_render", &json)? {
+                lines.push(String::from_utf8_lossy(&line).into_owned());
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Runs the custom filter `name`, returning `None` when no plugin provides it.
+    pub fn filter(&self, name: &str, task: &Task) -> Result<Option<bool>, Box<dyn Error>> {
+        let export = format!("filter_{}", name);
+        let json = serde_json::to_vec(task)?;
+        for (_, module) in &self.modules {
+            if module.get_export(&export).is_none() {
+                continue;
+            }
+            let (mut store, instance, ptr, len) = self.instantiate(module, &json)?;
+            let filter = instance.get_typed_func::<(i32, i32), i32>(&mut store, &export)?;
+            return Ok(Some(filter.call(&mut store, (ptr, len))? != 0));
+        }
+        Ok(None)
+    }
+
+    /// Calls a hook returning a packed string, if the module exports it.
+    fn call_returning(
+        &self,
+        module: &wasmtime::Module,
+        hook: &str,
+        input: &[u8],
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        if module.get_export(hook).is_none() {
+            return Ok(None);
+        }
+        let (mut store, instance, ptr, len) = self.instantiate(module, input)?;
+        let func = instance.get_typed_func::<(i32, i32), i64>(&mut store, hook)?;
+        let packed = func.call(&mut store, (ptr, len))? as u64;
+        if packed == 0 {
+            return Ok(None);
+        }
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or("plugin does not export its memory")?;
+        let mut output = vec![0; (packed & 0xffff_ffff) as usize];
+        memory.read(&store, (packed >> 32) as usize, &mut output)?;
+        Ok(Some(output))
+    }
+
+    /// Creates a fresh, limited instance of a module and copies the input into its memory.
+    #[allow(clippy::type_complexity)]
+    fn instantiate(
+        &self,
+        module: &wasmtime::Module,
+        input: &[u8],
+    ) -> Result<
+        (
+            wasmtime::Store<wasmtime::StoreLimits>,
+            wasmtime::Instance,
+            i32,
+            i32,
+        ),
+        Box<dyn Error>,
+    > {
+        let limits = wasmtime::StoreLimitsBuilder::new()
+            .memory_size(Self::MAX_MEMORY)
+            .build();
+        let mut store = wasmtime::Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(Self::FUEL)?;
+
+        let instance = wasmtime::Instance::new(&mut store, module, &[])?;
+        let len = i32::try_from(input.len())?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let ptr = alloc.call(&mut store, len)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or("plugin does not export its memory")?;
+        memory.write(&mut store, ptr as usize, input)?;
+        Ok((store, instance, ptr, len))
+    }
+}
+
+/// `PluginHost`: Stands in for the WASM plugins when built without the `wasm-plugins` feature.
+#[cfg(not(feature = "wasm-plugins"))]
+pub struct PluginHost;
+
+#[cfg(not(feature = "wasm-plugins"))]
+impl PluginHost {
+    pub fn load(_dir: &Path) -> Result<PluginHost, Box<dyn Error>> {
+        Err(t!("wasm-plugins-unsupported").into())
+    }
+
+    pub fn on_add(&self, _task: &mut Task) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    pub fn on_list_render(&self, _task: &Task) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(vec![])
+    }
+
+    pub fn filter(&self, _name: &str, _task: &Task) -> Result<Option<bool>, Box<dyn Error>> {
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, feature = "wasm-plugins"))]
+mod tests {
+    use super::*;
+
+    /// Replaces new tasks with a tagged one, adds a fixed line to `list`, keeps tasks whose JSON
+    /// is longer than 100 bytes and has a hook that never returns.
+    const PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (data (i32.const 0) "{\"title\":\"Hooked\",\"description\":\"\",\"priority\":1,\"status\":\"Todo\",\"project\":\"P\",\"tags\":[\"plugin\"]}")
+          (data (i32.const 512) "from a plugin")
+          (func (export "on_add") (param i32 i32) (result i64)
+            (i64.const 96))
+          (func (export "on_list_render") (param i32 i32) (result i64)
+            (i64.or (i64.shl (i64.const 512) (i64.const 32)) (i64.const 13)))
+          (func (export "filter_busy") (param $ptr i32) (param $len i32) (result i32)
+            (local.get $len)
+            (i32.const 100)
+            (i32.gt_u))
+          (func (export "spin") (param i32 i32) (result i64)
+            (loop $forever (br $forever))
+            (i64.const 0)))
+    "#;
+
+    fn host() -> PluginHost {
+        let dir = std::env::temp_dir().join(format!("wasm-plugins-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("tagger.wat"), PLUGIN).unwrap();
+        let host = PluginHost::load(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        host
+    }
+
+    #[test]
+    fn test_hooks() {
+        let host = host();
+        let mut task = Task {
+            title: String::from("Task 1"),
+            ..Default::default()
+        };
+        assert_eq!(host.on_list_render(&task).unwrap(), vec!["from a plugin"]);
+        assert_eq!(host.filter("busy", &task).unwrap(), Some(false));
+        assert_eq!(host.filter("missing", &task).unwrap(), None);
+
+        host.on_add(&mut task).unwrap();
+        assert_eq!(task.title, "Hooked");
+        assert_eq!(task.tags, vec!["plugin"]);
+    }
+
+    #[test]
+    fn test_runaway_plugin_is_stopped() {
+        let host = host();
+        let (_, module) = &host.modules[0];
+        assert!(host.call_returning(module, "spin", b"{}").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Work-in-progress limits.
+//!
+//! Statuses, and projects, can cap how many tasks may be in them at once in the `wip` section of
+//! `config.json`. A project's limit counts its tasks In Progress. Moving a task into a status or
+//! project that is at its limit, with `add` or `update`, fails unless `--force` is given, or only
+//! warns when `warn_only` is set. `board` marks the columns over their limit.
+
+use crate::i18n::t;
+use crate::{is_in_progress, Task};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// `WipConfig`: The `wip` section of `config.json`.
+///
+/// For example `{ "statuses": { "In Progress": 5 }, "projects": { "Website": 2 } }` allows five
+/// tasks In Progress, at most two of them in the Website project.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WipConfig {
+    pub statuses: BTreeMap<String, usize>,
+    pub projects: BTreeMap<String, usize>,
+    /// Only warn about going over a limit instead of refusing to.
+    pub warn_only: bool,
+}
+
+impl WipConfig {
+    /// The limit of a status, matched ignoring case.
+    pub fn status_limit(&self, status: &str) -> Option<usize> {
+        self.statuses
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(status))
+            .map(|(_, limit)| *limit)
+    }
+
+    /// The limit on the tasks In Progress in a project.
+    pub fn project_limit(&self, project: &str) -> Option<usize> {
+        self.projects.get(project).copied()
+    }
+}
+
+/// Describes the limits the task with the given title would go over by moving into a status and
+/// project. A task already in them breaks no new limit.
+pub fn exceeded(
+    tasks: &[Task],
+    title: &str,
+    status: &str,
+    project: &str,
+    config: &WipConfig,
+) -> Vec<String> {
+    let others: Vec<&Task> = tasks.iter().filter(|task| task.title != title).collect();
+    let current = tasks.iter().find(|task| task.title == title);
+    let mut exceeded = Vec::new();
+
+    let moves_status = current.is_none_or(|task| !task.status.eq_ignore_ascii_case(status));
+    if let Some(limit) = config.status_limit(status).filter(|_| moves_status) {
+        let count = others
+            .iter()
+            .filter(|task| task.status.eq_ignore_ascii_case(status))
+            .count();
+        if count >= limit {
+            exceeded.push(t!("wip-status-exceeded", status = status, limit = limit));
+        }
+    }
+
+    let moves_project =
+        current.is_none_or(|task| !is_in_progress(&task.status) || task.project != project);
+    if let Some(limit) = config.project_limit(project) {
+        let count = others
+            .iter()
+            .filter(|task| task.project == project && is_in_progress(&task.status))
+            .count();
+        if is_in_progress(status) && moves_project && count >= limit {
+            exceeded.push(t!("wip-project-exceeded", project = project, limit = limit));
+        }
+    }
+    exceeded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeded() {
+        let task = |title: &str, project: &str, status: &str| Task {
+            title: String::from(title),
+            project: String::from(project),
+            status: String::from(status),
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("Header", "Website", "In Progress"),
+            task("Footer", "Website", "Todo"),
+            task("Backups", "Ops", "In Progress"),
+        ];
+        let config: WipConfig = serde_json::from_str(
+            r#"{ "statuses": { "in progress": 3 }, "projects": { "Website": 1 } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            exceeded(&tasks, "Footer", "In Progress", "Website", &config).len(),
+            1
+        );
+        assert!(exceeded(&tasks, "Header", "In Progress", "Website", &config).is_empty());
+        assert!(exceeded(&tasks, "Footer", "In Progress", "Ops", &config).is_empty());
+        assert!(exceeded(&tasks, "Footer", "Done", "Website", &config).is_empty());
+        // A new task counts against the status limit as well
+        let mut tasks = tasks;
+        tasks.push(task("Alerts", "Ops", "In Progress"));
+        assert_eq!(
+            exceeded(&tasks, "New", "In Progress", "Ops", &config).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Workspace archives.
+//!
+//! `export --archive workspace.tar.zst` bundles the whole data directory into one zstd-compressed
+//! tar archive: the store, `config.json`, the daily plans, goals, habits, backups and whatever
+//! else is kept there. `import --archive workspace.tar.zst` unpacks it into the data directory of
+//! another machine, replacing the files of the same name, so the workspace is restored as it was.
+//!
+//! The key an encrypted store is read with (see the `crypto` module) is left out, and so are the
+//! S3 keys and the mail password in `config.json`; copy the key separately and set the others
+//! again, or keep them in the keyring. The daily plans, goals, habits and the like are never
+//! encrypted, so the archive still says more about the tasks than an encrypted store does.
+
+use serde_json::Value;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The settings of `config.json` holding credentials, by section.
+const SECRETS: [(&str, &str); 3] = [
+    ("s3", "access_key"),
+    ("s3", "secret_key"),
+    ("email", "password"),
+];
+
+/// `config.json` without the credentials it holds, or `None` if it cannot be read as JSON, so
+/// nothing is left in it that cannot be told apart.
+fn redact(contents: &[u8]) -> Option<Vec<u8>> {
+    let mut config: Value = serde_json::from_slice(contents).ok()?;
+    for (section, name) in SECRETS {
+        if let Some(section) = config.get_mut(section).and_then(Value::as_object_mut) {
+            section.remove(name);
+        }
+    }
+    serde_json::to_vec_pretty(&config).ok()
+}
+
+/// Lists the files below a directory, as paths relative to it, in name order.
+fn files(dir: &Path, prefix: &Path, found: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let relative = prefix.join(entry.file_name());
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            files(&entry.path(), &relative, found)?;
+        } else if kind.is_file() {
+            found.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// Writes the files of a directory to an archive, except those in `skip` and with the credentials
+/// taken out of `config.json`, returning how many were written.
+pub fn export(dir: &Path, archive: &Path, skip: &[PathBuf]) -> Result<usize, Box<dyn Error>> {
+    let mut found = Vec::new();
+    files(dir, Path::new(""), &mut found)?;
+    // The archive itself may be written into the directory
+    let skip: Vec<PathBuf> = skip
+        .iter()
+        .chain([&archive.to_path_buf()])
+        .filter_map(|path| path.canonicalize().ok())
+        .collect();
+    let encoder = zstd::Encoder::new(File::create(archive)?, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+    let mut builder = tar::Builder::new(encoder);
+    let mut count = 0;
+    for relative in found {
+        let path = dir.join(&relative);
+        if path.canonicalize().is_ok_and(|path| skip.contains(&path)) {
+            continue;
+        }
+        if relative == Path::new("config.json") {
+            let Some(redacted) = redact(&fs::read(&path)?) else {
+                continue;
+            };
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata(&fs::metadata(&path)?);
+            header.set_size(redacted.len() as u64);
+            builder.append_data(&mut header, &relative, redacted.as_slice())?;
+        } else {
+            builder.append_path_with_name(&path, &relative)?;
+        }
+        count += 1;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(count)
+}
+
+/// Unpacks an archive into a directory, returning how many files it held. Entries that would
+/// land outside the directory are skipped.
+pub fn import(archive: &Path, dir: &Path) -> Result<usize, Box<dyn Error>> {
+    let decoder = zstd::Decoder::new(File::open(archive)?)?;
+    let mut archive = tar::Archive::new(decoder);
+    let mut count = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_file() && entry.unpack_in(dir)? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_import() {
+        let root = std::env::temp_dir().join(format!("workspace-test-{}", std::process::id()));
+        let (from, to) = (root.join("from"), root.join("to"));
+        fs::create_dir_all(from.join("backups")).unwrap();
+        fs::create_dir_all(&to).unwrap();
+        fs::write(from.join("tasks.json"), "[]").unwrap();
+        let config = r#"{ "s3": { "bucket": "tasks", "secret_key": "hunter2" }, "email": { "password": "hunter2" } }"#;
+        fs::write(from.join("config.json"), config).unwrap();
+        fs::write(from.join("tasks.key"), "secret").unwrap();
+        fs::write(from.join("backups").join("20240701T123000Z.json.gz"), "gz").unwrap();
+        fs::write(to.join("tasks.json"), "[{}]").unwrap();
+
+        // Writing the archive into the directory it bundles leaves it out
+        let archive = from.join("workspace.tar.zst");
+        let count = export(&from, &archive, &[from.join("tasks.key")]).unwrap();
+        assert_eq!(count, 3);
+
+        assert_eq!(import(&archive, &to).unwrap(), 3);
+        assert_eq!(fs::read_to_string(to.join("tasks.json")).unwrap(), "[]");
+        assert!(to.join("backups").join("20240701T123000Z.json.gz").exists());
+        assert!(!to.join("tasks.key").exists());
+        let config = fs::read_to_string(to.join("config.json")).unwrap();
+        assert!(config.contains("\"bucket\": \"tasks\""));
+        assert!(!config.contains("hunter2"));
+        assert!(!to.join("workspace.tar.zst").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}